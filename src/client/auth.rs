@@ -4,9 +4,20 @@
 //! It handles the critical rate limiting that Tandoor imposes on authentication
 //! endpoints (10 requests per day).
 
+use crate::client::credentials::{CredentialProvider, Credentials};
+use crate::client::error::TandoorError;
+use crate::client::token_store::TokenStore;
 use crate::client::types::{AuthRequest, AuthToken};
 use anyhow::Result;
 use reqwest::Client;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+/// Number of logins Tandoor allows per day before throttling the client.
+pub const MAX_LOGINS_PER_DAY: usize = 10;
+
+const LOGIN_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// # Tandoor Authentication Handler
 ///
@@ -16,7 +27,11 @@ use reqwest::Client;
 ///
 /// **IMPORTANT**: Tandoor has very strict rate limiting on authentication endpoints.
 /// Only 10 authentication requests are allowed per day per IP address. This handler
-/// caches tokens to minimize authentication requests.
+/// caches tokens to minimize authentication requests, and counts its own logins so
+/// that automatic re-authentication stops before the budget is used up.
+///
+/// With a [`TokenStore`] attached, issued tokens are also written to disk and reused by
+/// the next process, so a restart does not cost a login while the token is still valid.
 ///
 /// ## Token Format
 ///
@@ -28,7 +43,15 @@ pub struct TandoorAuth {
     /// HTTP client for authentication requests
     client: Client,
     /// Cached authentication token
-    token: Option<String>,
+    token: RwLock<Option<String>>,
+    /// Source of credentials for logins and re-authentication when the token expires
+    credentials: RwLock<Option<Arc<dyn CredentialProvider>>>,
+    /// Times of recent login attempts, used to enforce the daily budget
+    login_attempts: Mutex<VecDeque<Instant>>,
+    /// Serializes logins so concurrent 401s or first requests trigger a single login
+    refresh_lock: tokio::sync::Mutex<()>,
+    /// Optional on-disk cache of issued tokens
+    token_store: RwLock<Option<TokenStore>>,
 }
 
 impl TandoorAuth {
@@ -36,11 +59,194 @@ impl TandoorAuth {
         Self {
             base_url,
             client: Client::new(),
-            token: None,
+            token: RwLock::new(None),
+            credentials: RwLock::new(None),
+            login_attempts: Mutex::new(VecDeque::new()),
+            refresh_lock: tokio::sync::Mutex::new(()),
+            token_store: RwLock::new(None),
+        }
+    }
+
+    /// Persist issued tokens in `store` and reuse cached tokens in [`Self::authenticate`].
+    pub fn set_token_store(&self, store: TokenStore) {
+        *self.token_store.write().unwrap() = Some(store);
+    }
+
+    /// Use `provider` for logins and for re-authentication when the token expires.
+    pub fn set_credential_provider(&self, provider: Arc<dyn CredentialProvider>) {
+        *self.credentials.write().unwrap() = Some(provider);
+    }
+
+    /// Log in with `username` and `password` and remember them for re-authentication.
+    ///
+    /// Replaces any credential provider set before. See [`Self::authenticate_with_provider`].
+    pub async fn authenticate(&self, username: String, password: String) -> Result<()> {
+        self.set_credential_provider(Arc::new(Credentials::new(username, password)));
+        self.authenticate_with_provider().await
+    }
+
+    /// Log in with the credentials from the credential provider.
+    ///
+    /// If a token store is attached and holds a token for this user that Tandoor still
+    /// accepts, that token is used instead of logging in. Fails without contacting
+    /// Tandoor if a login is needed and the daily login budget is already used up.
+    pub async fn authenticate_with_provider(&self) -> Result<()> {
+        let credentials = self.provided_credentials()?;
+
+        if self.restore_cached_token(&credentials.username).await {
+            return Ok(());
+        }
+        Ok(self
+            .login(credentials.username, credentials.password)
+            .await?)
+    }
+
+    /// Log in with the credential provider unless a token is already set.
+    ///
+    /// Runs under the re-authentication lock, so tasks that find the client logged out
+    /// at the same time wait for one login instead of each spending one.
+    pub async fn ensure_authenticated(&self) -> Result<()> {
+        let _guard = self.refresh_lock.lock().await;
+        if self.is_authenticated() {
+            return Ok(());
         }
+        self.authenticate_with_provider().await
+    }
+
+    /// Ask the credential provider for credentials.
+    fn provided_credentials(&self) -> Result<Credentials, TandoorError> {
+        let provider = self.credentials.read().unwrap().clone();
+        let Some(provider) = provider else {
+            return Err(TandoorError::Unauthorized {
+                message: "No credentials are available to authenticate with".to_string(),
+            });
+        };
+        provider
+            .credentials()
+            .map_err(|e| TandoorError::Unauthorized {
+                message: format!("Could not load Tandoor credentials: {e}"),
+            })
     }
 
-    pub async fn authenticate(&mut self, username: String, password: String) -> Result<()> {
+    /// Load the cached token for `username` and keep it if Tandoor still accepts it.
+    async fn restore_cached_token(&self, username: &str) -> bool {
+        let store = self.token_store.read().unwrap().clone();
+        let Some(store) = store else {
+            return false;
+        };
+        let Some(token) = store.load(&self.base_url, username) else {
+            tracing::debug!("No cached token for user {}", username);
+            return false;
+        };
+
+        match self.validate_token(&token).await {
+            Ok(true) => {
+                tracing::info!("Using cached token for user: {}", username);
+                *self.token.write().unwrap() = Some(token);
+                true
+            }
+            Ok(false) => {
+                tracing::info!("Cached token for user {} was rejected", username);
+                if let Err(e) = store.remove(&self.base_url, username) {
+                    tracing::warn!("Failed to remove stale cached token: {}", e);
+                }
+                false
+            }
+            Err(e) => {
+                tracing::warn!("Could not validate cached token: {}", e);
+                false
+            }
+        }
+    }
+
+    /// Check `token` with a cheap authenticated request.
+    ///
+    /// Only a 401 counts as invalid; other failures say nothing about the token.
+    async fn validate_token(&self, token: &str) -> Result<bool> {
+        let url = format!("{}/api/keyword/?page_size=1", self.base_url);
+        tracing::debug!("Validating cached token against: {}", url);
+
+        let response = self
+            .client
+            .get(&url)
+            .bearer_auth(token)
+            .timeout(std::time::Duration::from_secs(10))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to connect to Tandoor API: {e}"))?;
+
+        Ok(response.status() != reqwest::StatusCode::UNAUTHORIZED)
+    }
+
+    /// Re-authenticate with the stored credentials after a token was rejected.
+    ///
+    /// `rejected_token` is the token the failed request was sent with. If another task
+    /// has already replaced it, no new login is performed.
+    pub async fn refresh(&self, rejected_token: Option<&str>) -> Result<(), TandoorError> {
+        let _guard = self.refresh_lock.lock().await;
+
+        let current = self.get_token();
+        if current.is_some() && current.as_deref() != rejected_token {
+            tracing::debug!("Token was already refreshed by another request");
+            return Ok(());
+        }
+
+        if !self.has_credentials() {
+            return Err(TandoorError::Unauthorized {
+                message: "Authentication token was rejected and no credentials are available to re-authenticate".to_string(),
+            });
+        }
+        let credentials = self.provided_credentials()?;
+        tracing::info!(
+            "Re-authenticating user {} after rejected token",
+            credentials.username
+        );
+        self.login(credentials.username, credentials.password).await
+    }
+
+    /// Number of logins still available in the current 24 hour window.
+    pub fn remaining_logins(&self) -> usize {
+        let mut attempts = self.login_attempts.lock().unwrap();
+        Self::prune_attempts(&mut attempts);
+        MAX_LOGINS_PER_DAY.saturating_sub(attempts.len())
+    }
+
+    fn prune_attempts(attempts: &mut VecDeque<Instant>) {
+        while attempts
+            .front()
+            .is_some_and(|attempt| attempt.elapsed() >= LOGIN_WINDOW)
+        {
+            attempts.pop_front();
+        }
+    }
+
+    async fn login(&self, username: String, password: String) -> Result<(), TandoorError> {
+        {
+            let mut attempts = self.login_attempts.lock().unwrap();
+            Self::prune_attempts(&mut attempts);
+            if attempts.len() >= MAX_LOGINS_PER_DAY {
+                let retry_in = attempts
+                    .front()
+                    .map(|oldest| LOGIN_WINDOW.saturating_sub(oldest.elapsed()))
+                    .unwrap_or_default();
+                tracing::error!(
+                    "Login budget exhausted: {} logins in the last 24 hours",
+                    attempts.len()
+                );
+                return Err(TandoorError::RateLimited {
+                    message: format!(
+                        "Tandoor login budget exhausted: {} of {} daily logins already used. \
+                         Set TANDOOR_AUTH_TOKEN or try again in {} minutes.",
+                        attempts.len(),
+                        MAX_LOGINS_PER_DAY,
+                        retry_in.as_secs().div_ceil(60)
+                    ),
+                    retry_after: Some(retry_in.as_secs()),
+                });
+            }
+            attempts.push_back(Instant::now());
+        }
+
         tracing::info!("Attempting authentication for user: {}", username);
 
         let auth_request = AuthRequest {
@@ -60,15 +266,21 @@ impl TandoorAuth {
             .await
             .map_err(|e| {
                 tracing::error!("Network error during authentication: {}", e);
-                anyhow::anyhow!(
-                    "Failed to connect to Tandoor server at {}: {}",
-                    self.base_url,
-                    e
-                )
+                TandoorError::Network {
+                    message: format!(
+                        "Failed to connect to Tandoor server at {}: {}",
+                        self.base_url, e
+                    ),
+                }
             })?;
 
         let status = response.status();
         tracing::debug!("Authentication response status: {}", status);
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
 
         if !status.is_success() {
             let error_body = response
@@ -81,28 +293,49 @@ impl TandoorAuth {
                 error_body
             );
 
-            match status.as_u16() {
-                400 => anyhow::bail!(
-                    "Invalid credentials provided. Please check username and password."
-                ),
-                401 => anyhow::bail!("Authentication failed: Invalid username or password"),
-                403 => anyhow::bail!("Access denied: User account may be disabled"),
-                404 => anyhow::bail!(
-                    "Tandoor API endpoint not found. Check your base URL: {}",
-                    self.base_url
-                ),
-                500..=599 => anyhow::bail!("Tandoor server error ({status}): {error_body}"),
-                _ => anyhow::bail!("Authentication failed with status {status}: {error_body}"),
-            }
+            return Err(match status.as_u16() {
+                400 => TandoorError::Unauthorized {
+                    message: "Invalid credentials provided. Please check username and password."
+                        .to_string(),
+                },
+                401 => TandoorError::Unauthorized {
+                    message: "Authentication failed: Invalid username or password".to_string(),
+                },
+                403 => TandoorError::Forbidden {
+                    message: "Access denied: User account may be disabled".to_string(),
+                },
+                404 => TandoorError::NotFound {
+                    message: format!(
+                        "Tandoor API endpoint not found. Check your base URL: {}",
+                        self.base_url
+                    ),
+                },
+                429 => TandoorError::RateLimited {
+                    message: format!(
+                        "Tandoor login rate limit reached ({MAX_LOGINS_PER_DAY} per day). \
+                         Set TANDOOR_AUTH_TOKEN or wait for the limit to reset."
+                    ),
+                    retry_after,
+                },
+                _ => TandoorError::from_response(status, retry_after, &error_body),
+            });
         }
 
         let auth_token: AuthToken = response.json().await.map_err(|e| {
             tracing::error!("Failed to parse authentication response: {}", e);
-            anyhow::anyhow!("Invalid response from Tandoor server: {e}")
+            TandoorError::Decode {
+                message: format!("Invalid response from Tandoor server: {e}"),
+            }
         })?;
 
-        self.token = Some(auth_token.token.clone());
+        *self.token.write().unwrap() = Some(auth_token.token.clone());
         tracing::info!("Authentication successful for user: {}", username);
+
+        if let Some(store) = self.token_store.read().unwrap().as_ref() {
+            if let Err(e) = store.save(&self.base_url, &username, &auth_token.token) {
+                tracing::warn!("Failed to cache token: {}", e);
+            }
+        }
         tracing::debug!(
             "Received token: {}...",
             &auth_token.token[..auth_token.token.len().min(10)]
@@ -111,15 +344,19 @@ impl TandoorAuth {
         Ok(())
     }
 
-    pub fn get_token(&self) -> Option<&str> {
-        self.token.as_deref()
+    pub fn get_token(&self) -> Option<String> {
+        self.token.read().unwrap().clone()
     }
 
     pub fn is_authenticated(&self) -> bool {
-        self.token.is_some()
+        self.token.read().unwrap().is_some()
+    }
+
+    pub fn has_credentials(&self) -> bool {
+        self.credentials.read().unwrap().is_some()
     }
 
-    pub fn set_token(&mut self, token: String) {
-        self.token = Some(token);
+    pub fn set_token(&self, token: String) {
+        *self.token.write().unwrap() = Some(token);
     }
 }
@@ -1,8 +1,27 @@
 //! HTTP client implementation for the Tandoor API.
 
-use crate::client::{auth::TandoorAuth, types::*};
+use crate::client::{
+    auth::TandoorAuth, credentials::CredentialProvider, error::TandoorError,
+    token_store::TokenStore, types::*,
+};
 use anyhow::Result;
-use reqwest::Client;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use reqwest::header::{AUTHORIZATION, RETRY_AFTER};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::sync::Arc;
+
+/// Page size requested when following every page of a list endpoint.
+const PAGE_SIZE: usize = 100;
+
+/// List endpoints that return a bare array when empty instead of a page.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum PageOrList<T> {
+    Page(PaginatedResponse<T>),
+    List(Vec<T>),
+}
 
 /// # Tandoor HTTP Client
 ///
@@ -18,6 +37,9 @@ use reqwest::Client;
 /// - **Food Search**: Find foods and ingredients in the database
 /// - **Import Support**: Import recipes from URLs
 /// - **Rate Limit Handling**: Works with Tandoor's authentication limits
+/// - **Automatic Re-authentication**: Retries once with a fresh token when a request gets a 401
+/// - **Typed Errors**: Every endpoint fails with a [`TandoorError`] describing what went wrong
+/// - **Pagination**: List endpoints follow every page (see [`TandoorClient::paginate`])
 ///
 /// ## Example
 ///
@@ -25,13 +47,13 @@ use reqwest::Client;
 /// use mcp_tandoor::client::TandoorClient;
 ///
 /// # async fn example() -> anyhow::Result<()> {
-/// let mut client = TandoorClient::new("http://localhost:8080".to_string());
+/// let client = TandoorClient::new("http://localhost:8080".to_string());
 ///
 /// // Authenticate
 /// client.authenticate("username".to_string(), "password".to_string()).await?;
 ///
 /// // Search recipes
-/// let recipes = client.search_recipes(Some("pasta"), Some(10)).await?;
+/// let recipes = client.search_recipes(Some("pasta"), Some(10), None).await?;
 ///
 /// // Get recipe details  
 /// if let Some(recipe) = recipes.results.first() {
@@ -59,10 +81,30 @@ impl TandoorClient {
         }
     }
 
-    pub async fn authenticate(&mut self, username: String, password: String) -> Result<()> {
+    pub async fn authenticate(&self, username: String, password: String) -> Result<()> {
         self.auth.authenticate(username, password).await
     }
 
+    /// Use `provider` for logins and for re-authentication when the token expires.
+    ///
+    /// No login happens until the first request or [`Self::authenticate_with_provider`].
+    pub fn set_credential_provider(&self, provider: Arc<dyn CredentialProvider>) {
+        self.auth.set_credential_provider(provider);
+    }
+
+    /// Log in with the credentials from the provider set with
+    /// [`Self::set_credential_provider`], reusing a cached token when possible.
+    pub async fn authenticate_with_provider(&self) -> Result<()> {
+        self.auth.authenticate_with_provider().await
+    }
+
+    /// Log in with the credential provider unless a token is already set.
+    ///
+    /// Concurrent callers share a single login.
+    pub async fn ensure_authenticated(&self) -> Result<()> {
+        self.auth.ensure_authenticated().await
+    }
+
     pub fn is_authenticated(&self) -> bool {
         self.auth.is_authenticated()
     }
@@ -73,29 +115,249 @@ impl TandoorClient {
             .map(|t| format!("{}...", &t[..t.len().min(10)]))
     }
 
-    pub fn set_token(&mut self, token: String) {
+    pub fn set_token(&self, token: String) {
         self.auth.set_token(token);
     }
 
-    pub fn get_token(&self) -> Option<&str> {
+    /// Cache issued tokens on disk so they can be reused across restarts.
+    ///
+    /// Once set, [`Self::authenticate`] first tries the cached token for the user and
+    /// only logs in if Tandoor rejects it.
+    pub fn set_token_store(&self, store: TokenStore) {
+        self.auth.set_token_store(store);
+    }
+
+    pub fn get_token(&self) -> Option<String> {
         self.auth.get_token()
     }
 
-    fn get_auth_header(&self) -> Result<String> {
-        match self.auth.get_token() {
-            Some(token) => {
-                tracing::trace!(
-                    "Using authentication token: {}...",
-                    &token[..token.len().min(10)]
-                );
-                // Tandoor uses OAuth2 access tokens with Bearer authentication
-                Ok(format!("Bearer {token}"))
+    /// Number of logins still available for automatic re-authentication today.
+    pub fn remaining_logins(&self) -> usize {
+        self.auth.remaining_logins()
+    }
+
+    /// Stream every item of a list endpoint, following `next` links across pages.
+    ///
+    /// `path` is relative to the base URL, e.g. `/api/keyword/?query=pasta`. Pages are
+    /// fetched lazily as the stream is polled, and at most `max_items` items are yielded
+    /// when a cap is given. Endpoints that answer with a bare array are treated as a
+    /// single page.
+    ///
+    /// ```no_run
+    /// use futures::TryStreamExt;
+    /// use mcp_tandoor::client::{types::Keyword, TandoorClient};
+    ///
+    /// # async fn example(client: &TandoorClient) -> anyhow::Result<()> {
+    /// let mut keywords = std::pin::pin!(client.paginate::<Keyword>("/api/keyword/", Some(500)));
+    /// while let Some(keyword) = keywords.try_next().await? {
+    ///     println!("{}", keyword.name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn paginate<'a, T>(
+        &'a self,
+        path: &str,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<T, TandoorError>> + 'a
+    where
+        T: DeserializeOwned + 'a,
+    {
+        let mut first_url = format!("{}{}", self.base_url, path);
+        if !first_url.contains("page_size=") {
+            first_url.push(if first_url.contains('?') { '&' } else { '?' });
+            first_url.push_str(&format!("page_size={PAGE_SIZE}"));
+        }
+
+        let pages = stream::try_unfold(Some(first_url), move |url| async move {
+            let Some(url) = url else {
+                return Ok(None);
+            };
+            tracing::debug!("Fetching page: {}", url);
+
+            let page: PageOrList<T> = self.execute(|client| client.get(&url)).await?;
+            let (results, next) = match page {
+                PageOrList::Page(page) => {
+                    let next = page
+                        .next
+                        .as_deref()
+                        .and_then(|next| next_page_url(&url, next));
+                    (page.results, next)
+                }
+                PageOrList::List(results) => (results, None),
+            };
+
+            Ok(Some((
+                stream::iter(results.into_iter().map(Ok::<T, TandoorError>)),
+                next,
+            )))
+        });
+
+        pages.try_flatten().take(max_items.unwrap_or(usize::MAX))
+    }
+
+    /// Collect every item of a list endpoint, see [`Self::paginate`].
+    pub async fn list_all<T>(
+        &self,
+        path: &str,
+        max_items: Option<usize>,
+    ) -> Result<Vec<T>, TandoorError>
+    where
+        T: DeserializeOwned,
+    {
+        let items: Vec<T> = self.paginate(path, max_items).try_collect().await?;
+        tracing::debug!("Collected {} items from {}", items.len(), path);
+        Ok(items)
+    }
+
+    /// Send a request and decode its JSON response body.
+    ///
+    /// This is the single path every endpoint goes through, so all of them report
+    /// failures as the same [`TandoorError`] variants.
+    async fn execute<T, F>(&self, build: F) -> Result<T, TandoorError>
+    where
+        T: DeserializeOwned,
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let response = self.execute_raw(build).await?;
+        let path = response.url().path().to_string();
+
+        let body = response.text().await.map_err(|e| {
+            tracing::error!("Failed to read response from {}: {}", path, e);
+            TandoorError::Network {
+                message: format!("Failed to read response from {path}: {e}"),
             }
-            None => {
-                tracing::warn!("Attempted to make authenticated request without valid token - server will handle re-authentication if credentials are available");
-                anyhow::bail!("Not authenticated - please verify credentials and re-authenticate")
+        })?;
+
+        serde_json::from_str(&body).map_err(|e| {
+            tracing::error!("Failed to parse response from {}: {}", path, e);
+            tracing::trace!("Unparseable response body: {}", body);
+            TandoorError::Decode {
+                message: format!("Unexpected response from {path}: {e}"),
             }
+        })
+    }
+
+    /// Send a request whose response body is not needed, such as a DELETE.
+    async fn execute_empty<F>(&self, build: F) -> Result<(), TandoorError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        self.execute_raw(build).await.map(|_| ())
+    }
+
+    /// Send a request and turn an unsuccessful status into a [`TandoorError`].
+    async fn execute_raw<F>(&self, build: F) -> Result<Response, TandoorError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let response = self.send(build).await?;
+
+        let status = response.status();
+        tracing::trace!("{} response status: {}", response.url().path(), status);
+
+        if status.is_success() {
+            return Ok(response);
         }
+
+        let path = response.url().path().to_string();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        let error_body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unable to read error response".to_string());
+        tracing::error!(
+            "Request to {} failed with status {}: {}",
+            path,
+            status,
+            error_body
+        );
+
+        Err(TandoorError::from_response(
+            status,
+            retry_after,
+            &error_body,
+        ))
+    }
+
+    /// Send an authenticated request, re-authenticating once if the token is rejected.
+    ///
+    /// `build` is called for every attempt, so the request is rebuilt with the fresh
+    /// token after a 401. Re-authentication only happens when credentials are known
+    /// (see [`Self::authenticate`]); otherwise the 401 response is returned as-is for
+    /// the caller to report.
+    async fn send<F>(&self, build: F) -> Result<Response, TandoorError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        if !self.auth.is_authenticated() && self.auth.has_credentials() {
+            self.auth.refresh(None).await?;
+        }
+
+        let token = self.auth.get_token();
+        let response = self.send_once(&build, token.as_deref()).await?;
+
+        if response.status() != StatusCode::UNAUTHORIZED || !self.auth.has_credentials() {
+            return Ok(response);
+        }
+
+        tracing::warn!(
+            "Tandoor rejected the authentication token for {}, re-authenticating",
+            response.url().path()
+        );
+        self.auth.refresh(token.as_deref()).await.map_err(|e| {
+            tracing::error!("Re-authentication failed: {}", e);
+            match e {
+                TandoorError::Unauthorized { message } => TandoorError::Unauthorized {
+                    message: format!(
+                        "Authentication expired and re-authentication failed: {message}"
+                    ),
+                },
+                other => other,
+            }
+        })?;
+
+        let token = self.auth.get_token();
+        self.send_once(&build, token.as_deref()).await
+    }
+
+    async fn send_once<F>(&self, build: &F, token: Option<&str>) -> Result<Response, TandoorError>
+    where
+        F: Fn(&Client) -> RequestBuilder,
+    {
+        let Some(token) = token else {
+            tracing::warn!("Attempted to make authenticated request without valid token - server will handle re-authentication if credentials are available");
+            return Err(TandoorError::Unauthorized {
+                message: "Not authenticated - please verify credentials and re-authenticate"
+                    .to_string(),
+            });
+        };
+
+        tracing::trace!(
+            "Using authentication token: {}...",
+            &token[..token.len().min(10)]
+        );
+
+        // Tandoor uses OAuth2 access tokens with Bearer authentication
+        let request = build(&self.client)
+            .header(AUTHORIZATION, format!("Bearer {token}"))
+            .build()
+            .map_err(|e| TandoorError::Network {
+                message: format!("Failed to build request: {e}"),
+            })?;
+        let method = request.method().clone();
+        let url = request.url().clone();
+
+        self.client.execute(request).await.map_err(|e| {
+            tracing::error!("Network error during {} {}: {}", method, url.path(), e);
+            TandoorError::Network {
+                message: format!("Failed to connect to Tandoor API: {e}"),
+            }
+        })
     }
 
     // Recipe operations
@@ -104,115 +366,72 @@ impl TandoorClient {
         query: Option<&str>,
         limit: Option<i32>,
         page: Option<i32>,
-    ) -> Result<PaginatedResponse<Recipe>> {
-        let auth_header = self.get_auth_header()?;
-        let mut url = format!("{}/api/recipe/", self.base_url);
+    ) -> Result<PaginatedResponse<Recipe>, TandoorError> {
+        let filter = query.map(RecipeSearchFilter::query).unwrap_or_default();
+        self.search_recipes_filtered(&filter, limit, page).await
+    }
 
-        let mut params = vec![];
-        if let Some(q) = query {
-            params.push(format!("query={}", urlencoding::encode(q)));
-        }
+    /// Fetch one page of recipes matching `filter`.
+    pub async fn search_recipes_filtered(
+        &self,
+        filter: &RecipeSearchFilter,
+        limit: Option<i32>,
+        page: Option<i32>,
+    ) -> Result<PaginatedResponse<Recipe>, TandoorError> {
+        let mut params = vec![filter.to_query_string()];
         if let Some(l) = limit {
             params.push(format!("page_size={l}"));
         }
         if let Some(p) = page {
             params.push(format!("page={p}"));
         }
+        params.retain(|param| !param.is_empty());
 
+        let mut url = format!("{}/api/recipe/", self.base_url);
         if !params.is_empty() {
             url.push('?');
             url.push_str(&params.join("&"));
         }
 
         tracing::debug!("Searching recipes with URL: {}", url);
-        tracing::trace!("Search parameters - query: {:?}, limit: {:?}", query, limit);
+        tracing::trace!("Search filter: {:?}, limit: {:?}", filter, limit);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error searching recipes: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Recipe search response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to search recipes with status {}: {}",
-                status,
-                error_body
-            );
-            anyhow::bail!("Failed to search recipes: {status} - {error_body}");
-        }
-
-        let recipes = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse recipe search response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
+        let recipes = self.execute(|client| client.get(&url)).await?;
 
         tracing::debug!("Recipe search successful");
         Ok(recipes)
     }
 
-    pub async fn get_recipe(&self, id: i32) -> Result<Recipe> {
-        let auth_header = self.get_auth_header()?;
+    /// Stream every recipe matching `filter` across all pages, see [`Self::paginate`].
+    pub fn search_all_recipes<'a>(
+        &'a self,
+        filter: &RecipeSearchFilter,
+        max_items: Option<usize>,
+    ) -> impl Stream<Item = Result<Recipe, TandoorError>> + 'a {
+        let query = filter.to_query_string();
+        let path = if query.is_empty() {
+            "/api/recipe/".to_string()
+        } else {
+            format!("/api/recipe/?{query}")
+        };
+        self.paginate(&path, max_items)
+    }
+
+    pub async fn get_recipe(&self, id: i32) -> Result<Recipe, TandoorError> {
         let url = format!("{}/api/recipe/{}/", self.base_url, id);
 
         tracing::debug!("Getting recipe details for ID: {}", id);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error getting recipe {}: {}", id, e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Get recipe response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to get recipe {} with status {}: {}",
-                id,
-                status,
-                error_body
-            );
-
-            match status.as_u16() {
-                404 => anyhow::bail!("Recipe with ID {id} not found"),
-                403 => anyhow::bail!("Access denied to recipe {id}"),
-                _ => anyhow::bail!("Failed to get recipe: {status} - {error_body}"),
-            }
-        }
-
-        let recipe = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse recipe response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
+        let recipe = self.execute(|client| client.get(&url)).await?;
 
         tracing::debug!("Successfully retrieved recipe: {}", id);
         Ok(recipe)
     }
 
-    pub async fn create_recipe(&self, request: CreateRecipeRequest) -> Result<Recipe> {
-        let auth_header = self.get_auth_header()?;
+    pub async fn create_recipe(
+        &self,
+        request: CreateRecipeRequest,
+    ) -> Result<Recipe, TandoorError> {
         let url = format!("{}/api/recipe/", self.base_url);
 
         tracing::debug!("Creating new recipe: {}", request.name);
@@ -223,39 +442,9 @@ impl TandoorClient {
             request.waiting_time
         );
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error creating recipe: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Create recipe response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to create recipe '{}' with status {}: {}",
-                request.name,
-                status,
-                error_body
-            );
-            anyhow::bail!("Failed to create recipe: {status} - {error_body}");
-        }
-
-        let recipe: Recipe = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse create recipe response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
+        let recipe: Recipe = self
+            .execute(|client| client.post(&url).json(&request))
+            .await?;
 
         tracing::info!(
             "Successfully created recipe '{}' with ID: {}",
@@ -269,30 +458,40 @@ impl TandoorClient {
         &self,
         recipe_id: i32,
         keywords: Vec<CreateKeywordRequest>,
-    ) -> Result<Recipe> {
-        let auth_header = self.get_auth_header()?;
+    ) -> Result<Recipe, TandoorError> {
         let url = format!("{}/api/recipe/{}/", self.base_url, recipe_id);
         let request = UpdateRecipeKeywordsRequest { keywords };
 
-        let response = self
-            .client
-            .patch(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
+        self.execute(|client| client.patch(&url).json(&request))
+            .await
+    }
 
-        if !response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to update recipe keywords: {body}");
-        }
+    /// Apply a partial update to a recipe and return the updated recipe.
+    pub async fn update_recipe(
+        &self,
+        recipe_id: i32,
+        request: &UpdateRecipeRequest,
+    ) -> Result<Recipe, TandoorError> {
+        let url = format!("{}/api/recipe/{}/", self.base_url, recipe_id);
 
-        let recipe = response.json().await?;
-        Ok(recipe)
+        self.execute(|client| client.patch(&url).json(request))
+            .await
+    }
+
+    /// Permanently delete a recipe.
+    pub async fn delete_recipe(&self, recipe_id: i32) -> Result<(), TandoorError> {
+        let url = format!("{}/api/recipe/{}/", self.base_url, recipe_id);
+
+        self.execute_empty(|client| client.delete(&url)).await
     }
 
-    pub async fn import_recipe_from_url(&self, url: &str) -> Result<Recipe> {
-        let auth_header = self.get_auth_header()?;
+    /// Parse a recipe from an external URL without saving it.
+    ///
+    /// The returned recipe can be inspected and then saved with [`Self::create_recipe`].
+    pub async fn import_recipe_from_url(
+        &self,
+        url: &str,
+    ) -> Result<RecipeFromSourceResponse, TandoorError> {
         let import_url = format!("{}/api/recipe-from-source/", self.base_url);
         let request = RecipeImport {
             url: url.to_string(),
@@ -300,51 +499,23 @@ impl TandoorClient {
 
         tracing::info!("Importing recipe from URL: {}", url);
 
-        let response = self
-            .client
-            .post(&import_url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error importing recipe from {}: {}", url, e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
+        let parsed: RecipeFromSourceResponse = self
+            .execute(|client| client.post(&import_url).json(&request))
+            .await?;
 
-        let status = response.status();
-        tracing::trace!("Import recipe response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to import recipe from {} with status {}: {}",
-                url,
-                status,
-                error_body
-            );
-
-            match status.as_u16() {
-                400 => anyhow::bail!("Invalid URL or unsupported recipe site: {url}"),
-                404 => anyhow::bail!("Recipe import endpoint not available"),
-                _ => anyhow::bail!("Failed to import recipe: {status} - {error_body}"),
-            }
+        if parsed.error || parsed.recipe.is_none() {
+            let message = parsed
+                .msg
+                .unwrap_or_else(|| "No recipe could be found at this URL".to_string());
+            tracing::error!("Tandoor could not parse recipe from {}: {}", url, message);
+            return Err(TandoorError::Validation {
+                message: format!("Failed to import recipe from {url}: {message}"),
+                field_errors: [("url".to_string(), vec![message])].into(),
+            });
         }
 
-        let recipe: Recipe = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse import recipe response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
-
-        tracing::info!(
-            "Successfully imported recipe '{}' with ID: {}",
-            recipe.name,
-            recipe.id
-        );
-        Ok(recipe)
+        tracing::info!("Successfully parsed recipe from {}", url);
+        Ok(parsed)
     }
 
     // Food operations
@@ -352,8 +523,7 @@ impl TandoorClient {
         &self,
         query: &str,
         limit: Option<i32>,
-    ) -> Result<PaginatedResponse<Food>> {
-        let auth_header = self.get_auth_header()?;
+    ) -> Result<PaginatedResponse<Food>, TandoorError> {
         let mut url = format!(
             "{}/api/food/?query={}",
             self.base_url,
@@ -370,127 +540,62 @@ impl TandoorClient {
             limit
         );
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error searching foods: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Food search response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to search foods '{}' with status {}: {}",
-                query,
-                status,
-                error_body
-            );
-            anyhow::bail!("Failed to search foods: {status} - {error_body}");
-        }
-
-        let foods: PaginatedResponse<Food> = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse food search response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
+        let foods: PaginatedResponse<Food> = self.execute(|client| client.get(&url)).await?;
 
         tracing::debug!("Food search successful, found {} results", foods.count);
         Ok(foods)
     }
 
-    pub async fn update_food_availability(&self, food_id: i32, available: bool) -> Result<Food> {
-        let auth_header = self.get_auth_header()?;
+    /// Every food in the space, up to `max_items`.
+    pub async fn list_foods(&self, max_items: Option<usize>) -> Result<Vec<Food>, TandoorError> {
+        tracing::debug!("Listing all foods, cap: {:?}", max_items);
+
+        self.list_all("/api/food/", max_items).await
+    }
+
+    pub async fn update_food_availability(
+        &self,
+        food_id: i32,
+        available: bool,
+    ) -> Result<Food, TandoorError> {
         let url = format!("{}/api/food/{}/", self.base_url, food_id);
         let request = UpdateFoodRequest {
             food_onhand: Some(available),
+            supermarket_category: None,
         };
 
-        let response = self
-            .client
-            .patch(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
+        self.execute(|client| client.patch(&url).json(&request))
+            .await
+    }
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to update food availability: {}", response.status());
-        }
+    /// Shelve a food in the supermarket category called `category`, creating the category
+    /// if needed, or remove its category with `None`.
+    pub async fn update_food_category(
+        &self,
+        food_id: i32,
+        category: Option<&str>,
+    ) -> Result<Food, TandoorError> {
+        let url = format!("{}/api/food/{}/", self.base_url, food_id);
+        let request = UpdateFoodRequest {
+            food_onhand: None,
+            supermarket_category: Some(category.map(|name| CreateSupermarketCategoryRequest {
+                name: name.to_string(),
+            })),
+        };
 
-        let food = response.json().await?;
-        Ok(food)
+        self.execute(|client| client.patch(&url).json(&request))
+            .await
     }
 
     // Shopping list operations
-    pub async fn get_shopping_list(&self) -> Result<PaginatedResponse<ShoppingListEntry>> {
-        let auth_header = self.get_auth_header()?;
-        let url = format!("{}/api/shopping-list-entry/", self.base_url);
 
+    /// The whole shopping list, collected across all pages.
+    pub async fn get_shopping_list(
+        &self,
+    ) -> Result<PaginatedResponse<ShoppingListEntry>, TandoorError> {
         tracing::debug!("Fetching shopping list");
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error getting shopping list: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Shopping list response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to get shopping list with status {}: {}",
-                status,
-                error_body
-            );
-            anyhow::bail!("Failed to get shopping list: {status} - {error_body}");
-        }
-
-        // Handle both paginated response and simple array response
-        let response_text = response.text().await.map_err(|e| {
-            tracing::error!("Failed to read shopping list response: {}", e);
-            anyhow::anyhow!("Failed to read response: {e}")
-        })?;
-
-        let shopping_list: PaginatedResponse<ShoppingListEntry> =
-            if response_text.trim().starts_with('[') {
-                // Simple array response (empty list case)
-                let entries: Vec<ShoppingListEntry> = serde_json::from_str(&response_text)
-                    .map_err(|e| {
-                        tracing::error!("Failed to parse shopping list array response: {}", e);
-                        anyhow::anyhow!("Invalid array response format: {e}")
-                    })?;
-                PaginatedResponse {
-                    count: entries.len() as i32,
-                    next: None,
-                    previous: None,
-                    results: entries,
-                }
-            } else {
-                // Paginated response
-                serde_json::from_str(&response_text).map_err(|e| {
-                    tracing::error!("Failed to parse shopping list paginated response: {}", e);
-                    anyhow::anyhow!("Invalid paginated response format: {e}")
-                })?
-            };
+        let shopping_list = single_page(self.list_all("/api/shopping-list-entry/", None).await?);
 
         tracing::debug!(
             "Successfully retrieved shopping list with {} items",
@@ -502,31 +607,17 @@ impl TandoorClient {
     pub async fn add_to_shopping_list(
         &self,
         request: CreateShoppingListEntryRequest,
-    ) -> Result<ShoppingListEntry> {
-        let auth_header = self.get_auth_header()?;
+    ) -> Result<ShoppingListEntry, TandoorError> {
         let url = format!("{}/api/shopping-list-entry/", self.base_url);
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to add to shopping list: {}", response.status());
-        }
-
-        let entry = response.json().await?;
-        Ok(entry)
+        self.execute(|client| client.post(&url).json(&request))
+            .await
     }
 
     pub async fn add_bulk_to_shopping_list(
         &self,
         entries: Vec<CreateShoppingListEntryRequest>,
-    ) -> Result<Vec<ShoppingListEntry>> {
-        let auth_header = self.get_auth_header()?;
+    ) -> Result<Vec<ShoppingListEntry>, TandoorError> {
         let url = format!("{}/api/shopping-list-entry/bulk/", self.base_url);
         let entry_count = entries.len();
         tracing::debug!("Adding {} items to shopping list in bulk", entry_count);
@@ -534,38 +625,9 @@ impl TandoorClient {
 
         let request = BulkShoppingListRequest { entries };
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error adding bulk items to shopping list: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Bulk add response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to add bulk items with status {}: {}",
-                status,
-                error_body
-            );
-            anyhow::bail!("Failed to add bulk to shopping list: {status} - {error_body}");
-        }
-
-        let entries: Vec<ShoppingListEntry> = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse bulk add response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
+        let entries: Vec<ShoppingListEntry> = self
+            .execute(|client| client.post(&url).json(&request))
+            .await?;
 
         tracing::info!(
             "Successfully added {} items to shopping list",
@@ -578,58 +640,60 @@ impl TandoorClient {
         &self,
         entry_id: i32,
         request: UpdateShoppingListEntryRequest,
-    ) -> Result<ShoppingListEntry> {
-        let auth_header = self.get_auth_header()?;
+    ) -> Result<ShoppingListEntry, TandoorError> {
         let url = format!("{}/api/shopping-list-entry/{}/", self.base_url, entry_id);
 
-        let response = self
-            .client
-            .patch(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
+        self.execute(|client| client.patch(&url).json(&request))
+            .await
+    }
 
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "Failed to update shopping list entry: {}",
-                response.status()
-            );
-        }
+    pub async fn delete_shopping_list_entry(&self, entry_id: i32) -> Result<(), TandoorError> {
+        let url = format!("{}/api/shopping-list-entry/{}/", self.base_url, entry_id);
 
-        let entry = response.json().await?;
-        Ok(entry)
+        self.execute_empty(|client| client.delete(&url)).await
     }
 
-    pub async fn delete_shopping_list_entry(&self, entry_id: i32) -> Result<()> {
-        let auth_header = self.get_auth_header()?;
-        let url = format!("{}/api/shopping-list-entry/{}/", self.base_url, entry_id);
+    /// Create a shopping list recipe that groups entries added from a recipe.
+    pub async fn create_shopping_list_recipe(
+        &self,
+        request: CreateShoppingListRecipeRequest,
+    ) -> Result<ShoppingListRecipe, TandoorError> {
+        let url = format!("{}/api/shopping-list-recipe/", self.base_url);
 
-        let response = self
-            .client
-            .delete(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await?;
+        tracing::debug!(
+            "Creating shopping list recipe for recipe {} ({} servings)",
+            request.recipe,
+            request.servings
+        );
 
-        if !response.status().is_success() {
-            anyhow::bail!(
-                "Failed to delete shopping list entry: {}",
-                response.status()
-            );
-        }
+        self.execute(|client| client.post(&url).json(&request))
+            .await
+    }
 
-        Ok(())
+    /// Delete a shopping list recipe together with all entries added from it.
+    pub async fn delete_shopping_list_recipe(
+        &self,
+        list_recipe_id: i32,
+    ) -> Result<(), TandoorError> {
+        let url = format!(
+            "{}/api/shopping-list-recipe/{}/",
+            self.base_url, list_recipe_id
+        );
+
+        self.execute_empty(|client| client.delete(&url)).await
     }
 
     // Meal planning operations
+
+    /// All meal plans in the date range, optionally of one meal type, collected across all
+    /// pages.
     pub async fn get_meal_plans(
         &self,
         from_date: Option<&str>,
         to_date: Option<&str>,
-    ) -> Result<PaginatedResponse<MealPlan>> {
-        let auth_header = self.get_auth_header()?;
-        let mut url = format!("{}/api/meal-plan/", self.base_url);
+        meal_type: Option<i32>,
+    ) -> Result<PaginatedResponse<MealPlan>, TandoorError> {
+        let mut path = "/api/meal-plan/".to_string();
 
         let mut params = vec![];
         if let Some(from) = from_date {
@@ -638,116 +702,134 @@ impl TandoorClient {
         if let Some(to) = to_date {
             params.push(format!("to_date={to}"));
         }
+        if let Some(meal_type) = meal_type {
+            params.push(format!("meal_type={meal_type}"));
+        }
 
         if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
+            path.push('?');
+            path.push_str(&params.join("&"));
         }
 
         tracing::debug!("Getting meal plans from {:?} to {:?}", from_date, to_date);
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error getting meal plans: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Meal plans response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to get meal plans with status {}: {}",
-                status,
-                error_body
-            );
-            anyhow::bail!("Failed to get meal plans: {status} - {error_body}");
-        }
-
-        let meal_plans: PaginatedResponse<MealPlan> = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse meal plans response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
+        let meal_plans = single_page(self.list_all(&path, None).await?);
 
         tracing::debug!("Successfully retrieved {} meal plans", meal_plans.count);
         Ok(meal_plans)
     }
 
-    pub async fn create_meal_plan(&self, request: CreateMealPlanRequest) -> Result<MealPlan> {
-        let auth_header = self.get_auth_header()?;
+    pub async fn create_meal_plan(
+        &self,
+        request: CreateMealPlanRequest,
+    ) -> Result<MealPlan, TandoorError> {
         let url = format!("{}/api/meal-plan/", self.base_url);
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
+        self.execute(|client| client.post(&url).json(&request))
+            .await
+    }
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to create meal plan: {}", response.status());
-        }
+    pub async fn get_meal_plan(&self, plan_id: i32) -> Result<MealPlan, TandoorError> {
+        let url = format!("{}/api/meal-plan/{}/", self.base_url, plan_id);
 
-        let meal_plan = response.json().await?;
-        Ok(meal_plan)
+        self.execute(|client| client.get(&url)).await
     }
 
-    pub async fn delete_meal_plan(&self, plan_id: i32) -> Result<()> {
-        let auth_header = self.get_auth_header()?;
+    /// Change the given fields of a meal plan, keeping its ID and shopping list links.
+    pub async fn update_meal_plan(
+        &self,
+        plan_id: i32,
+        request: UpdateMealPlanRequest,
+    ) -> Result<MealPlan, TandoorError> {
         let url = format!("{}/api/meal-plan/{}/", self.base_url, plan_id);
 
-        let response = self
-            .client
-            .delete(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await?;
+        self.execute(|client| client.patch(&url).json(&request))
+            .await
+    }
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to delete meal plan: {}", response.status());
-        }
+    pub async fn delete_meal_plan(&self, plan_id: i32) -> Result<(), TandoorError> {
+        let url = format!("{}/api/meal-plan/{}/", self.base_url, plan_id);
 
-        Ok(())
+        self.execute_empty(|client| client.delete(&url)).await
     }
 
     // Meal types
-    pub async fn get_meal_types(&self) -> Result<PaginatedResponse<MealType>> {
-        let auth_header = self.get_auth_header()?;
-        let url = format!("{}/api/meal-type/", self.base_url);
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await?;
+    pub async fn get_meal_types(&self) -> Result<PaginatedResponse<MealType>, TandoorError> {
+        Ok(single_page(self.list_all("/api/meal-type/", None).await?))
+    }
 
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to get meal types: {}", response.status());
-        }
+    // Supermarkets
+
+    /// Every supermarket, each with its category ordering.
+    pub async fn get_supermarkets(&self) -> Result<PaginatedResponse<Supermarket>, TandoorError> {
+        Ok(single_page(self.list_all("/api/supermarket/", None).await?))
+    }
+
+    pub async fn create_supermarket(
+        &self,
+        request: CreateSupermarketRequest,
+    ) -> Result<Supermarket, TandoorError> {
+        let url = format!("{}/api/supermarket/", self.base_url);
+
+        self.execute(|client| client.post(&url).json(&request))
+            .await
+    }
+
+    /// Place a category at `order` in a supermarket's walking order.
+    pub async fn create_supermarket_category_relation(
+        &self,
+        request: CreateSupermarketCategoryRelationRequest,
+    ) -> Result<SupermarketCategoryRelation, TandoorError> {
+        let url = format!("{}/api/supermarket-category-relation/", self.base_url);
+
+        self.execute(|client| client.post(&url).json(&request))
+            .await
+    }
+
+    pub async fn update_supermarket_category_order(
+        &self,
+        relation_id: i32,
+        order: i32,
+    ) -> Result<SupermarketCategoryRelation, TandoorError> {
+        let url = format!(
+            "{}/api/supermarket-category-relation/{}/",
+            self.base_url, relation_id
+        );
+        let request = UpdateSupermarketCategoryRelationRequest { order };
+
+        self.execute(|client| client.patch(&url).json(&request))
+            .await
+    }
+
+    pub async fn delete_supermarket_category_relation(
+        &self,
+        relation_id: i32,
+    ) -> Result<(), TandoorError> {
+        let url = format!(
+            "{}/api/supermarket-category-relation/{}/",
+            self.base_url, relation_id
+        );
 
-        let meal_types = response.json().await?;
-        Ok(meal_types)
+        self.execute_empty(|client| client.delete(&url)).await
+    }
+
+    pub async fn get_supermarket_categories(
+        &self,
+    ) -> Result<PaginatedResponse<SupermarketCategory>, TandoorError> {
+        Ok(single_page(
+            self.list_all("/api/supermarket-category/", None).await?,
+        ))
     }
 
     // Cook log operations
+
+    /// Cook log entries, optionally for one recipe, collected across all pages.
     pub async fn get_cook_log(
         &self,
         recipe_id: Option<i32>,
         days_back: Option<i32>,
-    ) -> Result<PaginatedResponse<CookLog>> {
-        let auth_header = self.get_auth_header()?;
-        let mut url = format!("{}/api/cook-log/", self.base_url);
+    ) -> Result<PaginatedResponse<CookLog>, TandoorError> {
+        let mut path = "/api/cook-log/".to_string();
 
         let mut params = vec![];
         if let Some(recipe) = recipe_id {
@@ -760,8 +842,8 @@ impl TandoorClient {
         }
 
         if !params.is_empty() {
-            url.push('?');
-            url.push_str(&params.join("&"));
+            path.push('?');
+            path.push_str(&params.join("&"));
         }
 
         tracing::debug!(
@@ -770,233 +852,91 @@ impl TandoorClient {
             days_back
         );
 
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error getting cook log: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::trace!("Cook log response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to get cook log with status {}: {}",
-                status,
-                error_body
-            );
-            anyhow::bail!("Failed to get cook log: {status} - {error_body}");
-        }
-
-        let cook_log: PaginatedResponse<CookLog> = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse cook log response: {}", e);
-            anyhow::anyhow!("Invalid response format: {e}")
-        })?;
+        let cook_log = single_page(self.list_all(&path, None).await?);
 
         tracing::debug!("Successfully retrieved {} cook log entries", cook_log.count);
         Ok(cook_log)
     }
 
-    pub async fn log_cooked_recipe(&self, request: CreateCookLogRequest) -> Result<CookLog> {
-        let auth_header = self.get_auth_header()?;
+    pub async fn log_cooked_recipe(
+        &self,
+        request: CreateCookLogRequest,
+    ) -> Result<CookLog, TandoorError> {
         let url = format!("{}/api/cook-log/", self.base_url);
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            anyhow::bail!("Failed to log cooked recipe: {}", response.status());
-        }
-
-        let cook_log = response.json().await?;
-        Ok(cook_log)
+        self.execute(|client| client.post(&url).json(&request))
+            .await
     }
 
     // Utility operations
-    pub async fn get_keywords(&self) -> Result<PaginatedResponse<Keyword>> {
-        let auth_header = self.get_auth_header()?;
-        let url = format!("{}/api/keyword/", self.base_url);
+    pub async fn get_keywords(&self) -> Result<PaginatedResponse<Keyword>, TandoorError> {
+        tracing::debug!("Fetching all keywords");
 
-        tracing::debug!("Making request to get keywords: {}", url);
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error getting keywords: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
-
-        let status = response.status();
-        tracing::debug!("Keywords response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!(
-                "Failed to get keywords with status {}: {}",
-                status,
-                error_body
-            );
-
-            match status.as_u16() {
-                401 => anyhow::bail!("Authentication expired or invalid. Please re-authenticate."),
-                403 => anyhow::bail!("Access denied to keywords endpoint. Check user permissions."),
-                404 => anyhow::bail!(
-                    "Keywords endpoint not found. Check Tandoor version and API availability."
-                ),
-                500..=599 => {
-                    anyhow::bail!("Tandoor server error getting keywords ({status}): {error_body}")
-                }
-                _ => anyhow::bail!("Failed to get keywords with status {status}: {error_body}"),
-            }
-        }
-
-        let keywords: PaginatedResponse<Keyword> = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse keywords response: {}", e);
-            anyhow::anyhow!("Invalid response format from Tandoor server: {e}")
-        })?;
+        let keywords = single_page(self.list_all("/api/keyword/", None).await?);
 
         tracing::debug!("Successfully retrieved {} keywords", keywords.count);
         Ok(keywords)
     }
 
     // Recipe book operations
-    pub async fn get_recipe_books(&self) -> Result<PaginatedResponse<RecipeBook>> {
-        let auth_header = self.get_auth_header()?;
-        let url = format!("{}/api/recipe-book/?page_size=100", self.base_url);
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to get recipe books: {body}");
-        }
-
-        let books = response.json().await?;
-        Ok(books)
+    pub async fn get_recipe_books(&self) -> Result<PaginatedResponse<RecipeBook>, TandoorError> {
+        Ok(single_page(self.list_all("/api/recipe-book/", None).await?))
     }
 
-    pub async fn create_recipe_book(&self, request: CreateRecipeBookRequest) -> Result<RecipeBook> {
-        let auth_header = self.get_auth_header()?;
+    pub async fn create_recipe_book(
+        &self,
+        request: CreateRecipeBookRequest,
+    ) -> Result<RecipeBook, TandoorError> {
         let url = format!("{}/api/recipe-book/", self.base_url);
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to create recipe book: {body}");
-        }
-
-        let book = response.json().await?;
-        Ok(book)
+        self.execute(|client| client.post(&url).json(&request))
+            .await
     }
 
     pub async fn add_recipe_to_book(
         &self,
         book_id: i32,
         recipe_id: i32,
-    ) -> Result<RecipeBookEntry> {
-        let auth_header = self.get_auth_header()?;
+    ) -> Result<RecipeBookEntry, TandoorError> {
         let url = format!("{}/api/recipe-book-entry/", self.base_url);
         let request = CreateRecipeBookEntryRequest {
             book: book_id,
             recipe: recipe_id,
         };
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", auth_header)
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Failed to add recipe to book: {body}");
-        }
-
-        let entry = response.json().await?;
-        Ok(entry)
+        self.execute(|client| client.post(&url).json(&request))
+            .await
     }
 
-    pub async fn get_units(&self) -> Result<PaginatedResponse<Unit>> {
-        let auth_header = self.get_auth_header()?;
-        let url = format!("{}/api/unit/", self.base_url);
-
-        tracing::debug!("Making request to get units: {}", url);
-
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
-                tracing::error!("Network error getting units: {}", e);
-                anyhow::anyhow!("Failed to connect to Tandoor API: {e}")
-            })?;
+    pub async fn get_units(&self) -> Result<PaginatedResponse<Unit>, TandoorError> {
+        tracing::debug!("Fetching all units");
 
-        let status = response.status();
-        tracing::debug!("Units response status: {}", status);
-
-        if !status.is_success() {
-            let error_body = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Unable to read error response".to_string());
-            tracing::error!("Failed to get units with status {}: {}", status, error_body);
-
-            match status.as_u16() {
-                401 => anyhow::bail!("Authentication expired or invalid. Please re-authenticate."),
-                403 => anyhow::bail!("Access denied to units endpoint. Check user permissions."),
-                404 => anyhow::bail!(
-                    "Units endpoint not found. Check Tandoor version and API availability."
-                ),
-                500..=599 => {
-                    anyhow::bail!("Tandoor server error getting units ({status}): {error_body}")
-                }
-                _ => anyhow::bail!("Failed to get units with status {status}: {error_body}"),
-            }
-        }
-
-        let units: PaginatedResponse<Unit> = response.json().await.map_err(|e| {
-            tracing::error!("Failed to parse units response: {}", e);
-            anyhow::anyhow!("Invalid response format from Tandoor server: {e}")
-        })?;
+        let units = single_page(self.list_all("/api/unit/", None).await?);
 
         tracing::debug!("Successfully retrieved {} units", units.count);
         Ok(units)
     }
 }
+
+/// Build the URL of the next page from the `next` link Tandoor returned.
+///
+/// Only the query string of `next` is used, so pages keep coming from the configured base
+/// URL even when Tandoor sits behind a proxy and reports its internal host. Returns `None`
+/// if the link is unusable or points back to the current page.
+fn next_page_url(current: &str, next: &str) -> Option<String> {
+    let next = reqwest::Url::parse(next).ok()?;
+    let mut url = reqwest::Url::parse(current).ok()?;
+    url.set_query(next.query());
+    let url = url.to_string();
+    (url != current).then_some(url)
+}
+
+/// Present a fully collected list in the paginated shape the API methods return.
+fn single_page<T>(results: Vec<T>) -> PaginatedResponse<T> {
+    PaginatedResponse {
+        count: results.len() as i32,
+        next: None,
+        previous: None,
+        results,
+    }
+}
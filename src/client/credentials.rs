@@ -0,0 +1,71 @@
+//! Credential providers for Tandoor logins.
+//!
+//! Each [`TandoorAuth`](crate::client::auth::TandoorAuth) asks its own
+//! [`CredentialProvider`] for a username and password whenever it has to log in, so
+//! several clients or servers for different Tandoor accounts can live in one process.
+//! The provider is consulted again on every re-authentication, which lets embedders
+//! rotate passwords or fetch them from a secret store without restarting.
+
+use anyhow::Result;
+use std::fmt;
+
+/// A Tandoor username and password.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+impl Credentials {
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+/// Source of the credentials used to log in to Tandoor.
+///
+/// Implemented for [`Credentials`] itself (fixed credentials) and for any
+/// `Fn() -> anyhow::Result<Credentials>` closure.
+///
+/// ```
+/// use mcp_tandoor::client::{CredentialProvider, Credentials};
+///
+/// let from_env = || -> anyhow::Result<Credentials> {
+///     Ok(Credentials::new(
+///         std::env::var("TANDOOR_USERNAME")?,
+///         std::env::var("TANDOOR_PASSWORD")?,
+///     ))
+/// };
+/// # let _ = from_env.credentials();
+/// ```
+pub trait CredentialProvider: Send + Sync {
+    /// Credentials for the next login.
+    fn credentials(&self) -> Result<Credentials>;
+}
+
+impl CredentialProvider for Credentials {
+    fn credentials(&self) -> Result<Credentials> {
+        Ok(self.clone())
+    }
+}
+
+impl<F> CredentialProvider for F
+where
+    F: Fn() -> Result<Credentials> + Send + Sync,
+{
+    fn credentials(&self) -> Result<Credentials> {
+        self()
+    }
+}
@@ -0,0 +1,292 @@
+//! Typed errors for Tandoor API requests.
+//!
+//! Every [`TandoorClient`](crate::client::TandoorClient) endpoint reports failures as a
+//! [`TandoorError`], so callers can tell an expired token from a missing recipe or a
+//! rejected field without parsing error strings.
+//!
+//! Each variant maps to a stable machine-readable [`code`](TandoorError::code) and a
+//! [retryability hint](TandoorError::is_retryable), and [`TandoorError::to_json`] renders
+//! both together with any per-field validation messages.
+
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Longest error body kept in a message; HTML error pages are truncated to this.
+const MAX_BODY_LEN: usize = 500;
+
+/// A failed Tandoor API request.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TandoorError {
+    /// The token is missing or was rejected and could not be refreshed (401)
+    Unauthorized { message: String },
+    /// The user may not access this resource (403)
+    Forbidden { message: String },
+    /// The resource does not exist (404)
+    NotFound { message: String },
+    /// Tandoor rejected the request body or parameters (400)
+    Validation {
+        message: String,
+        /// Messages per field, as reported by Django REST framework
+        field_errors: BTreeMap<String, Vec<String>>,
+    },
+    /// Tandoor throttled the request (429) or the login budget is used up
+    RateLimited {
+        message: String,
+        /// Seconds to wait before retrying, if known
+        retry_after: Option<u64>,
+    },
+    /// Tandoor failed to handle the request (5xx)
+    Server { status: u16, message: String },
+    /// Any other unsuccessful status
+    Unexpected { status: u16, message: String },
+    /// The request never got a response
+    Network { message: String },
+    /// The response body did not have the expected shape
+    Decode { message: String },
+}
+
+impl TandoorError {
+    /// Build an error from an unsuccessful response.
+    pub fn from_response(status: StatusCode, retry_after: Option<u64>, body: &str) -> Self {
+        let parsed: Option<Value> = serde_json::from_str(body).ok();
+        let message = parsed
+            .as_ref()
+            .and_then(detail_message)
+            .unwrap_or_else(|| truncate_body(body, status));
+
+        match status.as_u16() {
+            400 | 422 => {
+                let field_errors = parsed.as_ref().map(field_errors).unwrap_or_default();
+                let message = if field_errors.is_empty() {
+                    message
+                } else {
+                    field_errors
+                        .iter()
+                        .map(|(field, messages)| format!("{field}: {}", messages.join(" ")))
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                };
+                Self::Validation {
+                    message,
+                    field_errors,
+                }
+            }
+            401 => Self::Unauthorized { message },
+            403 => Self::Forbidden { message },
+            404 => Self::NotFound { message },
+            429 => Self::RateLimited {
+                message,
+                retry_after,
+            },
+            500..=599 => Self::Server {
+                status: status.as_u16(),
+                message,
+            },
+            _ => Self::Unexpected {
+                status: status.as_u16(),
+                message,
+            },
+        }
+    }
+
+    /// Stable machine-readable code for this kind of error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Unauthorized { .. } => "unauthorized",
+            Self::Forbidden { .. } => "forbidden",
+            Self::NotFound { .. } => "not_found",
+            Self::Validation { .. } => "validation_error",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::Server { .. } => "server_error",
+            Self::Unexpected { .. } => "unexpected_response",
+            Self::Network { .. } => "network_error",
+            Self::Decode { .. } => "decode_error",
+        }
+    }
+
+    /// Whether repeating the same request later may succeed.
+    ///
+    /// Rate limits, server errors and network failures are transient; everything else
+    /// needs a different request (or different credentials) to succeed.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RateLimited { .. } | Self::Network { .. } => true,
+            Self::Server { status, .. } => *status != 501,
+            _ => false,
+        }
+    }
+
+    /// Per-field validation messages, if Tandoor reported any.
+    pub fn field_errors(&self) -> Option<&BTreeMap<String, Vec<String>>> {
+        match self {
+            Self::Validation { field_errors, .. } if !field_errors.is_empty() => Some(field_errors),
+            _ => None,
+        }
+    }
+
+    /// Render the error as JSON with its code, message, retryability and, when present,
+    /// status, retry delay and field errors.
+    pub fn to_json(&self) -> Value {
+        let mut body = json!({
+            "code": self.code(),
+            "message": self.message(),
+            "retryable": self.is_retryable(),
+        });
+        if let Some(status) = self.status() {
+            body["status"] = json!(status);
+        }
+        if let Self::RateLimited {
+            retry_after: Some(seconds),
+            ..
+        } = self
+        {
+            body["retry_after"] = json!(seconds);
+        }
+        if let Some(field_errors) = self.field_errors() {
+            body["field_errors"] = json!(field_errors);
+        }
+        body
+    }
+
+    /// HTTP status of the response that caused this error, if there was one.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Unauthorized { .. } => Some(401),
+            Self::Forbidden { .. } => Some(403),
+            Self::NotFound { .. } => Some(404),
+            Self::Validation { .. } => Some(400),
+            Self::RateLimited { .. } => Some(429),
+            Self::Server { status, .. } | Self::Unexpected { status, .. } => Some(*status),
+            Self::Network { .. } | Self::Decode { .. } => None,
+        }
+    }
+
+    /// The human-readable message without the error kind prefix.
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Unauthorized { message }
+            | Self::Forbidden { message }
+            | Self::NotFound { message }
+            | Self::Validation { message, .. }
+            | Self::RateLimited { message, .. }
+            | Self::Server { message, .. }
+            | Self::Unexpected { message, .. }
+            | Self::Network { message }
+            | Self::Decode { message } => message,
+        }
+    }
+}
+
+impl fmt::Display for TandoorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unauthorized { message } => write!(f, "Unauthorized: {message}"),
+            Self::Forbidden { message } => write!(f, "Access denied: {message}"),
+            Self::NotFound { message } => write!(f, "Not found: {message}"),
+            Self::Validation { message, .. } => write!(f, "Invalid request: {message}"),
+            Self::RateLimited {
+                message,
+                retry_after: Some(seconds),
+            } => write!(f, "Rate limited: {message} (retry after {seconds}s)"),
+            Self::RateLimited { message, .. } => write!(f, "Rate limited: {message}"),
+            Self::Server { status, message } => {
+                write!(f, "Tandoor server error ({status}): {message}")
+            }
+            Self::Unexpected { status, message } => {
+                write!(f, "Unexpected response ({status}): {message}")
+            }
+            Self::Network { message } => write!(f, "Network error: {message}"),
+            Self::Decode { message } => write!(f, "Invalid response format: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for TandoorError {}
+
+/// Django REST framework puts general errors under `detail`.
+fn detail_message(body: &Value) -> Option<String> {
+    match body {
+        Value::Object(map) => map.get("detail").map(value_to_message),
+        Value::Array(items) if !items.is_empty() => Some(
+            items
+                .iter()
+                .map(value_to_message)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Value::String(message) => Some(message.clone()),
+        _ => None,
+    }
+}
+
+/// Collect Django REST framework validation errors into per-field messages.
+///
+/// Nested serializer errors are flattened into paths, so
+/// `{"steps": [{"ingredients": [{}, {"amount": ["..."]}]}]}` is reported under
+/// `steps[0].ingredients[1].amount`. General errors stay under `non_field_errors`.
+fn field_errors(body: &Value) -> BTreeMap<String, Vec<String>> {
+    let mut errors = BTreeMap::new();
+    if let Value::Object(map) = body {
+        for (field, value) in map {
+            if field != "detail" {
+                collect_field_errors(field.clone(), value, &mut errors);
+            }
+        }
+    }
+    errors
+}
+
+fn collect_field_errors(path: String, value: &Value, errors: &mut BTreeMap<String, Vec<String>>) {
+    match value {
+        Value::Object(map) => {
+            for (field, nested) in map {
+                collect_field_errors(format!("{path}.{field}"), nested, errors);
+            }
+        }
+        Value::Array(items)
+            if !items.is_empty()
+                && items
+                    .iter()
+                    .all(|item| !item.is_object() && !item.is_array()) =>
+        {
+            errors
+                .entry(path)
+                .or_default()
+                .extend(items.iter().map(value_to_message));
+        }
+        // A list of nested errors, one per item; items without errors are empty objects
+        Value::Array(items) => {
+            for (index, nested) in items.iter().enumerate() {
+                collect_field_errors(format!("{path}[{index}]"), nested, errors);
+            }
+        }
+        Value::Null => {}
+        other => errors
+            .entry(path)
+            .or_default()
+            .push(value_to_message(other)),
+    }
+}
+
+fn value_to_message(value: &Value) -> String {
+    match value {
+        Value::String(message) => message.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn truncate_body(body: &str, status: StatusCode) -> String {
+    let body = body.trim();
+    if body.is_empty() {
+        return status
+            .canonical_reason()
+            .unwrap_or("No error details")
+            .to_string();
+    }
+    match body.char_indices().nth(MAX_BODY_LEN) {
+        Some((index, _)) => format!("{}...", &body[..index]),
+        None => body.to_string(),
+    }
+}
@@ -7,6 +7,9 @@
 //!
 //! - [`auth`] - Authentication handling for Tandoor OAuth2 tokens
 //! - [`client`] - Main HTTP client implementation with all API methods
+//! - [`credentials`] - Injectable sources of login credentials
+//! - [`error`] - Typed errors returned by every API method
+//! - [`token_store`] - Persistent on-disk cache for authentication tokens
 //! - [`types`] - Type definitions for API requests and responses
 //!
 //! ## Quick Start
@@ -15,13 +18,13 @@
 //! use mcp_tandoor::client::TandoorClient;
 //!
 //! # async fn example() -> anyhow::Result<()> {
-//! let mut client = TandoorClient::new("http://localhost:8080".to_string());
+//! let client = TandoorClient::new("http://localhost:8080".to_string());
 //!
 //! // Authenticate with Tandoor
 //! client.authenticate("username".to_string(), "password".to_string()).await?;
 //!
 //! // Search for recipes
-//! let recipes = client.search_recipes(Some("pasta"), Some(10)).await?;
+//! let recipes = client.search_recipes(Some("pasta"), Some(10), None).await?;
 //! println!("Found {} recipes", recipes.count);
 //! # Ok(())
 //! # }
@@ -30,7 +33,13 @@
 pub mod auth;
 #[allow(clippy::module_inception)]
 pub mod client;
+pub mod credentials;
+pub mod error;
+pub mod token_store;
 pub mod types;
 
 pub use client::TandoorClient;
+pub use credentials::{CredentialProvider, Credentials};
+pub use error::TandoorError;
+pub use token_store::TokenStore;
 pub use types::*;
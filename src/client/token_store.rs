@@ -0,0 +1,197 @@
+//! Persistent on-disk cache for Tandoor authentication tokens.
+//!
+//! Tandoor only allows 10 logins per day, so restarting the server should not cost a
+//! login when the previous token is still valid. [`TokenStore`] keeps one token per
+//! Tandoor base URL and username in a small JSON file that only the current user can
+//! read (mode `0600` on Unix).
+//!
+//! The default location follows the XDG base directory spec:
+//! `$XDG_STATE_HOME/mcp-tandoor/tokens.json`, falling back to
+//! `$HOME/.local/state/mcp-tandoor/tokens.json`.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TokenFile {
+    #[serde(default)]
+    tokens: BTreeMap<String, CachedToken>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedToken {
+    token: String,
+    saved_at: DateTime<Utc>,
+}
+
+/// Distinguishes temporary files written at the same time by one process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// File-backed token cache keyed by Tandoor base URL and username.
+///
+/// Clones share a lock, so concurrent saves and removals through them never lose
+/// each other's tokens.
+#[derive(Debug, Clone)]
+pub struct TokenStore {
+    path: PathBuf,
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl TokenStore {
+    /// Create a store backed by the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Create a store at the default XDG state location, if a home directory is known.
+    pub fn default_location() -> Option<Self> {
+        let state_dir = std::env::var_os("XDG_STATE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .filter(|dir| !dir.is_empty())
+                    .map(|home| PathBuf::from(home).join(".local").join("state"))
+            })?;
+
+        Some(Self::new(state_dir.join("mcp-tandoor").join("tokens.json")))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the cached token for `base_url` and `username`, if any.
+    ///
+    /// A missing or unreadable cache file is treated as empty.
+    pub fn load(&self, base_url: &str, username: &str) -> Option<String> {
+        let file = match self.read() {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!(
+                    "Ignoring unreadable token cache {}: {}",
+                    self.path.display(),
+                    e
+                );
+                return None;
+            }
+        };
+
+        file.tokens
+            .get(&Self::key(base_url, username))
+            .map(|cached| cached.token.clone())
+    }
+
+    /// Save `token` for `base_url` and `username`, replacing any previous token.
+    pub fn save(&self, base_url: &str, username: &str, token: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut file = self.read().unwrap_or_default();
+        file.tokens.insert(
+            Self::key(base_url, username),
+            CachedToken {
+                token: token.to_string(),
+                saved_at: Utc::now(),
+            },
+        );
+        self.write(&file)
+    }
+
+    /// Forget the cached token for `base_url` and `username`.
+    pub fn remove(&self, base_url: &str, username: &str) -> Result<()> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut file = self.read().unwrap_or_default();
+        if file.tokens.remove(&Self::key(base_url, username)).is_some() {
+            self.write(&file)?;
+        }
+        Ok(())
+    }
+
+    fn key(base_url: &str, username: &str) -> String {
+        format!("{}|{}", base_url.trim_end_matches('/'), username)
+    }
+
+    fn read(&self) -> Result<TokenFile> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid token cache {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TokenFile::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        }
+    }
+
+    /// Write the cache atomically via a temporary file that is only readable by the owner.
+    /// Callers hold `write_lock`.
+    fn write(&self, file: &TokenFile) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            create_private_dir(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let tmp_path = self.path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let contents = serde_json::to_vec_pretty(file)?;
+        let written = open_private_file(&tmp_path)
+            .and_then(|mut tmp| {
+                tmp.write_all(&contents)?;
+                tmp.sync_all()
+            })
+            .and_then(|()| fs::rename(&tmp_path, &self.path));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e).with_context(|| format!("Failed to write {}", self.path.display()));
+        }
+
+        tracing::debug!("Saved token cache to {}", self.path.display());
+        Ok(())
+    }
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn open_private_file(path: &Path) -> std::io::Result<fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // `mode` only applies on creation; tighten a pre-existing file too
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn open_private_file(path: &Path) -> std::io::Result<fs::File> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(path)
+}
@@ -18,7 +18,7 @@
 //! - [`Recipe`] maps "created_at"/"updated_at" to "created"/"updated" fields
 //! - Many fields are optional to handle varying API response formats
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 /// OAuth2 authentication token response from Tandoor.
@@ -287,11 +287,52 @@ pub struct Food {
     pub description: Option<String>,
     pub recipe: Option<serde_json::Value>,
     pub food_onhand: bool,
-    pub supermarket_category: Option<serde_json::Value>,
+    /// Store section the food is shelved in, used to order the shopping list
+    pub supermarket_category: Option<SupermarketCategory>,
     pub inherit_fields: Vec<InheritField>,
     pub properties: Vec<FoodProperty>,
 }
 
+/// A store section foods are grouped by on the shopping list, e.g. "Produce".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupermarketCategory {
+    pub id: i32,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A store and the order its categories are walked through.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Supermarket {
+    pub id: i32,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Categories stocked by this store, in no guaranteed order; sort by `order`
+    #[serde(default)]
+    pub category_to_supermarket: Vec<SupermarketCategoryRelation>,
+}
+
+impl Supermarket {
+    /// Position of `category_id` in this store's walking order, if the store stocks it.
+    pub fn category_order(&self, category_id: i32) -> Option<i32> {
+        self.category_to_supermarket
+            .iter()
+            .find(|relation| relation.category.id == category_id)
+            .map(|relation| relation.order)
+    }
+}
+
+/// Places a category at a position in a supermarket's walking order.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SupermarketCategoryRelation {
+    pub id: i32,
+    pub category: SupermarketCategory,
+    pub supermarket: i32,
+    pub order: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Unit {
     pub id: i32,
@@ -359,6 +400,27 @@ pub struct ShoppingListEntry {
     pub created_by: serde_json::Value,
     /// User who marked this as completed (may be an object or integer)
     pub completed_by: Option<serde_json::Value>,
+    /// Recipe group this entry was added from, if any
+    #[serde(default)]
+    pub list_recipe: Option<i32>,
+    /// Recipe ingredient this entry was created from, if any
+    #[serde(default)]
+    pub ingredient: Option<i32>,
+}
+
+/// A recipe added to the shopping list, grouping the entries created from it.
+///
+/// Deleting a shopping list recipe removes all of its entries.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShoppingListRecipe {
+    pub id: i32,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub recipe: Option<i32>,
+    #[serde(default)]
+    pub mealplan: Option<i32>,
+    pub servings: f64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -397,11 +459,360 @@ pub struct CookLog {
     pub created_by: serde_json::Value,
 }
 
+/// Request payload for `/api/recipe-from-source/`.
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RecipeImport {
     pub url: String,
 }
 
+/// Result of parsing a recipe from an external source.
+///
+/// Tandoor only parses the page; nothing is saved until the recipe is posted to
+/// `/api/recipe/`. Older Tandoor versions return the parsed recipe as "recipe_json".
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecipeFromSourceResponse {
+    /// The parsed recipe, if the page could be understood
+    #[serde(default, alias = "recipe_json")]
+    pub recipe: Option<SourceRecipe>,
+    /// Existing recipes that look like duplicates of the imported one
+    #[serde(default)]
+    pub duplicates: Vec<serde_json::Value>,
+    /// Whether Tandoor reported a parsing error
+    #[serde(default)]
+    pub error: bool,
+    /// Message accompanying a parsing error
+    #[serde(default)]
+    pub msg: Option<String>,
+}
+
+/// A recipe as parsed from an external source, before it is saved.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceRecipe {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub servings: Option<i32>,
+    #[serde(default)]
+    pub servings_text: Option<String>,
+    #[serde(default)]
+    pub working_time: Option<i32>,
+    #[serde(default)]
+    pub waiting_time: Option<i32>,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default)]
+    pub keywords: Vec<SourceKeyword>,
+    #[serde(default)]
+    pub steps: Vec<SourceStep>,
+}
+
+/// A keyword suggested by the recipe parser (uses "label" or "name" like [`Keyword`]).
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceKeyword {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+impl SourceKeyword {
+    pub fn display_name(&self) -> Option<&str> {
+        self.name.as_deref().or(self.label.as_deref())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceStep {
+    #[serde(default)]
+    pub instruction: String,
+    #[serde(default)]
+    pub ingredients: Vec<SourceIngredient>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SourceIngredient {
+    #[serde(default)]
+    pub amount: f64,
+    #[serde(default)]
+    pub food: Option<CreateFoodRequest>,
+    #[serde(default)]
+    pub unit: Option<CreateUnitRequest>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub original_text: Option<String>,
+    #[serde(default)]
+    pub is_header: bool,
+    #[serde(default)]
+    pub no_amount: bool,
+}
+
+/// Convert a parsed source recipe into a request that saves it to Tandoor.
+impl From<SourceRecipe> for CreateRecipeRequest {
+    fn from(source: SourceRecipe) -> Self {
+        let keywords = source
+            .keywords
+            .iter()
+            .filter_map(|k| k.display_name())
+            .map(|name| CreateKeywordRequest {
+                name: name.to_string(),
+            })
+            .collect();
+
+        let steps = source
+            .steps
+            .into_iter()
+            .enumerate()
+            .map(|(step_index, step)| CreateStepRequest {
+                name: None,
+                instruction: step.instruction,
+                ingredients: step
+                    .ingredients
+                    .into_iter()
+                    .filter_map(|mut ingredient| {
+                        // Ingredients without a food cannot be saved; `import_recipe`
+                        // reports them as skipped
+                        let food = ingredient.food.take()?;
+                        Some((food, ingredient))
+                    })
+                    .enumerate()
+                    .map(|(order, (food, ingredient))| CreateStepIngredientRequest {
+                        food,
+                        unit: ingredient.unit,
+                        amount: ingredient.amount.to_string(),
+                        note: ingredient.note,
+                        order: order as i32,
+                        is_header: ingredient.is_header,
+                        no_amount: ingredient.no_amount,
+                    })
+                    .collect(),
+                time: None,
+                order: step_index as i32,
+            })
+            .collect();
+
+        CreateRecipeRequest {
+            name: source.name,
+            description: source.description,
+            servings: source.servings,
+            working_time: source.working_time.unwrap_or(0),
+            waiting_time: source.waiting_time.unwrap_or(0),
+            source_url: source.source_url,
+            keywords,
+            steps,
+        }
+    }
+}
+
+/// Filters for `/api/recipe/` searches.
+///
+/// Keyword, food and book filters take Tandoor IDs and come in four flavours, as in
+/// Tandoor's own search: `or` (any of), `and` (all of), `or_not` (none of) and
+/// `and_not` (not all of). Empty lists and `None` fields are not sent.
+///
+/// ```
+/// use chrono::NaiveDate;
+/// use mcp_tandoor::client::types::{DateFilter, RatingFilter, RecipeSearchFilter};
+///
+/// let filter = RecipeSearchFilter {
+///     keywords_and: vec![1, 2],
+///     rating: Some(RatingFilter::AtLeast(4)),
+///     cooked_on: Some(DateFilter::OnOrBefore(NaiveDate::from_ymd_opt(2026, 9, 30).unwrap())),
+///     ..Default::default()
+/// };
+/// assert_eq!(
+///     filter.to_query_string(),
+///     "keywords_and=1&keywords_and=2&rating=4&cookedon=-2026-09-30"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecipeSearchFilter {
+    /// Free-text search over names, descriptions and more
+    pub query: Option<String>,
+    pub keywords_or: Vec<i32>,
+    pub keywords_and: Vec<i32>,
+    pub keywords_or_not: Vec<i32>,
+    pub keywords_and_not: Vec<i32>,
+    pub foods_or: Vec<i32>,
+    pub foods_and: Vec<i32>,
+    pub foods_or_not: Vec<i32>,
+    pub foods_and_not: Vec<i32>,
+    pub books_or: Vec<i32>,
+    pub books_and: Vec<i32>,
+    pub books_or_not: Vec<i32>,
+    pub books_and_not: Vec<i32>,
+    /// The current user's rating
+    pub rating: Option<RatingFilter>,
+    /// When the recipe was last cooked; recipes never cooked never match
+    pub cooked_on: Option<DateFilter>,
+    /// When the recipe was created
+    pub created_on: Option<DateFilter>,
+    /// Only internal recipes (`true`) rather than external ones
+    pub internal: Option<bool>,
+    /// Return results in random order
+    pub random: bool,
+    pub sort_order: Option<RecipeSortOrder>,
+    pub sort_descending: bool,
+}
+
+impl RecipeSearchFilter {
+    /// Search by text only, as the plain recipe search does.
+    pub fn query(query: impl Into<String>) -> Self {
+        Self {
+            query: Some(query.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Query parameters in the form `/api/recipe/` expects, repeating list parameters.
+    pub fn to_query_pairs(&self) -> Vec<(&'static str, String)> {
+        let mut pairs = Vec::new();
+        if let Some(query) = &self.query {
+            pairs.push(("query", query.clone()));
+        }
+
+        let lists: [(&'static str, &Vec<i32>); 12] = [
+            ("keywords_or", &self.keywords_or),
+            ("keywords_and", &self.keywords_and),
+            ("keywords_or_not", &self.keywords_or_not),
+            ("keywords_and_not", &self.keywords_and_not),
+            ("foods_or", &self.foods_or),
+            ("foods_and", &self.foods_and),
+            ("foods_or_not", &self.foods_or_not),
+            ("foods_and_not", &self.foods_and_not),
+            ("books_or", &self.books_or),
+            ("books_and", &self.books_and),
+            ("books_or_not", &self.books_or_not),
+            ("books_and_not", &self.books_and_not),
+        ];
+        for (name, ids) in lists {
+            pairs.extend(ids.iter().map(|id| (name, id.to_string())));
+        }
+
+        if let Some(rating) = self.rating {
+            pairs.push(("rating", rating.to_param()));
+        }
+        if let Some(cooked_on) = self.cooked_on {
+            pairs.push(("cookedon", cooked_on.to_param()));
+        }
+        if let Some(created_on) = self.created_on {
+            pairs.push(("createdon", created_on.to_param()));
+        }
+        if let Some(internal) = self.internal {
+            pairs.push(("internal", internal.to_string()));
+        }
+        if self.random {
+            pairs.push(("random", "true".to_string()));
+        }
+        if let Some(order) = self.sort_order {
+            let prefix = if self.sort_descending { "-" } else { "" };
+            pairs.push(("sort_order", format!("{prefix}{}", order.as_str())));
+        }
+        pairs
+    }
+
+    /// The query parameters URL-encoded and joined with `&`.
+    pub fn to_query_string(&self) -> String {
+        self.to_query_pairs()
+            .into_iter()
+            .map(|(name, value)| format!("{name}={}", urlencoding::encode(&value)))
+            .collect::<Vec<_>>()
+            .join("&")
+    }
+}
+
+/// Rating filter; Tandoor sends these as `rating=4` (at least), `rating=-2` (at most,
+/// rated recipes only) and `rating=0` (unrated).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RatingFilter {
+    AtLeast(u8),
+    AtMost(u8),
+    Unrated,
+}
+
+impl RatingFilter {
+    fn to_param(self) -> String {
+        match self {
+            Self::AtLeast(rating) => rating.to_string(),
+            Self::AtMost(rating) => format!("-{rating}"),
+            Self::Unrated => "0".to_string(),
+        }
+    }
+}
+
+/// Date filter; Tandoor takes `YYYY-MM-DD` for on-or-after and `-YYYY-MM-DD` for on-or-before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateFilter {
+    OnOrAfter(NaiveDate),
+    OnOrBefore(NaiveDate),
+}
+
+impl DateFilter {
+    fn to_param(self) -> String {
+        match self {
+            Self::OnOrAfter(date) => date.format("%Y-%m-%d").to_string(),
+            Self::OnOrBefore(date) => date.format("-%Y-%m-%d").to_string(),
+        }
+    }
+}
+
+/// Sort orders `/api/recipe/` accepts in `sort_order`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecipeSortOrder {
+    /// Search relevance
+    Score,
+    Name,
+    Rating,
+    LastCooked,
+    TimesCooked,
+    CreatedAt,
+    LastViewed,
+}
+
+impl RecipeSortOrder {
+    pub const ALL: [Self; 7] = [
+        Self::Score,
+        Self::Name,
+        Self::Rating,
+        Self::LastCooked,
+        Self::TimesCooked,
+        Self::CreatedAt,
+        Self::LastViewed,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Score => "score",
+            Self::Name => "name",
+            Self::Rating => "rating",
+            Self::LastCooked => "lastcooked",
+            Self::TimesCooked => "times_cooked",
+            Self::CreatedAt => "created_at",
+            Self::LastViewed => "lastviewed",
+        }
+    }
+}
+
+impl std::str::FromStr for RecipeSortOrder {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|order| order.as_str().eq_ignore_ascii_case(value.trim()))
+            .ok_or_else(|| {
+                let known: Vec<_> = Self::ALL.iter().map(|order| order.as_str()).collect();
+                format!(
+                    "Unknown sort order '{value}', expected one of: {}",
+                    known.join(", ")
+                )
+            })
+    }
+}
+
 /// Standard paginated response wrapper used by most Tandoor API endpoints.
 ///
 /// This follows Django REST framework pagination format.
@@ -426,6 +837,8 @@ pub struct CreateRecipeRequest {
     pub servings: Option<i32>,
     pub working_time: i32,
     pub waiting_time: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
     pub keywords: Vec<CreateKeywordRequest>,
     pub steps: Vec<CreateStepRequest>,
 }
@@ -465,11 +878,49 @@ pub struct CreateUnitRequest {
     pub name: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSupermarketRequest {
+    pub name: String,
+    pub description: String,
+}
+
+/// A supermarket category by name. Tandoor reuses an existing category with this name.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSupermarketCategoryRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateSupermarketCategoryRelationRequest {
+    pub supermarket: i32,
+    pub category: CreateSupermarketCategoryRequest,
+    pub order: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateSupermarketCategoryRelationRequest {
+    pub order: i32,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateShoppingListEntryRequest {
     pub food: i32,
     pub unit: Option<i32>,
     pub amount: f64,
+    /// Shopping list recipe to group this entry under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub list_recipe: Option<i32>,
+    /// Recipe ingredient this entry comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ingredient: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateShoppingListRecipeRequest {
+    pub recipe: i32,
+    pub servings: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mealplan: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -482,6 +933,23 @@ pub struct CreateMealPlanRequest {
     pub note: Option<String>,
 }
 
+/// Fields to change on a meal plan; `None` leaves a field as it is.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateMealPlanRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub servings: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<chrono::NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meal_type: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateCookLogRequest {
     pub recipe: i32,
@@ -492,12 +960,18 @@ pub struct CreateCookLogRequest {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateFoodRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub food_onhand: Option<bool>,
+    /// Category by name, created if missing; `Some(None)` removes the category
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supermarket_category: Option<Option<CreateSupermarketCategoryRequest>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateShoppingListEntryRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub checked: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub amount: Option<f64>,
 }
 
@@ -511,6 +985,57 @@ pub struct UpdateRecipeKeywordsRequest {
     pub keywords: Vec<CreateKeywordRequest>,
 }
 
+/// Partial recipe update sent with PATCH; only the fields that are set change.
+///
+/// When `steps` is set it replaces the recipe's whole step list: steps and ingredients
+/// sent with an `id` are updated in place, ones without an `id` are created, and
+/// existing ones left out are removed.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct UpdateRecipeRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub servings: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_time: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_time: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub private: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steps: Option<Vec<UpdateStepRequest>>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateStepRequest {
+    /// Existing step to update; `None` creates a new step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    pub name: String,
+    pub instruction: String,
+    pub ingredients: Vec<UpdateStepIngredientRequest>,
+    pub time: Option<i32>,
+    pub order: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateStepIngredientRequest {
+    /// Existing ingredient to update; `None` creates a new ingredient
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    pub food: CreateFoodRequest,
+    pub unit: Option<CreateUnitRequest>,
+    pub amount: String,
+    pub note: Option<String>,
+    pub order: i32,
+    pub is_header: bool,
+    pub no_amount: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct RecipeBook {
     pub id: i32,
@@ -0,0 +1,555 @@
+//! Deterministic parser for free-text ingredient and shopping requests.
+//!
+//! Turns text like `"2 lbs chicken thighs, a dozen eggs, 500g flour"` into structured
+//! [`ParsedIngredient`]s with a quantity, a canonical unit, a food name and an optional note.
+//! The parser does no network access; resolving the results against Tandoor's foods and
+//! units is left to the caller.
+//!
+//! ## Supported Quantities
+//!
+//! - Integers and decimals: `2`, `1.5`
+//! - Fractions and mixed numbers: `1/2`, `1 1/2`, `½`, `1½`
+//! - Ranges (the upper bound is used): `2-3`
+//! - Number words: `a`, `an`, `one` .. `twelve`, `half`, `a couple`, `a dozen`
+//! - Multipliers: `2x milk`, `3 x yogurt`
+//!
+//! ## Example
+//!
+//! ```
+//! use mcp_tandoor::ingredient_parser::parse_request;
+//!
+//! let items = parse_request("2 lbs chicken thighs, a dozen eggs, 500g flour");
+//! assert_eq!(items.len(), 3);
+//! assert_eq!(items[0].amount, Some(2.0));
+//! assert_eq!(items[0].unit.as_deref(), Some("lb"));
+//! assert_eq!(items[1].food, "eggs");
+//! assert_eq!(items[1].amount, Some(12.0));
+//! ```
+
+use serde::Serialize;
+
+/// A single ingredient parsed from free text.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ParsedIngredient {
+    /// The text this ingredient was parsed from
+    pub original: String,
+    /// Parsed quantity, if one was given
+    pub amount: Option<f64>,
+    /// Canonical unit name (see [`normalize_unit`]), if one was given
+    pub unit: Option<String>,
+    /// Food name with quantity, unit and note removed
+    pub food: String,
+    /// Preparation or other notes, e.g. from parentheses
+    pub note: Option<String>,
+}
+
+/// Known units as (canonical name, accepted spellings).
+const UNITS: &[(&str, &[&str])] = &[
+    ("g", &["g", "gr", "gram", "grams", "gramme", "grammes"]),
+    (
+        "kg",
+        &["kg", "kgs", "kilo", "kilos", "kilogram", "kilograms"],
+    ),
+    ("mg", &["mg", "milligram", "milligrams"]),
+    ("lb", &["lb", "lbs", "pound", "pounds"]),
+    ("oz", &["oz", "ounce", "ounces"]),
+    (
+        "ml",
+        &[
+            "ml",
+            "milliliter",
+            "milliliters",
+            "millilitre",
+            "millilitres",
+        ],
+    ),
+    ("l", &["l", "liter", "liters", "litre", "litres"]),
+    ("tsp", &["tsp", "tsps", "teaspoon", "teaspoons", "t"]),
+    (
+        "tbsp",
+        &["tbsp", "tbsps", "tbs", "tablespoon", "tablespoons", "T"],
+    ),
+    ("fl oz", &["fl oz", "fl. oz", "fluid ounce", "fluid ounces"]),
+    ("cup", &["cup", "cups", "c"]),
+    ("pint", &["pint", "pints", "pt"]),
+    ("quart", &["quart", "quarts", "qt"]),
+    ("gallon", &["gallon", "gallons", "gal"]),
+    ("clove", &["clove", "cloves"]),
+    ("can", &["can", "cans", "tin", "tins"]),
+    ("bunch", &["bunch", "bunches"]),
+    (
+        "package",
+        &[
+            "package", "packages", "pack", "packs", "pkg", "packet", "packets",
+        ],
+    ),
+    ("bag", &["bag", "bags"]),
+    ("bottle", &["bottle", "bottles"]),
+    ("jar", &["jar", "jars"]),
+    ("box", &["box", "boxes"]),
+    ("carton", &["carton", "cartons"]),
+    ("slice", &["slice", "slices"]),
+    ("piece", &["piece", "pieces", "pc", "pcs"]),
+    ("head", &["head", "heads"]),
+    ("stick", &["stick", "sticks"]),
+    ("loaf", &["loaf", "loaves"]),
+    ("pinch", &["pinch", "pinches"]),
+    ("dash", &["dash", "dashes"]),
+];
+
+/// Words that describe the previous item rather than start a new one, e.g. the
+/// "boneless" in "2 lbs chicken thighs, boneless".
+const DESCRIPTORS: &[&str] = &[
+    "boneless",
+    "skinless",
+    "chopped",
+    "diced",
+    "minced",
+    "sliced",
+    "grated",
+    "shredded",
+    "peeled",
+    "crushed",
+    "melted",
+    "softened",
+    "fresh",
+    "frozen",
+    "ripe",
+    "optional",
+    "to taste",
+    "divided",
+    "room temperature",
+];
+
+/// Map a unit spelling to its canonical name, e.g. `"Pounds"` to `"lb"`.
+///
+/// Matching is case-insensitive except for the single-letter `t`/`T`
+/// (teaspoon/tablespoon) spellings, which are told apart by case.
+pub fn normalize_unit(token: &str) -> Option<&'static str> {
+    let token = token.trim().trim_end_matches('.');
+    if token == "T" {
+        return Some("tbsp");
+    }
+    if token == "t" {
+        return Some("tsp");
+    }
+    let lower = token.to_lowercase();
+    UNITS
+        .iter()
+        .find(|(_, spellings)| {
+            spellings
+                .iter()
+                .any(|s| *s != "t" && *s != "T" && s.eq_ignore_ascii_case(&lower))
+        })
+        .map(|(canonical, _)| *canonical)
+}
+
+/// Parse a free-text request containing one or more ingredients.
+///
+/// Items are separated by commas, semicolons, newlines, or "and" when it is followed
+/// by a quantity. Empty items are skipped.
+pub fn parse_request(text: &str) -> Vec<ParsedIngredient> {
+    let mut items: Vec<ParsedIngredient> = Vec::new();
+
+    for chunk in split_items(text) {
+        let is_descriptor = DESCRIPTORS
+            .iter()
+            .any(|d| d.eq_ignore_ascii_case(chunk.trim()));
+        if is_descriptor {
+            if let Some(previous) = items.last_mut() {
+                let descriptor = chunk.trim().to_lowercase();
+                previous.note = Some(match previous.note.take() {
+                    Some(note) => format!("{note}, {descriptor}"),
+                    None => descriptor,
+                });
+                continue;
+            }
+        }
+
+        if let Some(item) = parse_line(&chunk) {
+            items.push(item);
+        }
+    }
+
+    items
+}
+
+/// Parse a single ingredient line such as `"1 1/2 cups flour (sifted)"`.
+///
+/// Returns `None` if the line contains no food name or starts with an amount that is
+/// not positive, such as `"-1/2 cup milk"`.
+pub fn parse_line(line: &str) -> Option<ParsedIngredient> {
+    let original = line.trim().to_string();
+    let (text, mut notes) = extract_parentheses(&original);
+
+    let mut tokens: Vec<String> = tokenize(&text);
+    // Drop list bullets
+    while tokens
+        .first()
+        .is_some_and(|t| matches!(t.as_str(), "-" | "*" | "•"))
+    {
+        tokens.remove(0);
+    }
+    if tokens.first().is_some_and(|t| is_non_positive_number(t)) {
+        return None;
+    }
+
+    let mut amount = parse_quantity(&mut tokens);
+
+    // "2 x milk" / "2x milk"
+    if amount.is_some() && tokens.first().is_some_and(|t| t.eq_ignore_ascii_case("x")) {
+        tokens.remove(0);
+    }
+
+    let mut unit = None;
+    if let Some((canonical, consumed)) = match_unit(&tokens) {
+        // A bare "c" or "t" without a quantity is almost certainly part of the food name
+        if amount.is_some() || consumed > 1 || tokens[0].len() > 1 {
+            unit = Some(canonical.to_string());
+            tokens.drain(..consumed);
+        }
+    }
+
+    // "a dozen eggs" / "2 dozen eggs"
+    if tokens
+        .first()
+        .is_some_and(|t| t.eq_ignore_ascii_case("dozen"))
+    {
+        tokens.remove(0);
+        amount = Some(amount.unwrap_or(1.0) * 12.0);
+    }
+
+    if tokens.first().is_some_and(|t| t.eq_ignore_ascii_case("of")) {
+        tokens.remove(0);
+    }
+
+    let food_text = tokens.join(" ");
+    let (food, trailing_note) = split_trailing_note(&food_text);
+    if let Some(note) = trailing_note {
+        notes.push(note);
+    }
+
+    let food = food
+        .trim()
+        .trim_end_matches(['.', '!', '?'])
+        .trim()
+        .to_string();
+    if food.is_empty() {
+        return None;
+    }
+
+    Some(ParsedIngredient {
+        original,
+        amount,
+        unit,
+        food,
+        note: if notes.is_empty() {
+            None
+        } else {
+            Some(notes.join(", "))
+        },
+    })
+}
+
+/// Split a request into item chunks on `,`, `;`, newlines and quantity-led "and".
+fn split_items(text: &str) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+
+    for c in text.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                current.push(c);
+            }
+            ')' => {
+                depth = depth.saturating_sub(1);
+                current.push(c);
+            }
+            ',' | ';' | '\n' if depth == 0 => {
+                chunks.push(std::mem::take(&mut current));
+            }
+            _ => current.push(c),
+        }
+    }
+    chunks.push(current);
+
+    chunks
+        .into_iter()
+        .flat_map(|chunk| split_on_and(&chunk))
+        .map(|chunk| {
+            let trimmed = chunk.trim();
+            let trimmed = trimmed
+                .strip_prefix("and ")
+                .or_else(|| trimmed.strip_prefix("And "))
+                .unwrap_or(trimmed);
+            trimmed.trim().to_string()
+        })
+        .filter(|chunk| !chunk.is_empty())
+        .collect()
+}
+
+/// Split on " and " only when the following word starts a quantity, so that
+/// "mac and cheese" stays together but "eggs and 2 onions" is split.
+fn split_on_and(chunk: &str) -> Vec<String> {
+    let words: Vec<&str> = chunk.split_whitespace().collect();
+    let mut parts = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+
+    for (i, word) in words.iter().enumerate() {
+        if word.eq_ignore_ascii_case("and") && !current.is_empty() {
+            let mut lookahead: Vec<String> = words[i + 1..].iter().map(|w| w.to_string()).collect();
+            if parse_quantity(&mut lookahead).is_some() {
+                parts.push(current.join(" "));
+                current.clear();
+                continue;
+            }
+        }
+        current.push(word);
+    }
+    if !current.is_empty() {
+        parts.push(current.join(" "));
+    }
+    parts
+}
+
+/// Remove parenthesised text and return it as notes.
+fn extract_parentheses(text: &str) -> (String, Vec<String>) {
+    let mut notes = Vec::new();
+    let mut remaining = String::new();
+    let mut depth = 0usize;
+    let mut current_note = String::new();
+
+    for c in text.chars() {
+        match c {
+            '(' => {
+                if depth > 0 {
+                    current_note.push(c);
+                }
+                depth += 1;
+            }
+            ')' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    let note = current_note.trim().to_string();
+                    if !note.is_empty() {
+                        notes.push(note);
+                    }
+                    current_note.clear();
+                    remaining.push(' ');
+                } else {
+                    current_note.push(c);
+                }
+            }
+            _ if depth > 0 => current_note.push(c),
+            _ => remaining.push(c),
+        }
+    }
+
+    (remaining, notes)
+}
+
+/// Split a food name from a trailing note introduced by " - " or "for".
+fn split_trailing_note(text: &str) -> (String, Option<String>) {
+    if let Some((food, note)) = text.split_once(" - ") {
+        let note = note.trim();
+        return (
+            food.to_string(),
+            (!note.is_empty()).then(|| note.to_string()),
+        );
+    }
+    // ASCII lowercasing keeps byte offsets, so the index is valid in `text`
+    if let Some(index) = text.to_ascii_lowercase().find(" for ") {
+        let note = text[index + 1..].trim();
+        return (text[..index].to_string(), Some(note.to_string()));
+    }
+    (text.to_string(), None)
+}
+
+/// Split text into whitespace tokens, separating numbers glued to units ("500g")
+/// and unicode fractions glued to numbers ("1½").
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for word in text.split_whitespace() {
+        let split_at = word
+            .char_indices()
+            .find(|(_, c)| !(c.is_ascii_digit() || *c == '.' || *c == '/' || *c == '-'))
+            .map(|(i, _)| i);
+
+        match split_at {
+            Some(i) if i > 0 && word[..i].chars().any(|c| c.is_ascii_digit()) => {
+                let (number, rest) = word.split_at(i);
+                tokens.push(number.to_string());
+                let mut rest_chars = rest.chars();
+                let first = rest_chars.next();
+                if first.is_some_and(unicode_fraction_value_is_some) {
+                    tokens.push(first.unwrap().to_string());
+                    let tail: String = rest_chars.collect();
+                    if !tail.is_empty() {
+                        tokens.push(tail);
+                    }
+                } else {
+                    tokens.push(rest.to_string());
+                }
+            }
+            _ => tokens.push(word.to_string()),
+        }
+    }
+    tokens
+}
+
+fn unicode_fraction_value_is_some(c: char) -> bool {
+    unicode_fraction(c).is_some()
+}
+
+fn unicode_fraction(c: char) -> Option<f64> {
+    match c {
+        '½' => Some(0.5),
+        '⅓' => Some(1.0 / 3.0),
+        '⅔' => Some(2.0 / 3.0),
+        '¼' => Some(0.25),
+        '¾' => Some(0.75),
+        '⅕' => Some(0.2),
+        '⅛' => Some(0.125),
+        '⅜' => Some(0.375),
+        '⅝' => Some(0.625),
+        '⅞' => Some(0.875),
+        _ => None,
+    }
+}
+
+/// Parse a single numeric token: "2", "1.5", "1/2", "½", "2-3".
+fn parse_number(token: &str) -> Option<f64> {
+    let mut chars = token.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if let Some(value) = unicode_fraction(c) {
+            return Some(value);
+        }
+    }
+
+    if let Some((low, high)) = token.split_once('-') {
+        if !low.is_empty() && !high.is_empty() {
+            let low = parse_number(low)?;
+            let high = parse_number(high)?;
+            return Some(low.max(high));
+        }
+    }
+
+    if let Some((numerator, denominator)) = token.split_once('/') {
+        let numerator: f64 = numerator.parse().ok()?;
+        let denominator: f64 = denominator.parse().ok()?;
+        if denominator == 0.0 {
+            return None;
+        }
+        return Some(numerator / denominator).filter(|v| v.is_finite() && *v > 0.0);
+    }
+
+    token
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite() && *v > 0.0)
+}
+
+/// Whether `token` is a number that is zero or negative, e.g. "0" or "-1/2".
+fn is_non_positive_number(token: &str) -> bool {
+    let value = match token.split_once('/') {
+        Some((numerator, denominator)) => numerator
+            .parse::<f64>()
+            .ok()
+            .zip(denominator.parse::<f64>().ok())
+            .map(|(numerator, denominator)| numerator / denominator),
+        None => token.parse::<f64>().ok(),
+    };
+    value.is_some_and(|v| v.is_nan() || v <= 0.0)
+}
+
+fn number_word(token: &str) -> Option<f64> {
+    let value = match token.to_lowercase().as_str() {
+        "a" | "an" | "one" => 1.0,
+        "two" => 2.0,
+        "three" => 3.0,
+        "four" => 4.0,
+        "five" => 5.0,
+        "six" => 6.0,
+        "seven" => 7.0,
+        "eight" => 8.0,
+        "nine" => 9.0,
+        "ten" => 10.0,
+        "eleven" => 11.0,
+        "twelve" => 12.0,
+        "half" => 0.5,
+        "couple" => 2.0,
+        "few" => 3.0,
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Consume a leading quantity from `tokens`, returning its value.
+fn parse_quantity(tokens: &mut Vec<String>) -> Option<f64> {
+    let first = tokens.first()?.clone();
+
+    if let Some(mut value) = parse_number(&first) {
+        tokens.remove(0);
+        // Mixed numbers: "1 1/2", "1 ½"
+        if value.fract() == 0.0 {
+            if let Some(next) = tokens.first() {
+                if next.contains('/') || next.chars().all(unicode_fraction_value_is_some) {
+                    if let Some(fraction) = parse_number(next) {
+                        if fraction < 1.0 {
+                            value += fraction;
+                            tokens.remove(0);
+                        }
+                    }
+                }
+            }
+        }
+        return Some(value);
+    }
+
+    let value = number_word(&first)?;
+    // "a" / "an" are only quantities when followed by something to count
+    if tokens.len() < 2 {
+        return None;
+    }
+    tokens.remove(0);
+
+    // "a couple of", "a few", "half a dozen"
+    if matches!(first.to_lowercase().as_str(), "a" | "an" | "half") {
+        if let Some(next) = tokens.first() {
+            if let Some(inner) = number_word(next).filter(|_| !next.eq_ignore_ascii_case("a")) {
+                tokens.remove(0);
+                return Some(if first.eq_ignore_ascii_case("half") {
+                    value * inner
+                } else {
+                    inner
+                });
+            }
+            if first.eq_ignore_ascii_case("half")
+                && (next.eq_ignore_ascii_case("a") || next.eq_ignore_ascii_case("an"))
+            {
+                tokens.remove(0);
+            }
+        }
+    }
+
+    Some(value)
+}
+
+/// Match a unit at the start of `tokens`, returning the canonical unit and how many
+/// tokens it spans.
+fn match_unit(tokens: &[String]) -> Option<(&'static str, usize)> {
+    if tokens.len() >= 2 {
+        let pair = format!("{} {}", tokens[0], tokens[1]);
+        if let Some(unit) = normalize_unit(&pair) {
+            return Some((unit, 2));
+        }
+    }
+    let first = tokens.first()?;
+    // Never treat the last remaining word as a unit; it is the food
+    if tokens.len() < 2 {
+        return None;
+    }
+    normalize_unit(first).map(|unit| (unit, 1))
+}
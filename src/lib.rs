@@ -13,13 +13,28 @@
 //! The [`server`] module implements an MCP server that exposes Tandoor functionality
 //! as standardized tools that AI assistants can use.
 //!
+//! ## Ingredient Parser Module
+//!
+//! The [`ingredient_parser`] module turns free-text ingredient lines and shopping
+//! requests into structured quantities, units and foods without any network access.
+//!
+//! ## Units Module
+//!
+//! The [`units`] module converts between metric and US kitchen units of mass and
+//! volume, so scaled amounts can be rounded to handy units and quantities added up.
+//!
+//! ## Meal Templates Module
+//!
+//! The [`meal_templates`] module keeps named meal plan templates in a local file, so a
+//! week of meals can be saved once and planned again for any later week.
+//!
 //! ## Quick Start
 //!
 //! ```no_run
 //! use mcp_tandoor::{TandoorClient, TandoorMcpServer};
 //!
 //! // Use the client directly
-//! let mut client = TandoorClient::new("http://localhost:8080".to_string());
+//! let client = TandoorClient::new("http://localhost:8080".to_string());
 //!
 //! // Or create an MCP server
 //! let server = TandoorMcpServer::new_with_credentials(
@@ -30,7 +45,10 @@
 //! ```
 
 pub mod client;
+pub mod ingredient_parser;
+pub mod meal_templates;
 pub mod server;
+pub mod units;
 
 pub use client::TandoorClient;
 pub use server::TandoorMcpServer;
@@ -10,6 +10,12 @@
 //! - `TANDOOR_USERNAME`: Tandoor username for authentication (default: admin)
 //! - `TANDOOR_PASSWORD`: Tandoor password for authentication (default: admin)
 //! - `TANDOOR_AUTH_TOKEN`: Pre-set auth token to bypass username/password auth (avoids rate limiting)
+//! - `TANDOOR_TOKEN_CACHE`: Token cache file, or `off` to disable
+//!   (default: `$XDG_STATE_HOME/mcp-tandoor/tokens.json`)
+//! - `TANDOOR_MEAL_TEMPLATES`: Meal plan template file, or `off` to disable
+//!   (default: `$XDG_DATA_HOME/mcp-tandoor/meal-templates.json`)
+//! - `MCP_TRANSPORT`: Transport to serve on: `stdio`, `sse`, `streamable-http` or `http` (default: stdio)
+//! - `BIND_ADDR`: Listen address for the network transports (default: 127.0.0.1:3001)
 //! - `RUST_LOG`: Logging level (info, debug, trace, etc.)
 //!
 //! ## Usage
@@ -20,12 +26,87 @@
 //! TANDOOR_PASSWORD=your_password \
 //! cargo run
 //! ```
+//!
+//! ## Transports
+//!
+//! - `stdio`: one client over stdin/stdout, spawned by the client itself
+//! - `sse`: legacy HTTP+SSE transport, clients connect to `/sse` and post to `/message`
+//! - `streamable-http`: MCP streamable HTTP transport at `/mcp`
+//! - `http`: both network transports on the same listener
+//!
+//! The network transports share a single authenticated [`TandoorMcpServer`] between every
+//! connected client, so each new session reuses the cached token instead of logging in again.
 
+use mcp_tandoor::client::TokenStore;
+use mcp_tandoor::meal_templates::MealTemplateStore;
 use mcp_tandoor::server::TandoorMcpServer;
+use rmcp::transport::sse_server::{SseServer, SseServerConfig};
+use rmcp::transport::streamable_http_server::{
+    session::local::LocalSessionManager, StreamableHttpServerConfig, StreamableHttpService,
+};
 use rmcp::ServiceExt;
 use std::env;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// Token cache selected with `TANDOOR_TOKEN_CACHE`: a file path, `off` to disable,
+/// or unset for the default XDG state location.
+fn token_store_from_env() -> Option<TokenStore> {
+    match env::var("TANDOOR_TOKEN_CACHE") {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "" | "off" | "false" | "0" | "none" => None,
+            _ => Some(TokenStore::new(value.trim())),
+        },
+        Err(_) => TokenStore::default_location(),
+    }
+}
+
+/// Meal plan template file selected with `TANDOOR_MEAL_TEMPLATES`: a file path, `off` to
+/// disable templates, or unset for the default XDG data location.
+fn meal_template_store_from_env() -> Option<MealTemplateStore> {
+    match env::var("TANDOOR_MEAL_TEMPLATES") {
+        Ok(value) => match value.trim().to_lowercase().as_str() {
+            "" | "off" | "false" | "0" | "none" => None,
+            _ => Some(MealTemplateStore::new(value.trim())),
+        },
+        Err(_) => MealTemplateStore::default_location(),
+    }
+}
+
+/// Transport the MCP server is exposed on, selected with `MCP_TRANSPORT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    Stdio,
+    Sse,
+    StreamableHttp,
+    Http,
+}
+
+impl Transport {
+    fn from_env() -> anyhow::Result<Self> {
+        let value = env::var("MCP_TRANSPORT").unwrap_or_else(|_| "stdio".to_string());
+        match value.to_lowercase().as_str() {
+            "stdio" => Ok(Self::Stdio),
+            "sse" => Ok(Self::Sse),
+            "streamable-http" | "streamable_http" => Ok(Self::StreamableHttp),
+            "http" => Ok(Self::Http),
+            other => anyhow::bail!(
+                "Unknown MCP_TRANSPORT '{other}'. Expected one of: stdio, sse, streamable-http, http"
+            ),
+        }
+    }
+
+    fn serves_sse(self) -> bool {
+        matches!(self, Self::Sse | Self::Http)
+    }
+
+    fn serves_streamable_http(self) -> bool {
+        matches!(self, Self::StreamableHttp | Self::Http)
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Load .env file if present (silently ignored if not found)
@@ -41,6 +122,8 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     // Get configuration from environment variables
+    let transport = Transport::from_env()?;
+
     let base_url =
         env::var("TANDOOR_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
 
@@ -56,12 +139,19 @@ async fn main() -> anyhow::Result<()> {
         password.clone(),
     );
 
+    if let Some(store) = token_store_from_env() {
+        tracing::info!("Caching auth tokens in {}", store.path().display());
+        server.set_token_store(store);
+    }
+
+    if let Some(store) = meal_template_store_from_env() {
+        tracing::info!("Saving meal plan templates in {}", store.path().display());
+        server.set_meal_template_store(store);
+    }
+
     if let Ok(token) = env::var("TANDOOR_AUTH_TOKEN") {
         tracing::info!("Using pre-set token from TANDOOR_AUTH_TOKEN");
-        server
-            .set_global_auth_token(token)
-            .await
-            .expect("Failed to set auth token");
+        server.set_auth_token(token);
     } else if let Err(e) = server
         .authenticate(username.clone(), password.clone())
         .await
@@ -87,15 +177,82 @@ async fn main() -> anyhow::Result<()> {
         }
     }
 
-    tracing::info!("Starting Tandoor MCP Server on stdio transport");
+    if transport == Transport::Stdio {
+        tracing::info!("Starting Tandoor MCP Server on stdio transport");
 
-    // Run server over stdio (stdin/stdout)
-    let service = server.serve(rmcp::transport::io::stdio()).await?;
+        // Run server over stdio (stdin/stdout)
+        let service = server.serve(rmcp::transport::io::stdio()).await?;
 
-    // Wait until the client disconnects
-    service.waiting().await?;
+        // Wait until the client disconnects
+        service.waiting().await?;
+    } else {
+        let bind_addr: SocketAddr = env::var("BIND_ADDR")
+            .unwrap_or_else(|_| "127.0.0.1:3001".to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("Invalid BIND_ADDR: {e}"))?;
+
+        serve_http(server, transport, bind_addr).await?;
+    }
 
     tracing::info!("Tandoor MCP Server shutting down");
 
     Ok(())
 }
+
+/// Serve the MCP server over the network transports until Ctrl+C is received.
+///
+/// Every session is handed a clone of `server`, so all connected clients share the
+/// same Tandoor client and authentication token.
+async fn serve_http(
+    server: TandoorMcpServer,
+    transport: Transport,
+    bind_addr: SocketAddr,
+) -> anyhow::Result<()> {
+    let ct = CancellationToken::new();
+    let mut router = axum::Router::new();
+
+    if transport.serves_sse() {
+        let (sse_server, sse_router) = SseServer::new(SseServerConfig {
+            bind: bind_addr,
+            sse_path: "/sse".to_string(),
+            post_path: "/message".to_string(),
+            ct: ct.child_token(),
+            sse_keep_alive: None,
+        });
+        let sse_service = server.clone();
+        sse_server.with_service(move || sse_service.clone());
+        router = router.merge(sse_router);
+        tracing::info!("SSE transport available at http://{}/sse", bind_addr);
+    }
+
+    if transport.serves_streamable_http() {
+        let http_service = server.clone();
+        let service = StreamableHttpService::new(
+            move || Ok(http_service.clone()),
+            Arc::new(LocalSessionManager::default()),
+            StreamableHttpServerConfig::default(),
+        );
+        router = router.nest_service("/mcp", service);
+        tracing::info!(
+            "Streamable HTTP transport available at http://{}/mcp",
+            bind_addr
+        );
+    }
+
+    let listener = tokio::net::TcpListener::bind(bind_addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to bind {bind_addr}: {e}"))?;
+
+    tracing::info!("Starting Tandoor MCP Server on {}", bind_addr);
+
+    let shutdown = ct.clone();
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async move {
+            let _ = tokio::signal::ctrl_c().await;
+            tracing::info!("Received Ctrl+C, stopping network transports");
+            shutdown.cancel();
+        })
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+//! Named meal plan templates kept in a local JSON file.
+//!
+//! A [`MealTemplate`] is a rotation such as "taco Tuesday" or the usual weekday lunches:
+//! meals by day offset from the start of the template, meal type and servings, detached
+//! from any particular week. Templates are saved from existing meal plans and re-applied
+//! to any start date by the server's `copy_meal_plans` tool. Like the
+//! [`units`](crate::units) module, this module does no network access; the templates
+//! live only on this machine, not in Tandoor.
+//!
+//! The default location follows the XDG base directory spec:
+//! `$XDG_DATA_HOME/mcp-tandoor/meal-templates.json`, falling back to
+//! `$HOME/.local/share/mcp-tandoor/meal-templates.json`.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use mcp_tandoor::meal_templates::{MealTemplateStore, TemplateMeal};
+//!
+//! # fn example() -> anyhow::Result<()> {
+//! let store = MealTemplateStore::new("/tmp/meal-templates.json");
+//! let taco_tuesday = TemplateMeal {
+//!     day_offset: 1,
+//!     meal_type_id: 3,
+//!     meal_type: "Dinner".to_string(),
+//!     recipe_id: Some(12),
+//!     recipe_name: Some("Tacos".to_string()),
+//!     title: String::new(),
+//!     servings: 4,
+//!     note: String::new(),
+//! };
+//! store.save("Weeknights", 7, vec![taco_tuesday], false)?;
+//! assert_eq!(store.get("weeknights")?.unwrap().meals.len(), 1);
+//! # Ok(())
+//! # }
+//! ```
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::client::types::MealPlan;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TemplateFile {
+    #[serde(default)]
+    templates: Vec<MealTemplate>,
+}
+
+/// A named set of meals to plan again, relative to the day the template is applied on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MealTemplate {
+    pub name: String,
+    /// Number of days the template spans, e.g. 7 for a week
+    pub days: u32,
+    pub meals: Vec<TemplateMeal>,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// One meal of a [`MealTemplate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateMeal {
+    /// Days after the start of the template, 0 for its first day
+    pub day_offset: u32,
+    pub meal_type_id: i32,
+    /// Meal type name when the template was saved, for display
+    pub meal_type: String,
+    pub recipe_id: Option<i32>,
+    /// Recipe name when the template was saved, for display
+    pub recipe_name: Option<String>,
+    #[serde(default)]
+    pub title: String,
+    pub servings: i32,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl TemplateMeal {
+    /// The meal `plan` describes, as an offset from `start`. Plans before `start` have no
+    /// offset and give `None`.
+    pub fn from_plan(plan: &MealPlan, start: NaiveDate) -> Option<Self> {
+        let day_offset = u32::try_from((plan.date - start).num_days()).ok()?;
+        Some(Self {
+            day_offset,
+            meal_type_id: plan.meal_type.id,
+            meal_type: plan.meal_type.name.clone(),
+            recipe_id: plan.recipe.as_ref().map(|recipe| recipe.id),
+            recipe_name: plan.recipe.as_ref().map(|recipe| recipe.name.clone()),
+            title: plan.title.clone().unwrap_or_default(),
+            servings: plan.servings,
+            note: plan.note.clone().unwrap_or_default(),
+        })
+    }
+
+    /// The date this meal falls on when its template is applied from `start`; `None` past
+    /// the last representable date.
+    pub fn date_from(&self, start: NaiveDate) -> Option<NaiveDate> {
+        start.checked_add_days(chrono::Days::new(self.day_offset.into()))
+    }
+}
+
+/// Distinguishes temporary files written at the same time by one process.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// File-backed collection of meal templates, looked up by case-insensitive name.
+///
+/// Clones share a lock, so concurrent saves and removals through them never lose
+/// each other's changes.
+#[derive(Debug, Clone)]
+pub struct MealTemplateStore {
+    path: PathBuf,
+    write_lock: Arc<Mutex<()>>,
+}
+
+impl MealTemplateStore {
+    /// Create a store backed by the file at `path`.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Arc::new(Mutex::new(())),
+        }
+    }
+
+    /// Create a store at the default XDG data location, if a home directory is known.
+    pub fn default_location() -> Option<Self> {
+        let data_dir = std::env::var_os("XDG_DATA_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| {
+                std::env::var_os("HOME")
+                    .filter(|dir| !dir.is_empty())
+                    .map(|home| PathBuf::from(home).join(".local").join("share"))
+            })?;
+
+        Some(Self::new(
+            data_dir.join("mcp-tandoor").join("meal-templates.json"),
+        ))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every saved template, sorted by name. A missing file holds no templates.
+    pub fn list(&self) -> Result<Vec<MealTemplate>> {
+        let mut templates = self.read()?.templates;
+        templates.sort_by_key(|template| template.name.to_lowercase());
+        Ok(templates)
+    }
+
+    /// The template called `name`, ignoring case.
+    pub fn get(&self, name: &str) -> Result<Option<MealTemplate>> {
+        Ok(self
+            .read()?
+            .templates
+            .into_iter()
+            .find(|template| template.name.eq_ignore_ascii_case(name.trim())))
+    }
+
+    /// Save `meals` as the template `name`. A template of the same name is replaced with
+    /// `overwrite`, and otherwise kept, giving `None`.
+    pub fn save(
+        &self,
+        name: &str,
+        days: u32,
+        meals: Vec<TemplateMeal>,
+        overwrite: bool,
+    ) -> Result<Option<MealTemplate>> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut file = self.read()?;
+        let template = MealTemplate {
+            name: name.trim().to_string(),
+            days,
+            meals,
+            saved_at: Utc::now(),
+        };
+        let exists = |existing: &MealTemplate| existing.name.eq_ignore_ascii_case(&template.name);
+        if file.templates.iter().any(exists) {
+            if !overwrite {
+                return Ok(None);
+            }
+            file.templates.retain(|existing| !exists(existing));
+        }
+        file.templates.push(template.clone());
+        self.write(&file)?;
+        Ok(Some(template))
+    }
+
+    /// Delete the template called `name`; `false` if there was none.
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        let _guard = self.write_lock.lock().unwrap();
+        let mut file = self.read()?;
+        let count = file.templates.len();
+        file.templates
+            .retain(|template| !template.name.eq_ignore_ascii_case(name.trim()));
+        if file.templates.len() == count {
+            return Ok(false);
+        }
+        self.write(&file)?;
+        Ok(true)
+    }
+
+    /// Unlike the token cache, a corrupt template file is an error rather than empty, so
+    /// saving a template never silently drops the others.
+    fn read(&self) -> Result<TemplateFile> {
+        match fs::read_to_string(&self.path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("Invalid meal template file {}", self.path.display())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(TemplateFile::default()),
+            Err(e) => Err(e).with_context(|| format!("Failed to read {}", self.path.display())),
+        }
+    }
+
+    /// Write the file atomically via a temporary file. Callers hold `write_lock`.
+    fn write(&self, file: &TemplateFile) -> Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+
+        let tmp_path = self.path.with_extension(format!(
+            "json.{}.{}.tmp",
+            std::process::id(),
+            TMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let written = fs::write(&tmp_path, serde_json::to_vec_pretty(file)?)
+            .and_then(|()| fs::rename(&tmp_path, &self.path));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e).with_context(|| format!("Failed to write {}", self.path.display()));
+        }
+
+        tracing::debug!("Saved meal templates to {}", self.path.display());
+        Ok(())
+    }
+}
@@ -18,7 +18,10 @@
 //!
 //! The server supports both credential-based authentication and pre-set tokens
 //! to work around Tandoor's strict rate limiting (10 auth requests per day).
+//! Credentials come from a per-server [`CredentialProvider`], so one process can
+//! serve several Tandoor accounts.
 
+use futures::TryStreamExt;
 use rmcp::{
     handler::server::{router::tool::ToolRouter, tool::Parameters},
     model::*,
@@ -28,13 +31,48 @@ use rmcp::{
 };
 use serde_json::json;
 use std::future::Future;
-use std::sync::Arc;
-use std::sync::OnceLock;
-use tokio::sync::Mutex;
+use std::sync::{Arc, RwLock};
 
-use crate::client::TandoorClient;
+use crate::client::types::{
+    DateFilter, Food, MealPlan, MealType, RatingFilter, Recipe, RecipeSearchFilter,
+    RecipeSortOrder, ShoppingListEntry, StepIngredient, Supermarket, Unit,
+};
+use crate::client::{CredentialProvider, Credentials, TandoorClient, TandoorError, TokenStore};
+use crate::ingredient_parser;
+use crate::meal_templates::{MealTemplate, MealTemplateStore, TemplateMeal};
+use crate::units::{self, KitchenUnit, Measure};
 
 // Parameter structs for tools
+
+/// A Tandoor object given by ID or by name, e.g. `2` or `"Corner Market"`.
+#[derive(Debug, Clone, serde::Deserialize, schemars::JsonSchema)]
+#[serde(untagged)]
+pub enum NameOrId {
+    Id(i32),
+    Name(String),
+}
+
+impl NameOrId {
+    /// Whether this refers to the object with `id` and `name`. Names match
+    /// case-insensitively, and a numeric name also matches the ID.
+    fn matches(&self, id: i32, name: &str) -> bool {
+        match self {
+            Self::Id(wanted) => *wanted == id,
+            Self::Name(wanted) => {
+                wanted.trim().parse() == Ok(id) || name.eq_ignore_ascii_case(wanted.trim())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for NameOrId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Name(name) => write!(f, "{}", name.trim()),
+        }
+    }
+}
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct SearchRecipesParams {
     #[serde(default)]
@@ -44,6 +82,49 @@ pub struct SearchRecipesParams {
     /// 1-indexed page number for pagination
     #[serde(default)]
     pub page: Option<i32>,
+    /// Keyword names every recipe must have
+    #[serde(default)]
+    pub keywords: Option<Vec<String>>,
+    /// Keyword names of which a recipe must have at least one
+    #[serde(default)]
+    pub any_keywords: Option<Vec<String>>,
+    /// Keyword names a recipe must not have
+    #[serde(default)]
+    pub exclude_keywords: Option<Vec<String>>,
+    /// Food names every recipe must use
+    #[serde(default)]
+    pub foods: Option<Vec<String>>,
+    /// Food names a recipe must not use
+    #[serde(default)]
+    pub exclude_foods: Option<Vec<String>>,
+    /// Recipe book names; recipes must be in at least one of them
+    #[serde(default)]
+    pub books: Option<Vec<String>>,
+    /// Minimum rating (1-5)
+    #[serde(default)]
+    pub min_rating: Option<u8>,
+    /// Only recipes cooked on or after this date (YYYY-MM-DD)
+    #[serde(default)]
+    pub cooked_since: Option<String>,
+    /// Only recipes not cooked on or after this date (YYYY-MM-DD), including never cooked ones
+    #[serde(default)]
+    pub not_cooked_since: Option<String>,
+    /// Only recipes created on or after this date (YYYY-MM-DD)
+    #[serde(default)]
+    pub created_since: Option<String>,
+    /// Maximum working plus waiting time in minutes
+    #[serde(default)]
+    pub max_total_time: Option<i32>,
+    /// true for only internal recipes, false for only external ones
+    #[serde(default)]
+    pub internal: Option<bool>,
+    /// Return matches in random order
+    #[serde(default)]
+    pub random: bool,
+    /// Sort order: score, name, rating, lastcooked, times_cooked, created_at or lastviewed.
+    /// Prefix with '-' for descending, e.g. "-rating".
+    #[serde(default)]
+    pub sort: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -68,11 +149,54 @@ pub struct CreateRecipeParams {
     pub cook_time: Option<i32>,
     #[serde(default)]
     pub keywords: Option<Vec<String>>,
+    /// Recipe steps with their ingredients. Use instead of `instructions`.
+    #[serde(default)]
+    pub steps: Option<Vec<RecipeStepParams>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct RecipeStepParams {
+    /// Optional step heading, e.g. "Make the sauce"
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub instruction: String,
+    /// Step time in minutes
+    #[serde(default)]
+    pub time: Option<i32>,
+    /// Structured ingredients used in this step
+    #[serde(default)]
+    pub ingredients: Option<Vec<RecipeIngredientParams>>,
+    /// Free-text ingredient lines such as "2 cloves garlic, minced", parsed into ingredients
+    #[serde(default)]
+    pub ingredient_lines: Option<Vec<String>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct RecipeIngredientParams {
+    pub food: String,
+    /// Omit for ingredients without a quantity, e.g. "salt to taste"
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct ImportRecipeParams {
+    /// URL of the recipe page to import
     pub url: String,
+    /// If true, only parse and return the recipe without saving it
+    #[serde(default)]
+    pub preview: bool,
+    /// Extra keyword names to add to the imported recipe
+    #[serde(default)]
+    pub keywords: Option<Vec<String>>,
+    /// Recipe book to add the imported recipe to
+    #[serde(default)]
+    pub book_id: Option<i32>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -101,18 +225,85 @@ pub struct AddToShoppingListParams {
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct AddFromRecipeParams {
     pub recipe_id: i32,
+    /// Servings to shop for (defaults to the recipe's own servings)
     #[serde(default)]
     pub servings: Option<i32>,
+    /// Meal plan the ingredients are bought for, linked to the added entries
+    #[serde(default)]
+    pub meal_plan_id: Option<i32>,
+    /// Also add ingredients whose food is already marked as on hand
+    #[serde(default)]
+    pub include_on_hand: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct ShopForMealPlansParams {
+    pub from_date: String, // YYYY-MM-DD format
+    pub to_date: String,   // YYYY-MM-DD format
+    /// Only meal plans of this meal type, by ID or name
+    #[serde(default)]
+    pub meal_type: Option<NameOrId>,
+    /// Also add ingredients whose food is already marked as on hand
+    #[serde(default)]
+    pub include_on_hand: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct RemoveRecipeFromShoppingListParams {
+    /// Shopping list recipe ID returned when the recipe was added
+    pub list_recipe_id: i32,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct GetShoppingListParams {
+    /// "consolidated" (default) merges unchecked entries for the same food, "by_aisle"
+    /// also groups them by store section in a supermarket's order, "flat" lists every
+    /// entry and "grouped" splits entries into unchecked and checked
     #[serde(default = "default_format")]
     pub format: String,
+    /// Supermarket name or ID whose section order "by_aisle" follows; defaults to the
+    /// only supermarket when there is just one. Only valid with "by_aisle"
+    #[serde(default)]
+    pub supermarket: Option<NameOrId>,
 }
 
 fn default_format() -> String {
-    "flat".to_string()
+    "consolidated".to_string()
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MergeShoppingListDuplicatesParams {
+    /// Only report what would be merged, without changing the shopping list
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CreateSupermarketParams {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Category names in the order the store is walked through; missing categories are created
+    #[serde(default)]
+    pub categories: Option<Vec<String>>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetSupermarketCategoriesParams {
+    /// Supermarket name or ID
+    pub supermarket: NameOrId,
+    /// Category names in walking order; missing categories are created and categories
+    /// left out are removed from this store
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SetFoodCategoryParams {
+    /// Names of the foods to shelve in the category
+    pub foods: Vec<String>,
+    /// Category name, created if missing; omit to remove the foods' category
+    #[serde(default)]
+    pub category: Option<String>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -144,8 +335,9 @@ pub struct UpdatePantryParams {
 pub struct GetMealPlansParams {
     pub from_date: String, // YYYY-MM-DD format
     pub to_date: String,   // YYYY-MM-DD format
+    /// Only plans of this meal type, by ID or name (e.g. "Dinner")
     #[serde(default)]
-    pub meal_type: Option<String>,
+    pub meal_type: Option<NameOrId>,
 }
 
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
@@ -161,6 +353,107 @@ pub struct CreateMealPlanParams {
     pub note: Option<String>,
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct UpdateMealPlanParams {
+    pub id: i32,
+    /// New date, YYYY-MM-DD
+    #[serde(default)]
+    pub date: Option<String>,
+    /// New meal type, by ID or name
+    #[serde(default)]
+    pub meal_type: Option<NameOrId>,
+    #[serde(default)]
+    pub servings: Option<i32>,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// Recipe to cook instead
+    #[serde(default)]
+    pub recipe_id: Option<i32>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct MoveMealPlanParams {
+    pub id: i32,
+    /// Where to move the plan: a date (YYYY-MM-DD), a weekday ("thursday"), "tomorrow",
+    /// a day offset ("+2 days"), or "next free slot" / "next free dinner slot"
+    pub to: String,
+    /// Meal type to move the plan into, by ID or name; defaults to its current type
+    #[serde(default)]
+    pub meal_type: Option<NameOrId>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct GenerateMealPlanParams {
+    pub from_date: String, // YYYY-MM-DD format
+    pub to_date: String,   // YYYY-MM-DD format
+    /// Meal types to fill, by ID or name; defaults to the space's default meal types
+    #[serde(default)]
+    pub meal_types: Vec<NameOrId>,
+    /// Only recipes tagged with at least one of these keywords
+    #[serde(default)]
+    pub include_keywords: Vec<String>,
+    /// Skip recipes tagged with any of these keywords
+    #[serde(default)]
+    pub exclude_keywords: Vec<String>,
+    /// Skip recipes cooked within this many days before `from_date`
+    #[serde(default = "default_avoid_cooked_within_days")]
+    pub avoid_cooked_within_days: i64,
+    /// Servings per meal; defaults to each recipe's own servings
+    #[serde(default)]
+    pub servings: Option<i32>,
+    /// Token returned with the draft; required to actually create the meal plans
+    #[serde(default)]
+    pub confirm: Option<String>,
+}
+
+fn default_avoid_cooked_within_days() -> i64 {
+    14
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct CopyMealPlansParams {
+    /// First day of the meal plans to copy, YYYY-MM-DD; use with `to_date`
+    #[serde(default)]
+    pub from_date: Option<String>,
+    /// Last day of the meal plans to copy, YYYY-MM-DD
+    #[serde(default)]
+    pub to_date: Option<String>,
+    /// Name of a saved template to apply instead of copying a date range
+    #[serde(default)]
+    pub template: Option<String>,
+    /// Day the copy starts on, YYYY-MM-DD; each meal keeps its offset from the first day
+    pub target_date: String,
+    /// When a meal's date and meal type already have a meal: "abort" (default) copies
+    /// nothing and lists the conflicts, "skip" leaves those meals out, "add" plans them
+    /// alongside the existing ones
+    #[serde(default = "default_on_conflict")]
+    pub on_conflict: String,
+    /// Only show what would be copied
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn default_on_conflict() -> String {
+    "abort".to_string()
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct SaveMealPlanTemplateParams {
+    pub name: String,
+    pub from_date: String, // YYYY-MM-DD format
+    pub to_date: String,   // YYYY-MM-DD format
+    /// Replace a template with the same name
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DeleteMealPlanTemplateParams {
+    pub name: String,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct DeleteMealPlanParams {
     pub id: i32,
@@ -209,6 +502,124 @@ fn default_days_until_expiry() -> i32 {
     3
 }
 
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct UpdateRecipeParams {
+    /// The recipe ID to update
+    pub recipe_id: i32,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub servings: Option<i32>,
+    /// Active time in minutes
+    #[serde(default)]
+    pub working_time: Option<i32>,
+    /// Passive time in minutes
+    #[serde(default)]
+    pub waiting_time: Option<i32>,
+    #[serde(default)]
+    pub source_url: Option<String>,
+    #[serde(default)]
+    pub private: Option<bool>,
+    /// Step and ingredient edits, applied in order. IDs come from get_recipe_details.
+    #[serde(default)]
+    pub edits: Option<Vec<RecipeEdit>>,
+}
+
+/// One change to a recipe's steps or ingredients. Positions are 1-based.
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RecipeEdit {
+    /// Add a step at `position`, or at the end
+    AddStep {
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        instruction: String,
+        #[serde(default)]
+        time: Option<i32>,
+        #[serde(default)]
+        ingredients: Option<Vec<RecipeIngredientParams>>,
+        #[serde(default)]
+        ingredient_lines: Option<Vec<String>>,
+        #[serde(default)]
+        position: Option<usize>,
+    },
+    /// Change a step's name, instruction or time
+    UpdateStep {
+        step_id: i32,
+        #[serde(default)]
+        name: Option<String>,
+        #[serde(default)]
+        instruction: Option<String>,
+        #[serde(default)]
+        time: Option<i32>,
+    },
+    /// Remove a step and its ingredients
+    RemoveStep { step_id: i32 },
+    /// Move a step to another position
+    MoveStep { step_id: i32, position: usize },
+    /// Add an ingredient to a step, either structured or as a free-text `line`
+    AddIngredient {
+        step_id: i32,
+        #[serde(default)]
+        food: Option<String>,
+        #[serde(default)]
+        amount: Option<f64>,
+        #[serde(default)]
+        unit: Option<String>,
+        #[serde(default)]
+        note: Option<String>,
+        #[serde(default)]
+        line: Option<String>,
+        #[serde(default)]
+        position: Option<usize>,
+    },
+    /// Change an ingredient; an empty `unit` or `note` clears it
+    UpdateIngredient {
+        ingredient_id: i32,
+        #[serde(default)]
+        food: Option<String>,
+        #[serde(default)]
+        amount: Option<f64>,
+        #[serde(default)]
+        unit: Option<String>,
+        #[serde(default)]
+        note: Option<String>,
+    },
+    /// Remove an ingredient
+    RemoveIngredient { ingredient_id: i32 },
+    /// Move an ingredient to another position, optionally in another step
+    MoveIngredient {
+        ingredient_id: i32,
+        position: usize,
+        #[serde(default)]
+        step_id: Option<i32>,
+    },
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DeleteRecipeParams {
+    /// The recipe ID to delete
+    pub recipe_id: i32,
+    /// Token returned by a first call without it; required to actually delete
+    #[serde(default)]
+    pub confirm: Option<String>,
+}
+
+#[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
+pub struct DuplicateRecipeParams {
+    /// The recipe ID to copy
+    pub recipe_id: i32,
+    /// Name for the copy (defaults to "<original name> (copy)")
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Rescale ingredient amounts to this many servings
+    #[serde(default)]
+    pub servings: Option<i32>,
+}
+
 #[derive(Debug, serde::Deserialize, schemars::JsonSchema)]
 pub struct UpdateRecipeKeywordsParams {
     /// The recipe ID to update
@@ -237,242 +648,3006 @@ pub struct AddRecipeToBookParams {
     pub recipe_id: i32,
 }
 
-// Global shared authentication state
-/// Global authentication token storage to handle Tandoor's rate limiting
-static GLOBAL_AUTH: OnceLock<Arc<Mutex<Option<String>>>> = OnceLock::new();
+/// Describe a failure as JSON with a stable `code`, a `retryable` hint and, for Tandoor
+/// validation errors, per-field messages under `field_errors`.
+///
+/// Errors that did not come from the Tandoor API are reported as `internal_error`.
+fn error_json(context: &str, error: impl Into<anyhow::Error>) -> serde_json::Value {
+    let error = error.into();
+    let mut body = match error.downcast_ref::<TandoorError>() {
+        Some(tandoor_error) => tandoor_error.to_json(),
+        None => json!({
+            "code": "internal_error",
+            "message": error.to_string(),
+            "retryable": false
+        }),
+    };
+
+    let suggestion = match body["code"].as_str() {
+        Some("unauthorized") => Some("Check your Tandoor credentials or TANDOOR_AUTH_TOKEN"),
+        Some("forbidden") => Some("Check that the user has access to this Tandoor space"),
+        Some("not_found") => Some("Check the ID; it may have been deleted"),
+        Some("validation_error") => Some("Fix the fields listed in field_errors and try again"),
+        Some("rate_limited") => Some("Wait before retrying"),
+        Some("network_error") => {
+            Some("Check that Tandoor is running and accessible at the configured URL")
+        }
+        _ => None,
+    };
 
-/// Global credentials storage for automatic re-authentication
-static GLOBAL_CREDENTIALS: OnceLock<(String, String)> = OnceLock::new();
+    body["error"] = json!(context);
+    if let Some(suggestion) = suggestion {
+        body["suggestion"] = json!(suggestion);
+    }
+    body
+}
 
-/// # Tandoor MCP Server
-///
-/// The main MCP server implementation that provides Tandoor functionality through
-/// standardized tools. This server handles all communication with Tandoor APIs
-/// and exposes them as MCP tools for AI assistants.
-///
-/// ## Rate Limiting Considerations
-///
-/// Tandoor has aggressive rate limiting on authentication endpoints (10 requests/day).
-/// The server handles this by:
-/// - Storing authentication tokens globally
-/// - Reusing tokens across requests
-/// - Supporting pre-set tokens via environment variables
-///
-/// ## Example Usage
-///
-/// ```no_run
-/// use mcp_tandoor::server::TandoorMcpServer;
-///
-/// // Create server with credentials
-/// let server = TandoorMcpServer::new_with_credentials(
-///     "http://localhost:8080".to_string(),
-///     "admin".to_string(),
-///     "password".to_string()
-/// );
-///
-/// // Or set a pre-authenticated token
-/// server.set_global_auth_token("your_token".to_string()).await.unwrap();
-/// ```
-#[derive(Clone)]
-pub struct TandoorMcpServer {
-    /// Thread-safe client for Tandoor API communication
-    client: Arc<Mutex<TandoorClient>>,
-    /// MCP tool router for handling tool requests
-    tool_router: ToolRouter<TandoorMcpServer>,
+/// Build a structured tool error result, see [`error_json`].
+fn error_result(context: &str, error: impl Into<anyhow::Error>) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(
+        serde_json::to_string_pretty(&error_json(context, error)).unwrap(),
+    )])
 }
 
-#[tool_router]
-impl TandoorMcpServer {
-    /// Create a new Tandoor MCP server with just a base URL.
-    /// Authentication will need to be handled separately.
-    pub fn new(base_url: String) -> Self {
-        Self {
-            client: Arc::new(Mutex::new(TandoorClient::new(base_url))),
-            tool_router: Self::tool_router(),
+/// Build a tool error for a request the tool itself rejected before calling Tandoor.
+/// Several messages for one field are all kept, in order.
+fn invalid_params_result(message: &str, field_errors: &[(&str, &str)]) -> CallToolResult {
+    let mut error = json!({
+        "error": "Invalid parameters",
+        "code": "invalid_params",
+        "message": message,
+        "retryable": false
+    });
+    if !field_errors.is_empty() {
+        let mut by_field: std::collections::BTreeMap<&str, Vec<&str>> =
+            std::collections::BTreeMap::new();
+        for (field, message) in field_errors {
+            by_field.entry(field).or_default().push(message);
         }
+        error["field_errors"] = json!(by_field);
     }
+    CallToolResult::error(vec![Content::text(
+        serde_json::to_string_pretty(&error).unwrap(),
+    )])
+}
 
-    /// Create a new Tandoor MCP server with credentials for automatic authentication.
-    ///
-    /// The credentials are stored globally and will be used for automatic re-authentication
-    /// when tokens expire. This is the recommended constructor for most use cases.
-    ///
-    /// # Arguments
-    ///
-    /// * `base_url` - The base URL of the Tandoor server (e.g., "http://localhost:8080")
-    /// * `username` - Tandoor username for authentication
-    /// * `password` - Tandoor password for authentication
-    pub fn new_with_credentials(base_url: String, username: String, password: String) -> Self {
-        // Store credentials globally so all instances can use them
-        let _ = GLOBAL_CREDENTIALS.set((username, password));
+/// Whether `food` is called `name`, in either its singular or plural form.
+fn food_has_name(food: &Food, name: &str) -> bool {
+    food.name.eq_ignore_ascii_case(name)
+        || food
+            .plural_name
+            .as_deref()
+            .is_some_and(|plural| plural.eq_ignore_ascii_case(name))
+}
 
-        Self {
-            client: Arc::new(Mutex::new(TandoorClient::new(base_url))),
-            tool_router: Self::tool_router(),
-        }
-    }
+/// Find the best matching food for `name`, preferring exact (or plural) name matches
+/// over Tandoor's fuzzy search ranking.
+async fn resolve_food(client: &TandoorClient, name: &str) -> anyhow::Result<Option<Food>> {
+    let response = client.search_foods(name, Some(10)).await?;
+    let mut foods = response.results;
 
-    /// Set a pre-authenticated token to avoid rate limiting.
-    ///
-    /// This is useful when you have a token from a previous authentication
-    /// or from an external source. Tandoor limits authentication to 10 requests
-    /// per day, so reusing tokens is important for reliability.
-    ///
-    /// # Arguments
-    ///
-    /// * `token` - A valid Tandoor OAuth2 access token
-    pub async fn set_global_auth_token(&self, token: String) -> Result<(), anyhow::Error> {
-        let auth_storage = GLOBAL_AUTH.get_or_init(|| Arc::new(Mutex::new(None)));
-        let mut auth = auth_storage.lock().await;
-        *auth = Some(token);
-        tracing::debug!("Global auth token updated");
-        Ok(())
+    let exact = foods.iter().position(|food| food_has_name(food, name));
+
+    Ok(match exact {
+        Some(index) => Some(foods.swap_remove(index)),
+        None if foods.is_empty() => None,
+        None => Some(foods.swap_remove(0)),
+    })
+}
+
+/// Find the Tandoor unit for a unit name, accepting any spelling the ingredient
+/// parser knows (e.g. "lbs" matches a Tandoor unit called "pound").
+fn resolve_unit<'a>(units: &'a [Unit], name: &str) -> Option<&'a Unit> {
+    let matches_name = |candidate: &str| candidate.trim().eq_ignore_ascii_case(name.trim());
+    if let Some(unit) = units.iter().find(|unit| {
+        matches_name(&unit.name) || unit.plural_name.as_deref().is_some_and(matches_name)
+    }) {
+        return Some(unit);
     }
 
-    pub async fn authenticate(
-        &self,
-        username: String,
-        password: String,
-    ) -> Result<(), anyhow::Error> {
-        let mut client = self.client.lock().await;
-        let result = client.authenticate(username, password).await;
+    let canonical = ingredient_parser::normalize_unit(name)?;
+    units.iter().find(|unit| {
+        ingredient_parser::normalize_unit(&unit.name) == Some(canonical)
+            || unit
+                .plural_name
+                .as_deref()
+                .and_then(ingredient_parser::normalize_unit)
+                == Some(canonical)
+    })
+}
 
-        if result.is_ok() {
-            // Store the token globally for all instances to use
-            if let Some(token) = client.get_token() {
-                self.set_global_auth_token(token.to_string()).await?;
-            }
+/// Scale an ingredient amount, moving it to a handier unit and rounding it when its unit
+/// is a known kitchen unit, so a scaled "3 tablespoons" reads "2.25 tsp" rather than
+/// "0.75 tablespoon". The Tandoor unit name is kept when the unit does not change.
+fn scale_amount(amount: f64, unit: Option<&Unit>, factor: f64) -> (f64, Option<String>) {
+    let scaled = amount * factor;
+    let Some(unit) = unit else {
+        return (units::round_amount(scaled), None);
+    };
+    match KitchenUnit::for_unit(unit) {
+        Some(kitchen) => {
+            let measure = Measure::new(scaled, kitchen).normalized();
+            let name = if measure.unit == kitchen {
+                unit.name.clone()
+            } else {
+                measure.unit.name.to_string()
+            };
+            (measure.amount, Some(name))
         }
+        None => (units::round_amount(scaled), Some(unit.name.clone())),
+    }
+}
 
-        result
+/// Add two amounts of one food, converting the second into the first's unit when both are
+/// kitchen units of the same dimension. `None` if the units cannot be added, e.g. grams
+/// and cups, or cloves and heads.
+fn add_amounts(first: (f64, Option<&Unit>), second: (f64, Option<&Unit>)) -> Option<f64> {
+    let (first_amount, first_unit) = first;
+    let (second_amount, second_unit) = second;
+    if first_unit.map(|u| u.id) == second_unit.map(|u| u.id) {
+        return Some(first_amount + second_amount);
     }
+    let first = Measure::new(first_amount, KitchenUnit::for_unit(first_unit?)?);
+    let second = Measure::new(second_amount, KitchenUnit::for_unit(second_unit?)?);
+    first
+        .plus(second)
+        .map(|total| units::round_amount(total.amount))
+}
 
-    async fn ensure_authenticated(
-        &self,
-    ) -> Result<tokio::sync::MutexGuard<'_, TandoorClient>, anyhow::Error> {
-        tracing::info!("=== ensure_authenticated: acquiring client lock ===");
+/// Unchecked shopping list entries for one food whose amounts add up, e.g. 500 g and
+/// 1 kg of flour added from two recipes. The total is in the first entry's unit.
+struct ConsolidatedEntry<'a> {
+    entries: Vec<&'a ShoppingListEntry>,
+    amount: f64,
+}
 
-        // Add timeout to client lock acquisition
-        let client_result =
-            tokio::time::timeout(std::time::Duration::from_secs(5), self.client.lock()).await;
+impl ConsolidatedEntry<'_> {
+    fn first(&self) -> &ShoppingListEntry {
+        self.entries[0]
+    }
 
-        let mut client = match client_result {
-            Ok(client) => {
-                tracing::info!("=== ensure_authenticated: client lock acquired ===");
-                client
-            }
-            Err(_) => {
-                tracing::error!("=== ensure_authenticated: TIMEOUT acquiring client lock ===");
-                return Err(anyhow::anyhow!("Timeout acquiring client lock"));
-            }
-        };
+    fn to_json(&self) -> serde_json::Value {
+        let first = self.first();
+        json!({
+            "id": first.id,
+            "food": first.food.name,
+            "amount": self.amount,
+            "unit": first.unit.as_ref().map(|u| &u.name),
+            "checked": false,
+            "available": first.food.food_onhand,
+            "category": first.food.supermarket_category.as_ref().map(|c| &c.name),
+            "entry_ids": self.entries.iter().map(|entry| entry.id).collect::<Vec<_>>()
+        })
+    }
+}
 
-        if client.is_authenticated() {
-            tracing::info!("=== ensure_authenticated: already authenticated ===");
-            return Ok(client);
+/// Group unchecked entries by food, merging those whose units can be added. Entries for
+/// one food in units that cannot be added, e.g. cloves and heads of garlic, stay apart.
+/// With `per_recipe`, entries added from different recipes stay apart too, so merging
+/// them in Tandoor never lets removing one recipe take another recipe's amounts along.
+fn consolidate_entries(
+    entries: &[ShoppingListEntry],
+    per_recipe: bool,
+) -> Vec<ConsolidatedEntry<'_>> {
+    let mut consolidated: Vec<ConsolidatedEntry> = Vec::new();
+    for entry in entries.iter().filter(|entry| !entry.checked) {
+        let existing = consolidated
+            .iter_mut()
+            .filter(|group| {
+                group.first().food.id == entry.food.id
+                    && (!per_recipe || group.first().list_recipe == entry.list_recipe)
+            })
+            .find_map(|group| {
+                let first_unit = group.first().unit.as_ref();
+                add_amounts(
+                    (group.amount, first_unit),
+                    (entry.amount, entry.unit.as_ref()),
+                )
+                .map(|total| (group, total))
+            });
+        match existing {
+            Some((group, total)) => {
+                group.amount = total;
+                group.entries.push(entry);
+            }
+            None => consolidated.push(ConsolidatedEntry {
+                entries: vec![entry],
+                amount: entry.amount,
+            }),
         }
+    }
+    consolidated
+}
 
-        tracing::info!("=== ensure_authenticated: not authenticated, proceeding with auth ===");
-
-        // If no global token, try to authenticate with stored credentials directly
-        if let Some((username, password)) = GLOBAL_CREDENTIALS.get() {
-            tracing::info!(
-                "Auto-authenticating with stored credentials for user: {}",
-                username
+/// The supermarket `wanted` names by ID or name.
+fn find_supermarket<'a>(
+    supermarkets: &'a [Supermarket],
+    wanted: &NameOrId,
+) -> Result<&'a Supermarket, CallToolResult> {
+    supermarkets
+        .iter()
+        .find(|market| wanted.matches(market.id, &market.name))
+        .ok_or_else(|| {
+            let known: Vec<&str> = supermarkets.iter().map(|m| m.name.as_str()).collect();
+            let message = format!(
+                "Unknown supermarket '{wanted}', expected one of: {}",
+                known.join(", ")
             );
-            let result = client
-                .authenticate(username.clone(), password.clone())
-                .await;
-
-            match result {
-                Ok(_) => {
-                    tracing::info!("Authentication successful");
-                    Ok(client)
+            invalid_params_result(&message, &[("supermarket", &message)])
+        })
+}
+
+/// The supermarket `wanted` names, or the only one there is when `wanted` is `None`.
+/// Several supermarkets and no choice means no store order.
+fn choose_supermarket<'a>(
+    supermarkets: &'a [Supermarket],
+    wanted: Option<&NameOrId>,
+) -> Result<Option<&'a Supermarket>, CallToolResult> {
+    match (wanted, supermarkets) {
+        (Some(wanted), _) => find_supermarket(supermarkets, wanted).map(Some),
+        (None, [only]) => Ok(Some(only)),
+        (None, _) => Ok(None),
+    }
+}
+
+/// Consolidated items grouped by supermarket category, in `supermarket`'s walking order.
+/// Categories the store does not stock follow by name, then foods without a category.
+fn group_by_aisle(
+    consolidated: &[ConsolidatedEntry],
+    supermarket: Option<&Supermarket>,
+) -> Vec<serde_json::Value> {
+    let mut aisles: std::collections::BTreeMap<(u8, i32, String), (serde_json::Value, Vec<_>)> =
+        std::collections::BTreeMap::new();
+    for group in consolidated {
+        let category = group.first().food.supermarket_category.as_ref();
+        let key = match category {
+            Some(category) => match supermarket.and_then(|m| m.category_order(category.id)) {
+                Some(order) => (0, order, category.name.to_lowercase()),
+                None => (1, 0, category.name.to_lowercase()),
+            },
+            None => (2, 0, String::new()),
+        };
+        aisles
+            .entry(key)
+            .or_insert_with(|| {
+                let header = match category {
+                    Some(category) => {
+                        json!({"category": category.name, "category_id": category.id})
+                    }
+                    None => json!({"category": "Other", "category_id": null}),
+                };
+                (header, Vec::new())
+            })
+            .1
+            .push(group.to_json());
+    }
+    aisles
+        .into_values()
+        .map(|(mut aisle, items)| {
+            aisle["items"] = json!(items);
+            aisle
+        })
+        .collect()
+}
+
+fn supermarket_json(supermarket: &Supermarket) -> serde_json::Value {
+    let mut relations: Vec<_> = supermarket.category_to_supermarket.iter().collect();
+    relations.sort_by_key(|relation| relation.order);
+    json!({
+        "id": supermarket.id,
+        "name": supermarket.name,
+        "description": supermarket.description,
+        "categories": relations
+            .iter()
+            .map(|relation| json!({
+                "id": relation.category.id,
+                "name": relation.category.name,
+                "order": relation.order
+            }))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Reject blank or repeated category names, which would make the store order ambiguous.
+fn validate_category_names(field: &str, names: &[String]) -> Result<(), CallToolResult> {
+    for (index, name) in names.iter().enumerate() {
+        let repeated = names[..index]
+            .iter()
+            .any(|earlier| earlier.trim().eq_ignore_ascii_case(name.trim()));
+        if name.trim().is_empty() || repeated {
+            let message = if repeated {
+                format!("Category '{}' is listed twice", name.trim())
+            } else {
+                "Category names may not be blank".to_string()
+            };
+            return Err(invalid_params_result(
+                &message,
+                &[(&format!("{field}[{index}]"), &message)],
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Make `categories` the walking order of `supermarket`: categories it already stocks are
+/// reordered, new ones are added and those left out are removed. Returns the failures.
+async fn set_category_order(
+    client: &TandoorClient,
+    supermarket: &Supermarket,
+    categories: &[String],
+) -> Vec<serde_json::Value> {
+    let mut errors = Vec::new();
+    let relation_for = |name: &str| {
+        supermarket
+            .category_to_supermarket
+            .iter()
+            .find(|relation| relation.category.name.eq_ignore_ascii_case(name.trim()))
+    };
+
+    for (order, name) in categories.iter().enumerate() {
+        let order = order as i32;
+        let result = match relation_for(name) {
+            Some(relation) if relation.order == order => continue,
+            Some(relation) => client
+                .update_supermarket_category_order(relation.id, order)
+                .await
+                .map(drop),
+            None => client
+                .create_supermarket_category_relation(
+                    crate::client::types::CreateSupermarketCategoryRelationRequest {
+                        supermarket: supermarket.id,
+                        category: crate::client::types::CreateSupermarketCategoryRequest {
+                            name: name.trim().to_string(),
+                        },
+                        order,
+                    },
+                )
+                .await
+                .map(drop),
+        };
+        if let Err(e) = result {
+            let mut error = error_json("Failed to place category", e);
+            error["category"] = json!(name);
+            errors.push(error);
+        }
+    }
+
+    for relation in supermarket
+        .category_to_supermarket
+        .iter()
+        .filter(|relation| {
+            !categories
+                .iter()
+                .any(|name| relation.category.name.eq_ignore_ascii_case(name.trim()))
+        })
+    {
+        if let Err(e) = client
+            .delete_supermarket_category_relation(relation.id)
+            .await
+        {
+            let mut error = error_json("Failed to remove category", e);
+            error["category"] = json!(relation.category.name);
+            errors.push(error);
+        }
+    }
+    errors
+}
+
+/// Reply with `supermarket_id` as stored after an update, plus any failures along the way.
+async fn supermarket_result(
+    client: &TandoorClient,
+    supermarket_id: i32,
+    errors: Vec<serde_json::Value>,
+) -> CallToolResult {
+    let supermarkets = match client.get_supermarkets().await {
+        Ok(response) => response.results,
+        Err(e) => return error_result("Failed to get supermarkets", e),
+    };
+    let mut result = supermarkets
+        .iter()
+        .find(|market| market.id == supermarket_id)
+        .map_or_else(|| json!({"id": supermarket_id}), supermarket_json);
+    result["errors"] = json!(errors);
+    result["success"] = json!(errors.is_empty());
+    CallToolResult::success(vec![Content::text(
+        serde_json::to_string_pretty(&result).unwrap(),
+    )])
+}
+
+fn shopping_entry_json(entry: &ShoppingListEntry) -> serde_json::Value {
+    json!({
+        "id": entry.id,
+        "food": entry.food.name,
+        "amount": entry.amount,
+        "unit": entry.unit.as_ref().map(|u| &u.name),
+        "checked": entry.checked,
+        "available": entry.food.food_onhand,
+        "created": entry.created,
+        "completed": entry.completed
+    })
+}
+
+fn meal_plan_json(plan: &MealPlan) -> serde_json::Value {
+    json!({
+        "id": plan.id,
+        "date": plan.date,
+        "meal_type": plan.meal_type.name,
+        "recipe_id": plan.recipe.as_ref().map(|r| r.id),
+        "recipe_name": plan.recipe.as_ref().map(|r| &r.name),
+        "title": plan.title,
+        "servings": plan.servings,
+        "note": plan.note,
+        "created": plan.created
+    })
+}
+
+fn find_meal_type<'a>(
+    meal_types: &'a [MealType],
+    wanted: &NameOrId,
+    field: &str,
+) -> Result<&'a MealType, CallToolResult> {
+    meal_types
+        .iter()
+        .find(|meal_type| wanted.matches(meal_type.id, &meal_type.name))
+        .ok_or_else(|| {
+            let known: Vec<&str> = meal_types.iter().map(|t| t.name.as_str()).collect();
+            let message = format!(
+                "Unknown meal type '{wanted}', expected one of: {}",
+                known.join(", ")
+            );
+            invalid_params_result(&message, &[(field, &message)])
+        })
+}
+
+/// Meal plans grouped by day, then by meal type in the space's meal type order, with the
+/// number of meals, servings and recipe minutes planned for each day.
+fn group_meal_plans_by_day(plans: &[MealPlan]) -> Vec<serde_json::Value> {
+    let mut days: std::collections::BTreeMap<_, std::collections::BTreeMap<_, Vec<&MealPlan>>> =
+        std::collections::BTreeMap::new();
+    for plan in plans {
+        days.entry(plan.date)
+            .or_default()
+            .entry((plan.meal_type.order, plan.meal_type.id))
+            .or_default()
+            .push(plan);
+    }
+
+    days.into_iter()
+        .map(|(date, meals)| {
+            let day_plans: Vec<&MealPlan> = meals
+                .values()
+                .flat_map(|plans| plans.iter().copied())
+                .collect();
+            let total_minutes: i32 = day_plans
+                .iter()
+                .filter_map(|plan| plan.recipe.as_ref())
+                .map(|recipe| recipe.working_time.unwrap_or(0) + recipe.waiting_time.unwrap_or(0))
+                .sum();
+            json!({
+                "date": date,
+                "weekday": date.format("%A").to_string(),
+                "meals": meals
+                    .values()
+                    .map(|plans| json!({
+                        "meal_type": plans[0].meal_type.name,
+                        "meal_type_id": plans[0].meal_type.id,
+                        "plans": plans.iter().map(|plan| meal_plan_json(plan)).collect::<Vec<_>>()
+                    }))
+                    .collect::<Vec<_>>(),
+                "totals": {
+                    "meals": day_plans.len(),
+                    "servings": day_plans.iter().map(|plan| plan.servings).sum::<i32>(),
+                    "total_time_minutes": total_minutes
+                }
+            })
+        })
+        .collect()
+}
+
+/// Where `move_meal_plan` puts a plan. Relative targets count from the plan's current date.
+#[derive(Debug, PartialEq)]
+enum MoveTarget {
+    Date(chrono::NaiveDate),
+    /// The first such weekday after the plan's date
+    Weekday(chrono::Weekday),
+    /// Days after the plan's date, or before it when negative
+    Offset(i64),
+    /// The first day after the plan's date with no plan of the named meal type, or of the
+    /// plan's own meal type when none is named
+    NextFree(Option<String>),
+}
+
+impl MoveTarget {
+    fn parse(target: &str) -> Option<Self> {
+        let target = target.trim().to_lowercase();
+        if let Ok(date) = chrono::NaiveDate::parse_from_str(&target, "%Y-%m-%d") {
+            return Some(Self::Date(date));
+        }
+        match target.as_str() {
+            "tomorrow" => return Some(Self::Offset(1)),
+            "yesterday" => return Some(Self::Offset(-1)),
+            _ => {}
+        }
+        if let Some(rest) = target.strip_prefix("next free") {
+            let meal_type = rest.trim().trim_end_matches("slot").trim();
+            return Some(Self::NextFree(
+                (!meal_type.is_empty()).then(|| meal_type.to_string()),
+            ));
+        }
+        let weekday = target.strip_prefix("next ").unwrap_or(&target);
+        if let Ok(weekday) = weekday.trim().parse() {
+            return Some(Self::Weekday(weekday));
+        }
+        let offset = target
+            .trim_end_matches("days")
+            .trim_end_matches("day")
+            .trim();
+        if offset.starts_with(['+', '-']) {
+            return offset.replace(' ', "").parse().ok().map(Self::Offset);
+        }
+        None
+    }
+
+    /// The date this target means for a plan on `from`; `None` when it is out of the
+    /// supported date range, and for [`Self::NextFree`], which depends on the other plans.
+    fn date_from(&self, from: chrono::NaiveDate) -> Option<chrono::NaiveDate> {
+        match self {
+            Self::Date(date) => Some(*date),
+            Self::Weekday(weekday) => (1..=7)
+                .filter_map(|days| from.checked_add_days(chrono::Days::new(days)))
+                .find(|date| chrono::Datelike::weekday(date) == *weekday),
+            Self::Offset(days) => {
+                chrono::TimeDelta::try_days(*days).and_then(|delta| from.checked_add_signed(delta))
+            }
+            Self::NextFree(_) => None,
+        }
+    }
+}
+
+/// A recipe `generate_meal_plan` may schedule, with the pantry foods it uses.
+struct PlanCandidate {
+    recipe: Recipe,
+    minutes: i32,
+    pantry_foods: Vec<String>,
+}
+
+impl PlanCandidate {
+    fn new(recipe: Recipe, pantry: &std::collections::HashSet<i32>) -> Self {
+        let mut pantry_foods: Vec<String> = Vec::new();
+        for ingredient in recipe.steps.iter().flat_map(|step| &step.ingredients) {
+            if pantry.contains(&ingredient.food.id) && !pantry_foods.contains(&ingredient.food.name)
+            {
+                pantry_foods.push(ingredient.food.name.clone());
+            }
+        }
+        Self {
+            minutes: recipe.working_time.unwrap_or(0) + recipe.waiting_time.unwrap_or(0),
+            recipe,
+            pantry_foods,
+        }
+    }
+}
+
+/// One day of a generated plan: its free meal types and the recipes placed on it.
+#[derive(Default)]
+struct PlanDay<'a> {
+    meal_types: Vec<&'a MealType>,
+    recipes: Vec<&'a PlanCandidate>,
+    minutes: i32,
+}
+
+/// Spread the best `candidates` over the free `slots`, one recipe per slot and none twice.
+///
+/// Recipes are placed longest first on the day with the least prep time so far, which
+/// keeps daily totals even. Within a day the longest recipe takes the latest meal type, so
+/// a day that cannot be filled misses its earlier meals. Returns the filled slots in date
+/// and meal type order.
+fn draft_meal_plan<'a>(
+    slots: &[(chrono::NaiveDate, &'a MealType)],
+    candidates: &'a [PlanCandidate],
+) -> Vec<(chrono::NaiveDate, &'a MealType, &'a PlanCandidate)> {
+    let mut chosen: Vec<&PlanCandidate> = candidates.iter().take(slots.len()).collect();
+    chosen.sort_by_key(|candidate| std::cmp::Reverse(candidate.minutes));
+
+    let mut days: std::collections::BTreeMap<chrono::NaiveDate, PlanDay> =
+        std::collections::BTreeMap::new();
+    for (date, meal_type) in slots {
+        days.entry(*date).or_default().meal_types.push(meal_type);
+    }
+    for candidate in chosen {
+        let Some(day) = days
+            .values_mut()
+            .filter(|day| day.recipes.len() < day.meal_types.len())
+            .min_by_key(|day| day.minutes)
+        else {
+            break;
+        };
+        day.recipes.push(candidate);
+        day.minutes += candidate.minutes;
+    }
+
+    let mut draft: Vec<_> = days
+        .into_iter()
+        .flat_map(|(date, day)| {
+            day.meal_types
+                .into_iter()
+                .rev()
+                .zip(day.recipes)
+                .map(move |(meal_type, candidate)| (date, meal_type, candidate))
+        })
+        .collect();
+    draft.sort_by_key(|(date, meal_type, _)| (*date, meal_type.order, meal_type.id));
+    draft
+}
+
+fn meal_template_json(template: &MealTemplate) -> serde_json::Value {
+    json!({
+        "name": template.name,
+        "days": template.days,
+        "meal_count": template.meals.len(),
+        "saved_at": template.saved_at,
+        "meals": template
+            .meals
+            .iter()
+            .map(|meal| json!({
+                "day_offset": meal.day_offset,
+                "meal_type": meal.meal_type,
+                "recipe_id": meal.recipe_id,
+                "recipe_name": meal.recipe_name,
+                "title": meal.title,
+                "servings": meal.servings
+            }))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Parse a `from_date`/`to_date` pair, rejecting ranges that end before they start.
+fn parse_date_range(
+    from_date: &str,
+    to_date: &str,
+) -> Result<(chrono::NaiveDate, chrono::NaiveDate), CallToolResult> {
+    let from = parse_date_param("from_date", from_date)?;
+    let to = parse_date_param("to_date", to_date)?;
+    if to < from {
+        return Err(invalid_params_result(
+            "to_date is before from_date",
+            &[("to_date", "Must be on or after from_date.")],
+        ));
+    }
+    Ok((from, to))
+}
+
+/// An ingredient from `create_recipe` or `update_recipe`, before its food and unit are resolved.
+#[derive(Clone)]
+struct WantedIngredient {
+    food: String,
+    amount: Option<f64>,
+    unit: Option<String>,
+    note: Option<String>,
+}
+
+/// Parse one recipe ingredient line, keeping trailing descriptors such as the "minced"
+/// in "2 cloves garlic, minced" as the ingredient's note.
+fn parse_ingredient_line(line: &str) -> Option<ingredient_parser::ParsedIngredient> {
+    let mut items = ingredient_parser::parse_request(line);
+    if items.len() == 1 {
+        items.pop()
+    } else {
+        ingredient_parser::parse_line(line)
+    }
+}
+
+/// Collect a step's structured ingredients and parsed ingredient lines, rejecting
+/// entries without a food name. `field` names the step in error messages.
+fn collect_ingredients(
+    step: &RecipeStepParams,
+    field: &str,
+) -> Result<Vec<WantedIngredient>, CallToolResult> {
+    let mut wanted = Vec::new();
+    for (index, ingredient) in step.ingredients.iter().flatten().enumerate() {
+        if ingredient.food.trim().is_empty() {
+            let field = format!("{field}.ingredients[{index}].food");
+            return Err(invalid_params_result(
+                "Every ingredient needs a food name",
+                &[(&field, "This field may not be blank")],
+            ));
+        }
+        wanted.push(WantedIngredient {
+            food: ingredient.food.trim().to_string(),
+            amount: ingredient.amount,
+            unit: ingredient
+                .unit
+                .clone()
+                .filter(|unit| !unit.trim().is_empty()),
+            note: ingredient.note.clone(),
+        });
+    }
+    for (index, line) in step.ingredient_lines.iter().flatten().enumerate() {
+        let Some(parsed) = parse_ingredient_line(line) else {
+            let field = format!("{field}.ingredient_lines[{index}]");
+            return Err(invalid_params_result(
+                "Could not find a food in an ingredient line, e.g. '2 cloves garlic, minced'",
+                &[(&field, "No food found")],
+            ));
+        };
+        wanted.push(WantedIngredient {
+            food: parsed.food,
+            amount: parsed.amount,
+            unit: parsed.unit,
+            note: parsed.note,
+        });
+    }
+    Ok(wanted)
+}
+
+/// Tandoor foods and units chosen for a new recipe's ingredients.
+///
+/// Names that match an existing food or unit are replaced by its exact Tandoor name, so the
+/// recipe links to it instead of creating a near-duplicate. Anything else is recorded as new;
+/// Tandoor creates it when the recipe is saved.
+#[derive(Default)]
+struct IngredientNames {
+    foods: std::collections::HashMap<String, String>,
+    units: std::collections::HashMap<String, String>,
+    new_foods: Vec<String>,
+    new_units: Vec<String>,
+}
+
+impl IngredientNames {
+    async fn resolve(
+        client: &TandoorClient,
+        ingredients: &[Vec<WantedIngredient>],
+    ) -> Result<Self, TandoorError> {
+        let mut names = Self::default();
+        let all = || ingredients.iter().flatten();
+
+        let units = if all().any(|ingredient| ingredient.unit.is_some()) {
+            client.get_units().await?.results
+        } else {
+            Vec::new()
+        };
+
+        for ingredient in all() {
+            let key = ingredient.food.to_lowercase();
+            if !names.foods.contains_key(&key) {
+                let matches = client.search_foods(&ingredient.food, Some(10)).await?;
+                let name = match matches
+                    .results
+                    .iter()
+                    .find(|food| food_has_name(food, &ingredient.food))
+                {
+                    Some(food) => food.name.clone(),
+                    None => {
+                        names.new_foods.push(ingredient.food.clone());
+                        ingredient.food.clone()
+                    }
+                };
+                names.foods.insert(key, name);
+            }
+
+            if let Some(unit) = &ingredient.unit {
+                let key = unit.to_lowercase();
+                if !names.units.contains_key(&key) {
+                    let name = match resolve_unit(&units, unit) {
+                        Some(existing) => existing.name.clone(),
+                        None => {
+                            names.new_units.push(unit.clone());
+                            unit.clone()
+                        }
+                    };
+                    names.units.insert(key, name);
+                }
+            }
+        }
+
+        Ok(names)
+    }
+
+    /// The Tandoor name to save for `name`; names that were not resolved are kept as is.
+    fn food(&self, name: &str) -> String {
+        self.foods
+            .get(&name.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    fn unit(&self, name: &str) -> String {
+        self.units
+            .get(&name.to_lowercase())
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+}
+
+/// Token a destructive tool hands out on a first, dry-run call and requires on the second.
+///
+/// It is derived from the target's identity and last modification, so it only confirms
+/// the exact version the caller was shown and goes stale if the target changes.
+fn confirmation_token(action: &str, target: impl std::hash::Hash) -> String {
+    use std::hash::{DefaultHasher, Hash, Hasher};
+
+    let mut hasher = DefaultHasher::new();
+    action.hash(&mut hasher);
+    target.hash(&mut hasher);
+    format!("{action}-{:016x}", hasher.finish())
+}
+
+/// Summarize saved recipe steps, with the IDs `update_recipe` edits refer to.
+fn recipe_steps_json(steps: &[crate::client::types::Step]) -> Vec<serde_json::Value> {
+    steps
+        .iter()
+        .map(|step| {
+            json!({
+                "id": step.id,
+                "name": step.name,
+                "instruction": step.instruction,
+                "time": step.time,
+                "ingredients": step.ingredients.iter().map(|ingredient| json!({
+                    "id": ingredient.id,
+                    "food": ingredient.food.name,
+                    "amount": ingredient.amount,
+                    "unit": ingredient.unit.as_ref().map(|u| &u.name),
+                    "note": ingredient.note,
+                    "no_amount": ingredient.no_amount
+                })).collect::<Vec<_>>()
+            })
+        })
+        .collect()
+}
+
+/// A recipe step being edited by `update_recipe`; `id` is `None` for new steps.
+struct DraftStep {
+    id: Option<i32>,
+    name: String,
+    instruction: String,
+    time: Option<i32>,
+    ingredients: Vec<DraftIngredient>,
+}
+
+struct DraftIngredient {
+    id: Option<i32>,
+    food: String,
+    unit: Option<String>,
+    amount: Option<f64>,
+    note: Option<String>,
+    is_header: bool,
+}
+
+impl From<crate::client::types::Step> for DraftStep {
+    fn from(step: crate::client::types::Step) -> Self {
+        Self {
+            id: Some(step.id),
+            name: step.name,
+            instruction: step.instruction,
+            time: step.time,
+            ingredients: step
+                .ingredients
+                .into_iter()
+                .map(|ingredient| DraftIngredient {
+                    id: Some(ingredient.id),
+                    food: ingredient.food.name,
+                    unit: ingredient.unit.map(|unit| unit.name),
+                    amount: (!ingredient.no_amount).then_some(ingredient.amount),
+                    note: ingredient.note,
+                    is_header: ingredient.is_header,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<WantedIngredient> for DraftIngredient {
+    fn from(wanted: WantedIngredient) -> Self {
+        Self {
+            id: None,
+            food: wanted.food,
+            unit: wanted.unit,
+            amount: wanted.amount,
+            note: wanted.note,
+            is_header: false,
+        }
+    }
+}
+
+/// Turn a 1-based position into an insertion index, clamped to the end of the list.
+fn insert_index(position: Option<usize>, len: usize) -> usize {
+    position.map_or(len, |position| position.saturating_sub(1).min(len))
+}
+
+/// Apply `update_recipe` edits to the recipe's steps.
+///
+/// Returns the ingredients whose food or unit names were added or changed, which still
+/// need resolving against Tandoor, or the error for the first edit that does not apply.
+fn apply_recipe_edits(
+    steps: &mut Vec<DraftStep>,
+    edits: Vec<RecipeEdit>,
+) -> Result<Vec<WantedIngredient>, CallToolResult> {
+    let mut touched = Vec::new();
+
+    for (index, edit) in edits.into_iter().enumerate() {
+        let field = format!("edits[{index}]");
+        let reject = |message: String| invalid_params_result(&message, &[(&field, &message)]);
+        let find_step = |steps: &[DraftStep], step_id: i32| {
+            steps
+                .iter()
+                .position(|step| step.id == Some(step_id))
+                .ok_or_else(|| reject(format!("No step with id {step_id} in this recipe")))
+        };
+        let find_ingredient = |steps: &[DraftStep], ingredient_id: i32| {
+            steps
+                .iter()
+                .enumerate()
+                .find_map(|(step_index, step)| {
+                    step.ingredients
+                        .iter()
+                        .position(|ingredient| ingredient.id == Some(ingredient_id))
+                        .map(|position| (step_index, position))
+                })
+                .ok_or_else(|| {
+                    reject(format!(
+                        "No ingredient with id {ingredient_id} in this recipe"
+                    ))
+                })
+        };
+
+        match edit {
+            RecipeEdit::AddStep {
+                name,
+                instruction,
+                time,
+                ingredients,
+                ingredient_lines,
+                position,
+            } => {
+                let params = RecipeStepParams {
+                    name,
+                    instruction,
+                    time,
+                    ingredients,
+                    ingredient_lines,
+                };
+                let wanted = collect_ingredients(&params, &field)?;
+                let step = DraftStep {
+                    id: None,
+                    name: params.name.unwrap_or_default(),
+                    instruction: params.instruction,
+                    time: params.time,
+                    ingredients: wanted.iter().cloned().map(Into::into).collect(),
+                };
+                touched.extend(wanted);
+                steps.insert(insert_index(position, steps.len()), step);
+            }
+            RecipeEdit::UpdateStep {
+                step_id,
+                name,
+                instruction,
+                time,
+            } => {
+                let position = find_step(steps, step_id)?;
+                let step = &mut steps[position];
+                if let Some(name) = name {
+                    step.name = name;
+                }
+                if let Some(instruction) = instruction {
+                    step.instruction = instruction;
+                }
+                if time.is_some() {
+                    step.time = time;
+                }
+            }
+            RecipeEdit::RemoveStep { step_id } => {
+                let position = find_step(steps, step_id)?;
+                steps.remove(position);
+            }
+            RecipeEdit::MoveStep { step_id, position } => {
+                let step = steps.remove(find_step(steps, step_id)?);
+                steps.insert(insert_index(Some(position), steps.len()), step);
+            }
+            RecipeEdit::AddIngredient {
+                step_id,
+                food,
+                amount,
+                unit,
+                note,
+                line,
+                position,
+            } => {
+                let step_index = find_step(steps, step_id)?;
+                let wanted = match (line, food) {
+                    (Some(line), _) => {
+                        let parsed = parse_ingredient_line(&line)
+                            .ok_or_else(|| reject(format!("Could not find a food in '{line}'")))?;
+                        WantedIngredient {
+                            food: parsed.food,
+                            amount: parsed.amount,
+                            unit: parsed.unit,
+                            note: parsed.note,
+                        }
+                    }
+                    (None, Some(food)) if !food.trim().is_empty() => WantedIngredient {
+                        food: food.trim().to_string(),
+                        amount,
+                        unit: unit.filter(|unit| !unit.trim().is_empty()),
+                        note,
+                    },
+                    _ => return Err(reject("Give the ingredient a food or a line".to_string())),
+                };
+                let ingredients = &mut steps[step_index].ingredients;
+                let at = insert_index(position, ingredients.len());
+                ingredients.insert(at, wanted.clone().into());
+                touched.push(wanted);
+            }
+            RecipeEdit::UpdateIngredient {
+                ingredient_id,
+                food,
+                amount,
+                unit,
+                note,
+            } => {
+                let (step_index, position) = find_ingredient(steps, ingredient_id)?;
+                let ingredient = &mut steps[step_index].ingredients[position];
+                let renamed = food.is_some() || unit.is_some();
+                if let Some(food) = food.filter(|food| !food.trim().is_empty()) {
+                    ingredient.food = food.trim().to_string();
+                }
+                if amount.is_some() {
+                    ingredient.amount = amount;
+                }
+                if let Some(unit) = unit {
+                    ingredient.unit = Some(unit.trim().to_string()).filter(|u| !u.is_empty());
+                }
+                if let Some(note) = note {
+                    ingredient.note = Some(note).filter(|note| !note.is_empty());
+                }
+                if renamed {
+                    touched.push(WantedIngredient {
+                        food: ingredient.food.clone(),
+                        amount: ingredient.amount,
+                        unit: ingredient.unit.clone(),
+                        note: None,
+                    });
+                }
+            }
+            RecipeEdit::RemoveIngredient { ingredient_id } => {
+                let (step_index, position) = find_ingredient(steps, ingredient_id)?;
+                steps[step_index].ingredients.remove(position);
+            }
+            RecipeEdit::MoveIngredient {
+                ingredient_id,
+                position,
+                step_id,
+            } => {
+                let (from_step, from) = find_ingredient(steps, ingredient_id)?;
+                let to_step = match step_id {
+                    Some(step_id) => find_step(steps, step_id)?,
+                    None => from_step,
+                };
+                let ingredient = steps[from_step].ingredients.remove(from);
+                let ingredients = &mut steps[to_step].ingredients;
+                ingredients.insert(insert_index(Some(position), ingredients.len()), ingredient);
+            }
+        }
+    }
+
+    Ok(touched)
+}
+
+/// Add every ingredient of a recipe to the shopping list, scaled to the requested servings.
+///
+/// Entries are grouped under a shopping list recipe linked to the recipe (and meal plan,
+/// if given) so they can later be removed together. Header rows are skipped, as are foods
+/// already on hand unless `include_on_hand` is set.
+async fn add_recipe_ingredients(
+    client: &TandoorClient,
+    params: AddFromRecipeParams,
+) -> CallToolResult {
+    let recipe = match client.get_recipe(params.recipe_id).await {
+        Ok(recipe) => recipe,
+        Err(e) => {
+            let mut error = error_json("Failed to get recipe", e);
+            error["recipe_id"] = json!(params.recipe_id);
+            return CallToolResult::error(vec![Content::text(error.to_string())]);
+        }
+    };
+
+    let recipe_servings = recipe.servings.filter(|s| *s > 0).unwrap_or(1);
+    let servings = params
+        .servings
+        .filter(|s| *s > 0)
+        .unwrap_or(recipe_servings);
+    let scaling_factor = servings as f64 / recipe_servings as f64;
+
+    let mut skipped = Vec::new();
+    let ingredients: Vec<_> = recipe
+        .steps
+        .iter()
+        .flat_map(|step| &step.ingredients)
+        .filter(|ingredient| !ingredient.is_header)
+        .filter(|ingredient| {
+            if ingredient.food.food_onhand && !params.include_on_hand {
+                skipped.push(json!({
+                    "food": ingredient.food.name,
+                    "reason": "on hand"
+                }));
+                false
+            } else {
+                true
+            }
+        })
+        .collect();
+
+    if ingredients.is_empty() {
+        let result = json!({
+            "recipe_id": recipe.id,
+            "recipe_name": recipe.name,
+            "servings": servings,
+            "added": [],
+            "skipped": skipped,
+            "summary": "Nothing to add: every ingredient is already on hand or the recipe has no ingredients"
+        });
+        return CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]);
+    }
+
+    let list_recipe = match client
+        .create_shopping_list_recipe(crate::client::types::CreateShoppingListRecipeRequest {
+            recipe: recipe.id,
+            servings: servings as f64,
+            mealplan: params.meal_plan_id,
+        })
+        .await
+    {
+        Ok(list_recipe) => list_recipe,
+        Err(e) => {
+            let mut error = error_json("Failed to link recipe to shopping list", e);
+            error["recipe_id"] = json!(recipe.id);
+            return CallToolResult::error(vec![Content::text(error.to_string())]);
+        }
+    };
+
+    let requests = ingredients
+        .iter()
+        .map(
+            |ingredient| crate::client::types::CreateShoppingListEntryRequest {
+                food: ingredient.food.id,
+                unit: ingredient.unit.as_ref().map(|u| u.id),
+                amount: ingredient.amount * scaling_factor,
+                list_recipe: Some(list_recipe.id),
+                ingredient: Some(ingredient.id),
+            },
+        )
+        .collect();
+
+    match client.add_bulk_to_shopping_list(requests).await {
+        Ok(entries) => {
+            let added: Vec<serde_json::Value> = entries
+                .into_iter()
+                .map(|entry| {
+                    json!({
+                        "id": entry.id,
+                        "food": entry.food.name,
+                        "amount": entry.amount,
+                        "unit": entry.unit.as_ref().map(|u| &u.name),
+                        "status": "added"
+                    })
+                })
+                .collect();
+
+            let result = json!({
+                "recipe_id": recipe.id,
+                "recipe_name": recipe.name,
+                "servings": servings,
+                "scaling_factor": scaling_factor,
+                "list_recipe_id": list_recipe.id,
+                "meal_plan_id": params.meal_plan_id,
+                "added": added,
+                "skipped": skipped,
+                "summary": format!("Added {} ingredients from '{}', skipped {} on hand",
+                    added.len(), recipe.name, skipped.len())
+            });
+            CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap(),
+            )])
+        }
+        Err(e) => {
+            // Don't leave an empty recipe group behind
+            if let Err(cleanup) = client.delete_shopping_list_recipe(list_recipe.id).await {
+                tracing::warn!(
+                    "Failed to clean up shopping list recipe {}: {}",
+                    list_recipe.id,
+                    cleanup
+                );
+            }
+            let mut error = error_json("Failed to add recipe ingredients to shopping list", e);
+            error["recipe_id"] = json!(recipe.id);
+            CallToolResult::error(vec![Content::text(error.to_string())])
+        }
+    }
+}
+
+/// A food bought for planned meals, reported as one total in the unit of the first
+/// meal's ingredient. Each meal still gets its own shopping list entry.
+struct MealPlanNeed<'a> {
+    amount: f64,
+    /// Each meal plan needing the food, with its ingredient and scaled amount
+    uses: Vec<(&'a MealPlan, &'a StepIngredient, f64)>,
+}
+
+impl MealPlanNeed<'_> {
+    fn ingredient(&self) -> &StepIngredient {
+        self.uses[0].1
+    }
+}
+
+fn meal_plan_label(plan: &MealPlan) -> serde_json::Value {
+    json!({
+        "meal_plan_id": plan.id,
+        "date": plan.date,
+        "meal_type": plan.meal_type.name,
+        "recipe_name": plan.recipe.as_ref().map(|r| &r.name),
+        "title": plan.title
+    })
+}
+
+/// Delete shopping list recipes created before a later step failed.
+async fn remove_list_recipes(client: &TandoorClient, list_recipes: &[(i32, i32)]) {
+    for (_, list_recipe) in list_recipes {
+        if let Err(cleanup) = client.delete_shopping_list_recipe(*list_recipe).await {
+            tracing::warn!(
+                "Failed to clean up shopping list recipe {}: {}",
+                list_recipe,
+                cleanup
+            );
+        }
+    }
+}
+
+/// Most foods scanned for pantry items when suggesting recipes from inventory
+const MAX_INVENTORY_FOODS: usize = 5000;
+
+/// Most recipes scanned when a search uses filters Tandoor cannot apply itself
+const MAX_SCANNED_RECIPES: usize = 2000;
+
+/// Page size for searches paginated by this server rather than by Tandoor
+const DEFAULT_SEARCH_LIMIT: usize = 25;
+
+/// Days after a meal plan searched for a free slot by `move_meal_plan`
+const FREE_SLOT_SEARCH_DAYS: i64 = 60;
+
+/// Longest date range `generate_meal_plan` fills at once
+const MAX_GENERATED_DAYS: i64 = 31;
+
+/// Most candidate recipes whose ingredients are fetched to rank them by pantry use
+const MAX_PANTRY_CHECKED_RECIPES: usize = 60;
+
+/// A `search_recipes` request translated into Tandoor's filter plus the filters this
+/// server applies itself, because `/api/recipe/` has no equivalent.
+struct RecipeSearch {
+    filter: RecipeSearchFilter,
+    max_total_time: Option<i32>,
+    not_cooked_since: Option<chrono::NaiveDate>,
+    /// Human-readable filter descriptions for the search summary
+    described: Vec<String>,
+}
+
+impl RecipeSearch {
+    fn filters_locally(&self) -> bool {
+        self.max_total_time.is_some() || self.not_cooked_since.is_some()
+    }
+
+    fn keeps(&self, recipe: &Recipe) -> bool {
+        let total_time = recipe.working_time.unwrap_or(0) + recipe.waiting_time.unwrap_or(0);
+        self.max_total_time.is_none_or(|max| total_time <= max)
+            && self.not_cooked_since.is_none_or(|since| {
+                recipe
+                    .last_cooked
+                    .is_none_or(|cooked| cooked.date_naive() < since)
+            })
+    }
+}
+
+fn parse_date_param(field: &str, value: &str) -> Result<chrono::NaiveDate, CallToolResult> {
+    chrono::NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| {
+        invalid_params_result(
+            "Dates must be formatted as YYYY-MM-DD",
+            &[(field, "Date has wrong format. Use YYYY-MM-DD.")],
+        )
+    })
+}
+
+/// Translate `search_recipes` parameters into a [`RecipeSearch`], looking up the IDs of
+/// named keywords, foods and books. Unknown names are rejected rather than ignored, since
+/// dropping a filter silently would return misleading results.
+async fn build_recipe_search(
+    client: &TandoorClient,
+    params: &SearchRecipesParams,
+) -> Result<RecipeSearch, CallToolResult> {
+    let mut filter = RecipeSearchFilter {
+        query: params
+            .query
+            .clone()
+            .filter(|query| !query.trim().is_empty()),
+        internal: params.internal,
+        random: params.random,
+        ..RecipeSearchFilter::default()
+    };
+    let mut described = Vec::new();
+
+    if let Some(rating) = params.min_rating {
+        if !(1..=5).contains(&rating) {
+            return Err(invalid_params_result(
+                "Ratings range from 1 to 5",
+                &[("min_rating", "Ensure this value is between 1 and 5.")],
+            ));
+        }
+        filter.rating = Some(RatingFilter::AtLeast(rating));
+        described.push(format!("rated {rating}+"));
+    }
+    if let Some(date) = &params.cooked_since {
+        let date = parse_date_param("cooked_since", date)?;
+        filter.cooked_on = Some(DateFilter::OnOrAfter(date));
+        described.push(format!("cooked since {date}"));
+    }
+    if let Some(date) = &params.created_since {
+        let date = parse_date_param("created_since", date)?;
+        filter.created_on = Some(DateFilter::OnOrAfter(date));
+        described.push(format!("created since {date}"));
+    }
+    let not_cooked_since = match &params.not_cooked_since {
+        Some(date) => {
+            let date = parse_date_param("not_cooked_since", date)?;
+            described.push(format!("not cooked since {date}"));
+            Some(date)
+        }
+        None => None,
+    };
+    if let Some(max) = params.max_total_time {
+        described.push(format!("ready in {max} minutes or less"));
+    }
+    if let Some(sort) = &params.sort {
+        let (descending, name) = match sort.trim().strip_prefix('-') {
+            Some(name) => (true, name),
+            None => (false, sort.as_str()),
+        };
+        filter.sort_order = Some(
+            name.parse::<RecipeSortOrder>()
+                .map_err(|message| invalid_params_result(&message, &[("sort", &message)]))?,
+        );
+        filter.sort_descending = descending;
+    }
+
+    let names = |list: &Option<Vec<String>>| list.clone().unwrap_or_default();
+    let (keywords, any_keywords, exclude_keywords) = (
+        names(&params.keywords),
+        names(&params.any_keywords),
+        names(&params.exclude_keywords),
+    );
+    let (foods, exclude_foods, books) = (
+        names(&params.foods),
+        names(&params.exclude_foods),
+        names(&params.books),
+    );
+    let mut unknown: Vec<(&str, String)> = Vec::new();
+
+    if !(keywords.is_empty() && any_keywords.is_empty() && exclude_keywords.is_empty()) {
+        let known = client
+            .get_keywords()
+            .await
+            .map_err(|e| error_result("Failed to look up keywords", e))?
+            .results;
+        let mut lookup = |field: &'static str, wanted: &[String], ids: &mut Vec<i32>| {
+            for name in wanted {
+                match known
+                    .iter()
+                    .find(|k| k.name.eq_ignore_ascii_case(name.trim()))
+                {
+                    Some(keyword) => ids.push(keyword.id),
+                    None => unknown.push((field, format!("Unknown keyword '{name}'"))),
+                }
+            }
+        };
+        lookup("keywords", &keywords, &mut filter.keywords_and);
+        lookup("any_keywords", &any_keywords, &mut filter.keywords_or);
+        lookup(
+            "exclude_keywords",
+            &exclude_keywords,
+            &mut filter.keywords_or_not,
+        );
+    }
+
+    for (field, wanted, ids) in [
+        ("foods", &foods, &mut filter.foods_and),
+        ("exclude_foods", &exclude_foods, &mut filter.foods_or_not),
+    ] {
+        for name in wanted {
+            let matches = client
+                .search_foods(name, Some(10))
+                .await
+                .map_err(|e| error_result("Failed to look up foods", e))?;
+            match matches
+                .results
+                .iter()
+                .find(|food| food_has_name(food, name.trim()))
+            {
+                Some(food) => ids.push(food.id),
+                None => unknown.push((field, format!("Unknown food '{name}'"))),
+            }
+        }
+    }
+
+    if !books.is_empty() {
+        let known = client
+            .get_recipe_books()
+            .await
+            .map_err(|e| error_result("Failed to look up recipe books", e))?
+            .results;
+        for name in &books {
+            match known
+                .iter()
+                .find(|b| b.name.eq_ignore_ascii_case(name.trim()))
+            {
+                Some(book) => filter.books_or.push(book.id),
+                None => unknown.push(("books", format!("Unknown recipe book '{name}'"))),
+            }
+        }
+    }
+
+    if !unknown.is_empty() {
+        let field_errors: Vec<(&str, &str)> = unknown
+            .iter()
+            .map(|(field, message)| (*field, message.as_str()))
+            .collect();
+        return Err(invalid_params_result(
+            "Some filter names do not match anything in Tandoor",
+            &field_errors,
+        ));
+    }
+
+    if !keywords.is_empty() {
+        described.push(format!("with keywords {}", keywords.join(", ")));
+    }
+    if !any_keywords.is_empty() {
+        described.push(format!("with any of {}", any_keywords.join(", ")));
+    }
+    if !exclude_keywords.is_empty() {
+        described.push(format!("without {}", exclude_keywords.join(", ")));
+    }
+    if !foods.is_empty() {
+        described.push(format!("using {}", foods.join(", ")));
+    }
+    if !exclude_foods.is_empty() {
+        described.push(format!("not using {}", exclude_foods.join(", ")));
+    }
+    if !books.is_empty() {
+        described.push(format!("in {}", books.join(" or ")));
+    }
+
+    Ok(RecipeSearch {
+        filter,
+        max_total_time: params.max_total_time,
+        not_cooked_since,
+        described,
+    })
+}
+
+/// # Tandoor MCP Server
+///
+/// The main MCP server implementation that provides Tandoor functionality through
+/// standardized tools. This server handles all communication with Tandoor APIs
+/// and exposes them as MCP tools for AI assistants.
+///
+/// ## Rate Limiting Considerations
+///
+/// Tandoor has aggressive rate limiting on authentication endpoints (10 requests/day).
+/// The server handles this by:
+/// - Reusing its token across requests
+/// - Caching tokens on disk when a [`TokenStore`] is attached
+/// - Supporting pre-set tokens via environment variables
+///
+/// ## Multiple Accounts
+///
+/// Credentials and tokens belong to each server instance, so one process can run
+/// several servers for different Tandoor accounts. Clones of a server share its client.
+///
+/// ## Example Usage
+///
+/// ```no_run
+/// use mcp_tandoor::server::TandoorMcpServer;
+///
+/// # async fn example() -> anyhow::Result<()> {
+/// // Create server with credentials
+/// let server = TandoorMcpServer::new_with_credentials(
+///     "http://localhost:8080".to_string(),
+///     "admin".to_string(),
+///     "password".to_string()
+/// );
+///
+/// // Or set a pre-authenticated token
+/// server.set_auth_token("your_token".to_string());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct TandoorMcpServer {
+    /// Client for Tandoor API communication, shared by every concurrent tool call
+    client: Arc<TandoorClient>,
+    /// Where meal plan templates are saved; templates are disabled without one
+    meal_templates: Arc<RwLock<Option<MealTemplateStore>>>,
+    /// MCP tool router for handling tool requests
+    tool_router: ToolRouter<TandoorMcpServer>,
+}
+
+#[tool_router]
+impl TandoorMcpServer {
+    /// Create a new Tandoor MCP server with just a base URL.
+    /// Authentication will need to be handled separately.
+    pub fn new(base_url: String) -> Self {
+        Self {
+            client: Arc::new(TandoorClient::new(base_url)),
+            meal_templates: Arc::new(RwLock::new(None)),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    /// Create a new Tandoor MCP server with credentials for automatic authentication.
+    ///
+    /// The credentials belong to this server and are used to log in on first use and
+    /// to re-authenticate when tokens expire. This is the recommended constructor for
+    /// most use cases.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the Tandoor server (e.g., "http://localhost:8080")
+    /// * `username` - Tandoor username for authentication
+    /// * `password` - Tandoor password for authentication
+    pub fn new_with_credentials(base_url: String, username: String, password: String) -> Self {
+        Self::new_with_credential_provider(base_url, Arc::new(Credentials::new(username, password)))
+    }
+
+    /// Create a new Tandoor MCP server that asks `provider` for credentials whenever it
+    /// has to log in.
+    pub fn new_with_credential_provider(
+        base_url: String,
+        provider: Arc<dyn CredentialProvider>,
+    ) -> Self {
+        let client = TandoorClient::new(base_url);
+        client.set_credential_provider(provider);
+
+        Self {
+            client: Arc::new(client),
+            meal_templates: Arc::new(RwLock::new(None)),
+            tool_router: Self::tool_router(),
+        }
+    }
+
+    /// Set a pre-authenticated token to avoid rate limiting.
+    ///
+    /// This is useful when you have a token from a previous authentication
+    /// or from an external source. Tandoor limits authentication to 10 requests
+    /// per day, so reusing tokens is important for reliability. If the server has
+    /// credentials, they are still used to re-authenticate once the token expires.
+    ///
+    /// # Arguments
+    ///
+    /// * `token` - A valid Tandoor OAuth2 access token
+    pub fn set_auth_token(&self, token: String) {
+        self.client.set_token(token);
+        tracing::debug!("Auth token updated");
+    }
+
+    /// Cache issued tokens on disk so restarts can reuse them instead of logging in.
+    pub fn set_token_store(&self, store: TokenStore) {
+        self.client.set_token_store(store);
+    }
+
+    /// Save meal plan templates in `store`, enabling the template tools.
+    pub fn set_meal_template_store(&self, store: MealTemplateStore) {
+        *self.meal_templates.write().unwrap() = Some(store);
+    }
+
+    /// The meal template store, or an error result when templates are disabled.
+    fn meal_template_store(&self) -> Result<MealTemplateStore, CallToolResult> {
+        self.meal_templates.read().unwrap().clone().ok_or_else(|| {
+            error_result(
+                "Meal plan templates are disabled",
+                anyhow::anyhow!("Set TANDOOR_MEAL_TEMPLATES to a file path to save templates"),
+            )
+        })
+    }
+
+    /// Log in as `username`, replacing the credentials this server was created with.
+    pub async fn authenticate(
+        &self,
+        username: String,
+        password: String,
+    ) -> Result<(), anyhow::Error> {
+        self.client.authenticate(username, password).await
+    }
+
+    /// The shared client, logged in with the credential provider if it has no token yet.
+    ///
+    /// Tool calls never wait on each other here; only a call that finds the client
+    /// logged out waits for the (single) login.
+    async fn ensure_authenticated(&self) -> Result<&TandoorClient, anyhow::Error> {
+        if !self.client.is_authenticated() {
+            tracing::info!("Not authenticated, logging in with stored credentials");
+            self.client.ensure_authenticated().await.map_err(|e| {
+                tracing::error!("Authentication failed: {}", e);
+                e
+            })?;
+        }
+        Ok(&self.client)
+    }
+
+    pub async fn test_api_access(&self) -> Result<(), anyhow::Error> {
+        // Just check if we can authenticate - don't make actual API calls during startup
+        let client = self.ensure_authenticated().await?;
+
+        // Check if we have a token
+        if client.is_authenticated() {
+            if let Some(preview) = client.get_token_preview() {
+                tracing::info!("Authentication test successful - have token: {}", preview);
+            }
+            Ok(())
+        } else {
+            tracing::error!("Authentication test failed - no token available");
+            Err(anyhow::anyhow!("No authentication token available"))
+        }
+    }
+
+    // Recipe tools
+    #[tool(
+        description = "Search for recipes by text and filters: keywords (all/any/exclude), foods, recipe books, minimum rating, cooked or not cooked since a date, created since a date, maximum total time, internal, random order and sort order. Names are matched against Tandoor's keywords, foods and books."
+    )]
+    async fn search_recipes(
+        &self,
+        Parameters(params): Parameters<SearchRecipesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Ensure we're authenticated before making API calls
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in search_recipes: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let search = match build_recipe_search(client, &params).await {
+            Ok(search) => search,
+            Err(result) => return Ok(result),
+        };
+        let current_page = params.page.unwrap_or(1).max(1);
+
+        let mut truncated = false;
+        let (recipes, total_count, has_next) = if search.filters_locally() {
+            // Tandoor cannot apply these filters, so page through its matches here
+            let limit = params
+                .limit
+                .filter(|limit| *limit > 0)
+                .map_or(DEFAULT_SEARCH_LIMIT, |limit| limit as usize);
+            let mut scanned = 0;
+            let matches: Vec<Recipe> = match client
+                .search_all_recipes(&search.filter, Some(MAX_SCANNED_RECIPES))
+                .inspect_ok(|_| scanned += 1)
+                .try_filter(|recipe| std::future::ready(search.keeps(recipe)))
+                .try_collect()
+                .await
+            {
+                Ok(matches) => matches,
+                Err(e) => return Ok(error_result("Failed to search recipes", e)),
+            };
+            truncated = scanned >= MAX_SCANNED_RECIPES;
+            let start = (current_page as usize - 1) * limit;
+            let total = matches.len();
+            let page = matches.into_iter().skip(start).take(limit).collect();
+            (page, total as i32, start + limit < total)
+        } else {
+            match client
+                .search_recipes_filtered(&search.filter, params.limit, params.page)
+                .await
+            {
+                Ok(response) => (response.results, response.count, response.next.is_some()),
+                Err(e) => return Ok(error_result("Failed to search recipes", e)),
+            }
+        };
+
+        let recipes_json: Vec<serde_json::Value> = recipes
+            .into_iter()
+            .map(|recipe| {
+                json!({
+                    "id": recipe.id,
+                    "name": recipe.name,
+                    "description": recipe.description,
+                    "total_time": recipe.working_time.unwrap_or(0) + recipe.waiting_time.unwrap_or(0),
+                    "servings": recipe.servings,
+                    "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<String>>(),
+                    "rating": recipe.rating,
+                    "last_cooked": recipe.last_cooked,
+                    "created": recipe.created,
+                    "updated": recipe.updated
+                })
+            })
+            .collect();
+
+        let mut interpretation = format!(
+            "Found {} recipes{}",
+            total_count,
+            params
+                .query
+                .as_ref()
+                .map_or(String::new(), |q| format!(" matching '{q}'"))
+        );
+        if !search.described.is_empty() {
+            interpretation.push_str(&format!(" ({})", search.described.join("; ")));
+        }
+        if truncated {
+            interpretation.push_str(&format!(
+                "; only the first {MAX_SCANNED_RECIPES} recipes were checked, narrow the search to see the rest"
+            ));
+        }
+
+        let mut result = json!({
+            "recipes": recipes_json,
+            "total_count": total_count,
+            "current_page": current_page,
+            "next_page": if has_next { Some(current_page + 1) } else { None },
+            "search_interpretation": interpretation
+        });
+        if truncated {
+            result["truncated"] = json!(true);
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
+    }
+
+    #[tool(description = "Get comprehensive recipe information including scaled ingredients")]
+    async fn get_recipe_details(
+        &self,
+        Parameters(params): Parameters<GetRecipeDetailsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Ensure we're authenticated before making API calls
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in get_recipe_details: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        match client.get_recipe(params.id).await {
+            Ok(recipe) => {
+                let mut ingredients = Vec::new();
+                let scaling_factor = if let Some(target_servings) = params.servings {
+                    if let Some(original_servings) = recipe.servings {
+                        target_servings as f64 / original_servings as f64
+                    } else {
+                        1.0
+                    }
+                } else {
+                    1.0
+                };
+
+                for step in &recipe.steps {
+                    for ingredient in &step.ingredients {
+                        let (amount, unit) = if scaling_factor != 1.0 {
+                            scale_amount(
+                                ingredient.amount,
+                                ingredient.unit.as_ref(),
+                                scaling_factor,
+                            )
+                        } else {
+                            (
+                                ingredient.amount,
+                                ingredient.unit.as_ref().map(|u| u.name.clone()),
+                            )
+                        };
+                        ingredients.push(json!({
+                            "id": ingredient.id,
+                            "step_id": step.id,
+                            "food": ingredient.food.name,
+                            "amount": amount,
+                            "unit": unit,
+                            "note": ingredient.note,
+                            "is_header": ingredient.is_header,
+                            "no_amount": ingredient.no_amount
+                        }));
+                    }
+                }
+
+                let steps: Vec<serde_json::Value> = recipe
+                    .steps
+                    .iter()
+                    .map(|step| json!({"id": step.id, "name": step.name, "time": step.time}))
+                    .collect();
+                let instructions: Vec<String> = recipe
+                    .steps
+                    .into_iter()
+                    .map(|step| {
+                        if step.name.is_empty() {
+                            step.instruction
+                        } else {
+                            format!("{}: {}", step.name, step.instruction)
+                        }
+                    })
+                    .collect();
+
+                let result = json!({
+                    "id": recipe.id,
+                    "name": recipe.name,
+                    "description": recipe.description,
+                    "instructions": instructions,
+                    "steps": steps,
+                    "ingredients": ingredients,
+                    "servings": params.servings.unwrap_or(recipe.servings.unwrap_or(1)),
+                    "working_time": recipe.working_time,
+                    "waiting_time": recipe.waiting_time,
+                    "total_time": recipe.working_time.unwrap_or(0) + recipe.waiting_time.unwrap_or(0),
+                    "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<String>>(),
+                    "nutrition": recipe.nutrition,
+                    "created": recipe.created,
+                    "updated": recipe.updated,
+                    "scaling_applied": scaling_factor != 1.0
+                });
+
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => Ok(error_result("Failed to get recipe details", e)),
+        }
+    }
+
+    #[tool(
+        description = "Create a new recipe. Give plain 'instructions', or 'steps' that each have an instruction and ingredients, either structured (food, amount, unit, note) or as free-text 'ingredient_lines' like '2 cloves garlic, minced'. Existing foods and units are reused; the result lists any that were newly created."
+    )]
+    async fn create_recipe(
+        &self,
+        Parameters(params): Parameters<CreateRecipeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Ensure we're authenticated before making API calls
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in create_recipe: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        // Convert keywords from strings to CreateKeywordRequest
+        let keywords = params
+            .keywords
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| crate::client::types::CreateKeywordRequest { name })
+            .collect();
+
+        let step_params = match (params.steps, params.instructions) {
+            (Some(_), Some(_)) => {
+                return Ok(invalid_params_result(
+                    "Provide either 'instructions' or 'steps', not both",
+                    &[("instructions", "Put the instructions in a step instead")],
+                ));
+            }
+            (Some(steps), None) => steps,
+            // Plain instructions become a single step without ingredients
+            (None, Some(instruction)) => vec![RecipeStepParams {
+                name: None,
+                instruction,
+                time: None,
+                ingredients: None,
+                ingredient_lines: None,
+            }],
+            (None, None) => vec![],
+        };
+
+        let wanted = match step_params
+            .iter()
+            .enumerate()
+            .map(|(index, step)| collect_ingredients(step, &format!("steps[{index}]")))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(wanted) => wanted,
+            Err(result) => return Ok(result),
+        };
+        let names = match IngredientNames::resolve(client, &wanted).await {
+            Ok(names) => names,
+            Err(e) => return Ok(error_result("Failed to look up foods and units", e)),
+        };
+
+        let steps = step_params
+            .into_iter()
+            .zip(wanted)
+            .enumerate()
+            .map(
+                |(step_index, (step, ingredients))| crate::client::types::CreateStepRequest {
+                    name: step.name,
+                    instruction: step.instruction,
+                    ingredients: ingredients
+                        .into_iter()
+                        .enumerate()
+                        .map(|(order, ingredient)| {
+                            crate::client::types::CreateStepIngredientRequest {
+                                food: crate::client::types::CreateFoodRequest {
+                                    name: names.food(&ingredient.food),
+                                },
+                                unit: ingredient.unit.as_deref().map(|unit| {
+                                    crate::client::types::CreateUnitRequest {
+                                        name: names.unit(unit),
+                                    }
+                                }),
+                                amount: ingredient.amount.unwrap_or(0.0).to_string(),
+                                note: ingredient.note,
+                                order: order as i32,
+                                is_header: false,
+                                no_amount: ingredient.amount.is_none(),
+                            }
+                        })
+                        .collect(),
+                    time: step.time,
+                    order: step_index as i32,
+                },
+            )
+            .collect();
+
+        let request = crate::client::types::CreateRecipeRequest {
+            name: params.name,
+            description: params.description,
+            servings: params.servings,
+            working_time: params.prep_time.unwrap_or(0),
+            waiting_time: params.cook_time.unwrap_or(0),
+            source_url: None,
+            keywords,
+            steps,
+        };
+
+        match client.create_recipe(request).await {
+            Ok(recipe) => {
+                let result = json!({
+                    "id": recipe.id,
+                    "name": recipe.name,
+                    "description": recipe.description,
+                    "servings": recipe.servings,
+                    "working_time": recipe.working_time,
+                    "waiting_time": recipe.waiting_time,
+                    "created": recipe.created,
+                    "steps": recipe_steps_json(&recipe.steps),
+                    "new_foods": names.new_foods,
+                    "new_units": names.new_units,
+                    "success": true,
+                    "message": "Recipe created successfully"
+                });
+
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                tracing::error!("create_recipe tool failed: {}", e);
+                Ok(error_result("Failed to create recipe", e))
+            }
+        }
+    }
+
+    #[tool(
+        description = "Update an existing recipe: name, description, servings, working/waiting time, source URL, private flag, and step or ingredient edits (add_step, update_step, remove_step, move_step, add_ingredient, update_ingredient, remove_ingredient, move_ingredient). Step and ingredient IDs come from get_recipe_details; only the fields you pass change."
+    )]
+    async fn update_recipe(
+        &self,
+        Parameters(params): Parameters<UpdateRecipeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in update_recipe: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let mut request = crate::client::types::UpdateRecipeRequest {
+            name: params.name,
+            description: params.description,
+            servings: params.servings,
+            working_time: params.working_time,
+            waiting_time: params.waiting_time,
+            source_url: params.source_url,
+            private: params.private,
+            steps: None,
+        };
+
+        let mut names = IngredientNames::default();
+        if let Some(edits) = params.edits.filter(|edits| !edits.is_empty()) {
+            let recipe = match client.get_recipe(params.recipe_id).await {
+                Ok(recipe) => recipe,
+                Err(e) => return Ok(error_result("Failed to get recipe", e)),
+            };
+            let mut steps: Vec<DraftStep> = recipe.steps.into_iter().map(Into::into).collect();
+            let touched = match apply_recipe_edits(&mut steps, edits) {
+                Ok(touched) => touched,
+                Err(result) => return Ok(result),
+            };
+            names = match IngredientNames::resolve(client, &[touched]).await {
+                Ok(names) => names,
+                Err(e) => return Ok(error_result("Failed to look up foods and units", e)),
+            };
+
+            request.steps = Some(
+                steps
+                    .into_iter()
+                    .enumerate()
+                    .map(|(order, step)| crate::client::types::UpdateStepRequest {
+                        id: step.id,
+                        name: step.name,
+                        instruction: step.instruction,
+                        ingredients: step
+                            .ingredients
+                            .into_iter()
+                            .enumerate()
+                            .map(|(order, ingredient)| {
+                                crate::client::types::UpdateStepIngredientRequest {
+                                    id: ingredient.id,
+                                    food: crate::client::types::CreateFoodRequest {
+                                        name: names.food(&ingredient.food),
+                                    },
+                                    unit: ingredient.unit.as_deref().map(|unit| {
+                                        crate::client::types::CreateUnitRequest {
+                                            name: names.unit(unit),
+                                        }
+                                    }),
+                                    amount: ingredient.amount.unwrap_or(0.0).to_string(),
+                                    note: ingredient.note,
+                                    order: order as i32,
+                                    is_header: ingredient.is_header,
+                                    no_amount: ingredient.amount.is_none(),
+                                }
+                            })
+                            .collect(),
+                        time: step.time,
+                        order: order as i32,
+                    })
+                    .collect(),
+            );
+        }
+
+        if serde_json::to_value(&request).is_ok_and(|body| body == json!({})) {
+            return Ok(invalid_params_result(
+                "Nothing to update. Pass at least one recipe field or edit.",
+                &[],
+            ));
+        }
+
+        match client.update_recipe(params.recipe_id, &request).await {
+            Ok(recipe) => {
+                let result = json!({
+                    "id": recipe.id,
+                    "name": recipe.name,
+                    "description": recipe.description,
+                    "servings": recipe.servings,
+                    "working_time": recipe.working_time,
+                    "waiting_time": recipe.waiting_time,
+                    "source_url": recipe.source_url,
+                    "private": recipe.private,
+                    "steps": recipe_steps_json(&recipe.steps),
+                    "new_foods": names.new_foods,
+                    "new_units": names.new_units,
+                    "success": true,
+                    "message": "Recipe updated successfully"
+                });
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                tracing::error!("update_recipe tool failed: {}", e);
+                Ok(error_result("Failed to update recipe", e))
+            }
+        }
+    }
+
+    #[tool(
+        description = "Delete a recipe permanently. The first call only returns what would be deleted and a confirmation token; call again with confirm=<token> to delete."
+    )]
+    async fn delete_recipe(
+        &self,
+        Parameters(params): Parameters<DeleteRecipeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in delete_recipe: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let recipe = match client.get_recipe(params.recipe_id).await {
+            Ok(recipe) => recipe,
+            Err(e) => return Ok(error_result("Failed to get recipe", e)),
+        };
+        let token = confirmation_token("delete-recipe", (recipe.id, recipe.updated));
+        let summary = json!({
+            "id": recipe.id,
+            "name": recipe.name,
+            "steps": recipe.steps.len(),
+            "ingredients": recipe.steps.iter().map(|step| step.ingredients.len()).sum::<usize>(),
+            "updated": recipe.updated
+        });
+
+        match params.confirm {
+            None => {
+                let result = json!({
+                    "recipe": summary,
+                    "deleted": false,
+                    "confirm": token,
+                    "message": format!(
+                        "Nothing deleted yet. Call delete_recipe again with confirm=\"{token}\" to permanently delete '{}'.",
+                        recipe.name
+                    )
+                });
+                return Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]));
+            }
+            Some(confirm) if confirm != token => {
+                return Ok(invalid_params_result(
+                    "Confirmation token does not match this recipe or the recipe changed since it was issued. Call delete_recipe without confirm to get a new token.",
+                    &[("confirm", "Invalid or stale confirmation token")],
+                ));
+            }
+            Some(_) => {}
+        }
+
+        match client.delete_recipe(recipe.id).await {
+            Ok(()) => {
+                let result = json!({
+                    "recipe": summary,
+                    "deleted": true,
+                    "success": true,
+                    "message": "Recipe deleted successfully"
+                });
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                tracing::error!("delete_recipe tool failed: {}", e);
+                Ok(error_result("Failed to delete recipe", e))
+            }
+        }
+    }
+
+    #[tool(
+        description = "Copy a recipe with all its steps and ingredients, e.g. to make your own version. Optionally rename it and rescale ingredients to new servings. The copy gets a keyword linking back to the original."
+    )]
+    async fn duplicate_recipe(
+        &self,
+        Parameters(params): Parameters<DuplicateRecipeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in duplicate_recipe: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        if params.servings.is_some_and(|servings| servings <= 0) {
+            return Ok(invalid_params_result(
+                "Servings must be a positive number",
+                &[(
+                    "servings",
+                    "Ensure this value is greater than or equal to 1.",
+                )],
+            ));
+        }
+
+        let original = match client.get_recipe(params.recipe_id).await {
+            Ok(recipe) => recipe,
+            Err(e) => return Ok(error_result("Failed to get recipe", e)),
+        };
+
+        let original_servings = original.servings.filter(|s| *s > 0).unwrap_or(1);
+        let servings = params.servings.unwrap_or(original_servings);
+        let scaling_factor = servings as f64 / original_servings as f64;
+
+        let link_keyword = format!("copy-of-recipe-{}", original.id);
+        let mut keywords: Vec<crate::client::types::CreateKeywordRequest> = original
+            .keywords
+            .into_iter()
+            .map(|keyword| crate::client::types::CreateKeywordRequest { name: keyword.name })
+            .collect();
+        if !keywords.iter().any(|keyword| keyword.name == link_keyword) {
+            keywords.push(crate::client::types::CreateKeywordRequest {
+                name: link_keyword.clone(),
+            });
+        }
+
+        let steps = original
+            .steps
+            .into_iter()
+            .map(|step| crate::client::types::CreateStepRequest {
+                name: Some(step.name).filter(|name| !name.is_empty()),
+                instruction: step.instruction,
+                ingredients: step
+                    .ingredients
+                    .into_iter()
+                    .map(|ingredient| {
+                        let amount = if ingredient.no_amount || ingredient.is_header {
+                            ingredient.amount
+                        } else {
+                            ingredient.amount * scaling_factor
+                        };
+                        crate::client::types::CreateStepIngredientRequest {
+                            food: crate::client::types::CreateFoodRequest {
+                                name: ingredient.food.name,
+                            },
+                            unit: ingredient.unit.map(|unit| {
+                                crate::client::types::CreateUnitRequest { name: unit.name }
+                            }),
+                            amount: amount.to_string(),
+                            note: ingredient.note,
+                            order: ingredient.order,
+                            is_header: ingredient.is_header,
+                            no_amount: ingredient.no_amount,
+                        }
+                    })
+                    .collect(),
+                time: step.time,
+                order: step.order,
+            })
+            .collect();
+
+        let request = crate::client::types::CreateRecipeRequest {
+            name: params
+                .name
+                .unwrap_or_else(|| format!("{} (copy)", original.name)),
+            description: original.description,
+            servings: Some(servings),
+            working_time: original.working_time.unwrap_or(0),
+            waiting_time: original.waiting_time.unwrap_or(0),
+            source_url: original.source_url,
+            keywords,
+            steps,
+        };
+
+        match client.create_recipe(request).await {
+            Ok(recipe) => {
+                let result = json!({
+                    "id": recipe.id,
+                    "name": recipe.name,
+                    "servings": recipe.servings,
+                    "original": {"id": original.id, "name": original.name, "servings": original_servings},
+                    "scaling_factor": scaling_factor,
+                    "link_keyword": link_keyword,
+                    "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<_>>(),
+                    "steps": recipe_steps_json(&recipe.steps),
+                    "success": true,
+                    "message": "Recipe duplicated successfully"
+                });
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                tracing::error!("duplicate_recipe tool failed: {}", e);
+                Ok(error_result("Failed to duplicate recipe", e))
+            }
+        }
+    }
+
+    #[tool(
+        description = "Import a recipe from a website URL. Use preview=true to inspect the parsed name, ingredients and steps without saving. Optionally adds keywords and a recipe book in the same call."
+    )]
+    async fn import_recipe(
+        &self,
+        Parameters(params): Parameters<ImportRecipeParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in import_recipe: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let parsed = match client.import_recipe_from_url(&params.url).await {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let mut error = error_json("Failed to import recipe", e);
+                error["url"] = json!(params.url);
+                return Ok(CallToolResult::error(vec![Content::text(
+                    error.to_string(),
+                )]));
+            }
+        };
+
+        let duplicates = parsed.duplicates;
+        let Some(mut source) = parsed.recipe else {
+            let error = json!({
+                "error": "Failed to import recipe",
+                "code": "validation_error",
+                "message": "Tandoor returned no recipe for this URL",
+                "retryable": false,
+                "url": params.url
+            });
+            return Ok(CallToolResult::error(vec![Content::text(
+                error.to_string(),
+            )]));
+        };
+
+        if source.source_url.is_none() {
+            source.source_url = Some(params.url.clone());
+        }
+
+        let ingredients: Vec<serde_json::Value> = source
+            .steps
+            .iter()
+            .flat_map(|step| &step.ingredients)
+            .map(|ingredient| {
+                json!({
+                    "food": ingredient.food.as_ref().map(|f| &f.name),
+                    "amount": ingredient.amount,
+                    "unit": ingredient.unit.as_ref().map(|u| &u.name),
+                    "note": ingredient.note,
+                    "original_text": ingredient.original_text
+                })
+            })
+            .collect();
+        // Tandoor cannot save ingredients without a food, so they are reported instead
+        let skipped_ingredients: Vec<String> = source
+            .steps
+            .iter()
+            .flat_map(|step| &step.ingredients)
+            .filter(|ingredient| ingredient.food.is_none())
+            .map(|ingredient| {
+                ingredient
+                    .original_text
+                    .clone()
+                    .or_else(|| ingredient.note.clone())
+                    .unwrap_or_default()
+            })
+            .collect();
+        let instructions: Vec<&str> = source
+            .steps
+            .iter()
+            .map(|step| step.instruction.as_str())
+            .filter(|instruction| !instruction.is_empty())
+            .collect();
+        let mut keywords: Vec<String> = source
+            .keywords
+            .iter()
+            .filter_map(|k| k.display_name())
+            .map(str::to_string)
+            .collect();
+        for kw in params.keywords.unwrap_or_default() {
+            if !keywords.contains(&kw) {
+                keywords.push(kw);
+            }
+        }
+
+        let preview = json!({
+            "name": source.name,
+            "description": source.description,
+            "servings": source.servings,
+            "working_time": source.working_time,
+            "waiting_time": source.waiting_time,
+            "source_url": source.source_url,
+            "keywords": keywords,
+            "ingredients": ingredients,
+            "instructions": instructions,
+            "possible_duplicates": duplicates
+        });
+
+        if params.preview {
+            let result = json!({
+                "preview": preview,
+                "skipped_ingredients": skipped_ingredients,
+                "saved": false,
+                "message": "Recipe parsed but not saved. Call again with preview=false to import it."
+            });
+            return Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap(),
+            )]));
+        }
+
+        let mut request = crate::client::types::CreateRecipeRequest::from(source);
+        request.keywords = keywords
+            .into_iter()
+            .map(|name| crate::client::types::CreateKeywordRequest { name })
+            .collect();
+
+        let recipe = match client.create_recipe(request).await {
+            Ok(recipe) => recipe,
+            Err(e) => {
+                tracing::error!("import_recipe tool failed to save recipe: {}", e);
+                let mut error = error_json("Failed to save imported recipe", e);
+                error["preview"] = preview;
+                return Ok(CallToolResult::error(vec![Content::text(
+                    error.to_string(),
+                )]));
+            }
+        };
+
+        let book = match params.book_id {
+            Some(book_id) => match client.add_recipe_to_book(book_id, recipe.id).await {
+                Ok(entry) => json!({"book_id": entry.book, "added": true}),
+                Err(e) => {
+                    let mut error = error_json("Failed to add recipe to book", e);
+                    error["book_id"] = json!(book_id);
+                    error["added"] = json!(false);
+                    error
+                }
+            },
+            None => serde_json::Value::Null,
+        };
+
+        let result = json!({
+            "id": recipe.id,
+            "name": recipe.name,
+            "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<_>>(),
+            "source_url": recipe.source_url,
+            "book": book,
+            "preview": preview,
+            "skipped_ingredients": skipped_ingredients,
+            "saved": true,
+            "success": true,
+            "message": "Recipe imported successfully"
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
+    }
+
+    #[tool(
+        description = "Update the keywords/tags on an existing recipe. Use mode='set' to replace all tags (default), or mode='add' to append to existing tags."
+    )]
+    async fn update_recipe_keywords(
+        &self,
+        Parameters(params): Parameters<UpdateRecipeKeywordsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in update_recipe_keywords: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        // If "add" mode, fetch existing keywords first and merge
+        let final_keywords = if params.mode == "add" {
+            match client.get_recipe(params.recipe_id).await {
+                Ok(recipe) => {
+                    let mut all_keywords: Vec<String> =
+                        recipe.keywords.into_iter().map(|k| k.name).collect();
+                    for kw in &params.keywords {
+                        if !all_keywords.contains(kw) {
+                            all_keywords.push(kw.clone());
+                        }
+                    }
+                    all_keywords
+                }
+                Err(e) => {
+                    return Ok(error_result("Failed to fetch existing recipe keywords", e));
+                }
+            }
+        } else {
+            params.keywords
+        };
+
+        let keyword_requests = final_keywords
+            .into_iter()
+            .map(|name| crate::client::types::CreateKeywordRequest { name })
+            .collect();
+
+        match client
+            .patch_recipe_keywords(params.recipe_id, keyword_requests)
+            .await
+        {
+            Ok(recipe) => {
+                let result = json!({
+                    "id": recipe.id,
+                    "name": recipe.name,
+                    "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<_>>(),
+                    "success": true
+                });
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => Ok(error_result("Failed to update recipe keywords", e)),
+        }
+    }
+
+    #[tool(description = "Get all recipe books")]
+    async fn get_recipe_books(&self) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(c) => c,
+            Err(e) => return Ok(error_result("Authentication Error", e)),
+        };
+
+        match client.get_recipe_books().await {
+            Ok(response) => {
+                let books: Vec<serde_json::Value> = response
+                    .results
+                    .into_iter()
+                    .map(|b| json!({"id": b.id, "name": b.name, "description": b.description}))
+                    .collect();
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(
+                        &json!({"books": books, "total_count": response.count}),
+                    )
+                    .unwrap(),
+                )]))
+            }
+            Err(e) => Ok(error_result("Failed to get recipe books", e)),
+        }
+    }
+
+    #[tool(description = "Create a new recipe book (collection)")]
+    async fn create_recipe_book(
+        &self,
+        Parameters(params): Parameters<CreateRecipeBookParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(c) => c,
+            Err(e) => return Ok(error_result("Authentication Error", e)),
+        };
+
+        let request = crate::client::types::CreateRecipeBookRequest {
+            name: params.name,
+            description: params.description.unwrap_or_default(),
+            shared: vec![],
+        };
+
+        match client.create_recipe_book(request).await {
+            Ok(book) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(
+                    &json!({"id": book.id, "name": book.name, "description": book.description, "success": true}),
+                ).unwrap(),
+            )])),
+            Err(e) => Ok(error_result("Failed to create recipe book", e)),
+        }
+    }
+
+    #[tool(description = "Add a recipe to a recipe book")]
+    async fn add_recipe_to_book(
+        &self,
+        Parameters(params): Parameters<AddRecipeToBookParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(c) => c,
+            Err(e) => return Ok(error_result("Authentication Error", e)),
+        };
+
+        match client.add_recipe_to_book(params.book_id, params.recipe_id).await {
+            Ok(entry) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(
+                    &json!({"id": entry.id, "book_id": entry.book, "recipe_id": entry.recipe, "success": true}),
+                ).unwrap(),
+            )])),
+            Err(e) => Ok(error_result("Failed to add recipe to book", e)),
+        }
+    }
+
+    // Shopping list tools
+    #[tool(
+        description = "Add items to the shopping list, either as structured items or as a free-text request like '2 lbs chicken thighs, a dozen eggs, 500g flour'. Items for the same food are merged when their units can be added, e.g. '500g flour, 1 kg flour'."
+    )]
+    async fn add_to_shopping_list(
+        &self,
+        Parameters(params): Parameters<AddToShoppingListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Ensure we're authenticated before making API calls
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in add_to_shopping_list: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let mut requests: Vec<(
+            crate::client::types::CreateShoppingListEntryRequest,
+            Option<&Unit>,
+        )> = Vec::new();
+        let mut added = Vec::new();
+        let mut errors = Vec::new();
+        let mut parsed_items = Vec::new();
+        let mut merged_count = 0;
+
+        // Both branches resolve to (name, amount, unit) and share the lookup below
+        let wanted: Vec<(String, f64, Option<String>)> = if let Some(items) = params.items {
+            items
+                .into_iter()
+                .map(|item| (item.name, item.amount, item.unit))
+                .collect()
+        } else if let Some(request_text) = params.request {
+            let parsed = ingredient_parser::parse_request(&request_text);
+            if parsed.is_empty() {
+                return Ok(invalid_params_result(
+                    "Could not understand request. List items separated by commas, e.g. '2 lbs chicken thighs, a dozen eggs, 500g flour'",
+                    &[("request", "No items found")],
+                ));
+            }
+            let wanted = parsed
+                .iter()
+                .map(|item| {
+                    (
+                        item.food.clone(),
+                        item.amount.unwrap_or(1.0),
+                        item.unit.clone(),
+                    )
+                })
+                .collect();
+            parsed_items = parsed;
+            wanted
+        } else if let Some(from_recipe) = params.from_recipe {
+            return Ok(add_recipe_ingredients(client, from_recipe).await);
+        } else {
+            return Ok(invalid_params_result(
+                "Please provide an 'items' array, 'request' text, or 'from_recipe'",
+                &[],
+            ));
+        };
+
+        // Only fetch units when something actually asks for one
+        let units = if wanted.iter().any(|(_, _, unit)| unit.is_some()) {
+            match client.get_units().await {
+                Ok(response) => response.results,
+                Err(e) => {
+                    errors.push(error_json(
+                        "Failed to get units, adding items without units",
+                        e,
+                    ));
+                    Vec::new()
+                }
+            }
+        } else {
+            Vec::new()
+        };
+
+        for (name, amount, unit_name) in wanted {
+            match resolve_food(client, &name).await {
+                Ok(Some(food)) => {
+                    let unit = unit_name.as_deref().and_then(|u| resolve_unit(&units, u));
+                    if unit_name.is_some() && unit.is_none() {
+                        errors.push(json!({
+                            "food": name,
+                            "unit": unit_name,
+                            "error": "Unit not found, item added without a unit"
+                        }));
+                    }
+                    // Several items for one food become one entry when their units add up
+                    let existing = requests
+                        .iter_mut()
+                        .filter(|(request, _)| request.food == food.id)
+                        .find_map(|(request, request_unit)| {
+                            add_amounts((request.amount, *request_unit), (amount, unit))
+                                .map(|total| (request, total))
+                        });
+                    if let Some((request, total)) = existing {
+                        request.amount = total;
+                        merged_count += 1;
+                        continue;
+                    }
+                    requests.push((
+                        crate::client::types::CreateShoppingListEntryRequest {
+                            food: food.id,
+                            unit: unit.map(|u| u.id),
+                            amount,
+                            list_recipe: None,
+                            ingredient: None,
+                        },
+                        unit,
+                    ));
+                }
+                Ok(None) => {
+                    errors.push(json!({
+                        "food": name,
+                        "error": "Food not found",
+                        "suggestion": "Try creating the food first or use a different name"
+                    }));
+                }
+                Err(e) => {
+                    let mut error = error_json("Failed to search for food", e);
+                    error["food"] = json!(name);
+                    errors.push(error);
+                }
+            }
+        }
+
+        if !requests.is_empty() {
+            let requests = requests.into_iter().map(|(request, _)| request).collect();
+            match client.add_bulk_to_shopping_list(requests).await {
+                Ok(entries) => {
+                    for entry in entries {
+                        added.push(json!({
+                            "id": entry.id,
+                            "food": entry.food.name,
+                            "amount": entry.amount,
+                            "unit": entry.unit.as_ref().map(|u| &u.name),
+                            "status": "added"
+                        }));
+                    }
                 }
                 Err(e) => {
-                    tracing::error!("Authentication failed: {}", e);
-                    Err(e)
+                    errors.push(error_json("Failed to add items to shopping list", e));
                 }
             }
+        }
+
+        let mut summary = format!("Added {} items, {} errors", added.len(), errors.len());
+        if merged_count > 0 {
+            summary.push_str(&format!(
+                ", {merged_count} duplicate items merged into existing ones"
+            ));
+        }
+        let mut result = json!({
+            "added": added,
+            "errors": errors,
+            "summary": summary
+        });
+        if !parsed_items.is_empty() {
+            // Shopping list entries have no note, so notes are reported rather than saved
+            let notes: Vec<_> = parsed_items
+                .iter()
+                .filter_map(|item| {
+                    let note = item.note.as_ref()?;
+                    Some(json!({"food": item.food, "note": note}))
+                })
+                .collect();
+            result["parsed"] = parsed_items
+                .iter()
+                .map(|item| {
+                    json!({
+                        "original": item.original,
+                        "amount": item.amount,
+                        "unit": item.unit,
+                        "food": item.food
+                    })
+                })
+                .collect();
+            if !notes.is_empty() {
+                result["notes_not_saved"] = json!(notes);
+            }
+        }
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
+    }
+
+    #[tool(
+        description = "Get the shopping list. By default unchecked entries for the same food are merged into one item with a total amount (entry_ids lists the merged entries). Use format 'by_aisle' to group items by store section in the order of a supermarket (name or ID in 'supermarket'), 'flat' for every entry or 'grouped' to split unchecked from checked entries."
+    )]
+    async fn get_shopping_list(
+        &self,
+        Parameters(params): Parameters<GetShoppingListParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Ensure we're authenticated before making API calls
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in get_shopping_list: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        if !["consolidated", "by_aisle", "flat", "grouped"].contains(&params.format.as_str()) {
+            return Ok(invalid_params_result(
+                "Unknown shopping list format",
+                &[(
+                    "format",
+                    "Use 'consolidated', 'by_aisle', 'flat' or 'grouped'.",
+                )],
+            ));
+        }
+        if params.supermarket.is_some() && params.format != "by_aisle" {
+            return Ok(invalid_params_result(
+                "A supermarket only applies to the by_aisle format",
+                &[(
+                    "supermarket",
+                    "Use format 'by_aisle' to sort the list by a supermarket's sections.",
+                )],
+            ));
+        }
+
+        let supermarkets = if params.format == "by_aisle" {
+            match client.get_supermarkets().await {
+                Ok(response) => response.results,
+                Err(e) => return Ok(error_result("Failed to get supermarkets", e)),
+            }
         } else {
-            tracing::error!("No authentication credentials available");
-            Err(anyhow::anyhow!("No authentication credentials available"))
+            Vec::new()
+        };
+        let supermarket = match choose_supermarket(&supermarkets, params.supermarket.as_ref()) {
+            Ok(supermarket) => supermarket,
+            Err(result) => return Ok(result),
+        };
+
+        match client.get_shopping_list().await {
+            Ok(response) => {
+                let result = match params.format.as_str() {
+                    "flat" => json!({
+                        "items": response.results.iter().map(shopping_entry_json).collect::<Vec<_>>(),
+                        "total_items": response.count,
+                        "format": "flat"
+                    }),
+                    "grouped" => {
+                        let (checked, unchecked): (Vec<_>, Vec<_>) =
+                            response.results.iter().partition(|entry| entry.checked);
+                        json!({
+                            "unchecked_items": unchecked.into_iter().map(shopping_entry_json).collect::<Vec<_>>(),
+                            "checked_items": checked.into_iter().map(shopping_entry_json).collect::<Vec<_>>(),
+                            "total_items": response.count,
+                            "format": "grouped"
+                        })
+                    }
+                    "by_aisle" => {
+                        let consolidated = consolidate_entries(&response.results, false);
+                        let mut result = json!({
+                            "aisles": group_by_aisle(&consolidated, supermarket),
+                            "checked_items": response.results.iter().filter(|entry| entry.checked).map(shopping_entry_json).collect::<Vec<_>>(),
+                            "supermarket": supermarket.map(|m| &m.name),
+                            "total_items": response.count,
+                            "format": "by_aisle"
+                        });
+                        if supermarket.is_none() && !supermarkets.is_empty() {
+                            result["available_supermarkets"] =
+                                json!(supermarkets.iter().map(|m| &m.name).collect::<Vec<_>>());
+                        }
+                        result
+                    }
+                    _ => {
+                        let consolidated = consolidate_entries(&response.results, false);
+                        let unchecked_count = response
+                            .results
+                            .iter()
+                            .filter(|entry| !entry.checked)
+                            .count();
+                        json!({
+                            "items": consolidated.iter().map(ConsolidatedEntry::to_json).collect::<Vec<_>>(),
+                            "checked_items": response.results.iter().filter(|entry| entry.checked).map(shopping_entry_json).collect::<Vec<_>>(),
+                            "total_items": response.count,
+                            "duplicates_merged": unchecked_count - consolidated.len(),
+                            "format": "consolidated"
+                        })
+                    }
+                };
+
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                tracing::error!("get_shopping_list tool failed: {}", e);
+                Ok(error_result("Failed to get shopping list", e))
+            }
         }
     }
 
-    pub async fn test_api_access(&self) -> Result<(), anyhow::Error> {
-        // Just check if we can authenticate - don't make actual API calls during startup
-        // to avoid holding locks during network I/O
-        let client = self.ensure_authenticated().await?;
+    #[tool(
+        description = "Merge duplicate unchecked shopping list entries for the same food into one entry with the total amount, when their units can be added. Entries added from different recipes are not merged, so each recipe can still be removed on its own. The first entry is kept and the others are deleted. Use dry_run to preview."
+    )]
+    async fn merge_shopping_list_duplicates(
+        &self,
+        Parameters(params): Parameters<MergeShoppingListDuplicatesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!(
+                    "Authentication failed in merge_shopping_list_duplicates: {}",
+                    e
+                );
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
 
-        // Check if we have a token
-        if client.is_authenticated() {
-            if let Some(preview) = client.get_token_preview() {
-                tracing::info!("Authentication test successful - have token: {}", preview);
+        let entries = match client.get_shopping_list().await {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get shopping list", e)),
+        };
+
+        let mut merged = Vec::new();
+        let mut errors = Vec::new();
+        for group in consolidate_entries(&entries, true)
+            .into_iter()
+            .filter(|group| group.entries.len() > 1)
+        {
+            let kept = group.first();
+            let removed: Vec<i32> = group.entries[1..].iter().map(|entry| entry.id).collect();
+            let mut report = json!({
+                "food": kept.food.name,
+                "amount": group.amount,
+                "unit": kept.unit.as_ref().map(|u| &u.name),
+                "kept_id": kept.id,
+                "removed_ids": removed
+            });
+
+            if !params.dry_run {
+                // Update before deleting, so a failure never loses an amount
+                let request = crate::client::types::UpdateShoppingListEntryRequest {
+                    checked: None,
+                    amount: Some(group.amount),
+                };
+                if let Err(e) = client.update_shopping_list_entry(kept.id, request).await {
+                    let mut error = error_json("Failed to update merged entry", e);
+                    error["food"] = json!(kept.food.name);
+                    errors.push(error);
+                    continue;
+                }
+                for id in &removed {
+                    if let Err(e) = client.delete_shopping_list_entry(*id).await {
+                        let mut error = error_json("Failed to delete duplicate entry", e);
+                        error["item_id"] = json!(id);
+                        errors.push(error);
+                        report["partially_merged"] = json!(true);
+                    }
+                }
             }
-            Ok(())
-        } else {
-            tracing::error!("Authentication test failed - no token available");
-            Err(anyhow::anyhow!("No authentication token available"))
+            merged.push(report);
         }
-        // Lock is automatically released here when client goes out of scope
+
+        let removed_count: usize = merged
+            .iter()
+            .map(|report| report["removed_ids"].as_array().map_or(0, Vec::len))
+            .sum();
+        let result = json!({
+            "merged": merged,
+            "errors": errors,
+            "dry_run": params.dry_run,
+            "summary": format!(
+                "{} {} foods, {} duplicate entries {}",
+                if params.dry_run { "Would merge" } else { "Merged" },
+                merged.len(),
+                removed_count,
+                if params.dry_run { "would be removed" } else { "removed" }
+            )
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
     }
 
-    // Recipe tools
-    #[tool(description = "Search for recipes with flexible querying")]
-    async fn search_recipes(
+    #[tool(description = "Search for foods/ingredients with fuzzy name matching")]
+    async fn search_foods(
         &self,
-        Parameters(params): Parameters<SearchRecipesParams>,
+        Parameters(params): Parameters<SearchFoodsParams>,
     ) -> Result<CallToolResult, McpError> {
         // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in search_recipes: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in search_foods: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        match client
-            .search_recipes(params.query.as_deref(), params.limit, params.page)
-            .await
-        {
+        match client.search_foods(&params.query, params.limit).await {
             Ok(response) => {
-                let has_next = response.next.is_some();
-                let current_page = params.page.unwrap_or(1);
-                let recipes_json: Vec<serde_json::Value> = response.results
+                let foods_json: Vec<serde_json::Value> = response
+                    .results
                     .into_iter()
-                    .map(|recipe| {
+                    .map(|food| {
                         json!({
-                            "id": recipe.id,
-                            "name": recipe.name,
-                            "description": recipe.description,
-                            "total_time": recipe.working_time.unwrap_or(0) + recipe.waiting_time.unwrap_or(0),
-                            "servings": recipe.servings,
-                            "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<String>>(),
-                            "created": recipe.created,
-                            "updated": recipe.updated
+                            "id": food.id,
+                            "name": food.name,
+                            "plural_name": food.plural_name,
+                            "description": food.description,
+                            "food_onhand": food.food_onhand,
+                            "supermarket_category": food.supermarket_category
                         })
                     })
                     .collect();
 
                 let result = json!({
-                    "recipes": recipes_json,
+                    "foods": foods_json,
                     "total_count": response.count,
-                    "current_page": current_page,
-                    "next_page": if has_next { Some(current_page + 1) } else { None },
-                    "search_interpretation": format!("Found {} recipes{}",
-                        response.count,
-                        params.query.as_ref().map_or(String::new(), |q| format!(" matching '{q}'"))
-                    )
+                    "query": params.query
                 });
 
                 Ok(CallToolResult::success(vec![Content::text(
@@ -480,1137 +3655,1685 @@ impl TandoorMcpServer {
                 )]))
             }
             Err(e) => {
-                let error = json!({
-                    "error": "Failed to search recipes",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
+                tracing::error!(
+                    "search_foods tool failed for query '{}': {}",
+                    params.query,
+                    e
+                );
+                Ok(error_result("Failed to search foods", e))
             }
         }
     }
 
-    #[tool(description = "Get comprehensive recipe information including scaled ingredients")]
-    async fn get_recipe_details(
-        &self,
-        Parameters(params): Parameters<GetRecipeDetailsParams>,
-    ) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Get all available recipe keywords/tags")]
+    async fn get_keywords(&self) -> Result<CallToolResult, McpError> {
+        tracing::debug!("MCP tool call: get_keywords");
+
         // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in get_recipe_details: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in get_keywords: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        match client.get_recipe(params.id).await {
-            Ok(recipe) => {
-                let mut ingredients = Vec::new();
-                let scaling_factor = if let Some(target_servings) = params.servings {
-                    if let Some(original_servings) = recipe.servings {
-                        target_servings as f64 / original_servings as f64
-                    } else {
-                        1.0
-                    }
-                } else {
-                    1.0
-                };
+        match client.get_keywords().await {
+            Ok(response) => {
+                tracing::debug!("Successfully retrieved keywords from Tandoor API");
+                let keywords_json: Vec<serde_json::Value> = response
+                    .results
+                    .into_iter()
+                    .map(|keyword| {
+                        json!({
+                            "id": keyword.id,
+                            "name": keyword.name,
+                            "description": keyword.description
+                        })
+                    })
+                    .collect();
 
-                for step in &recipe.steps {
-                    for ingredient in &step.ingredients {
-                        ingredients.push(json!({
-                            "food": ingredient.food.name,
-                            "amount": ingredient.amount * scaling_factor,
-                            "unit": ingredient.unit.as_ref().map(|u| &u.name),
-                            "note": ingredient.note,
-                            "is_header": ingredient.is_header,
-                            "no_amount": ingredient.no_amount
-                        }));
-                    }
-                }
+                let result = json!({
+                    "keywords": keywords_json,
+                    "total_count": response.count
+                });
 
-                let instructions: Vec<String> = recipe
-                    .steps
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                tracing::error!("get_keywords tool failed: {}", e);
+
+                Ok(error_result("Failed to get keywords", e))
+            }
+        }
+    }
+
+    #[tool(description = "Get available measurement units")]
+    async fn get_units(&self) -> Result<CallToolResult, McpError> {
+        tracing::info!("=== MCP tool call: get_units started ===");
+
+        // Ensure we're authenticated before making API calls
+        tracing::info!("Checking authentication for get_units");
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => {
+                tracing::info!("Authentication successful, proceeding with get_units API");
+                client
+            }
+            Err(e) => {
+                tracing::error!("Authentication failed in get_units: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        match client.get_units().await {
+            Ok(response) => {
+                tracing::debug!("Successfully retrieved {} units", response.count);
+                let units_json: Vec<serde_json::Value> = response
+                    .results
                     .into_iter()
-                    .map(|step| {
-                        if step.name.is_empty() {
-                            step.instruction
-                        } else {
-                            format!("{}: {}", step.name, step.instruction)
-                        }
+                    .map(|unit| {
+                        json!({
+                            "id": unit.id,
+                            "name": unit.name,
+                            "plural_name": unit.plural_name,
+                            "description": unit.description,
+                            "base_unit": unit.base_unit,
+                            "type": unit.type_
+                        })
                     })
                     .collect();
 
                 let result = json!({
-                    "id": recipe.id,
-                    "name": recipe.name,
-                    "description": recipe.description,
-                    "instructions": instructions,
-                    "ingredients": ingredients,
-                    "servings": params.servings.unwrap_or(recipe.servings.unwrap_or(1)),
-                    "working_time": recipe.working_time,
-                    "waiting_time": recipe.waiting_time,
-                    "total_time": recipe.working_time.unwrap_or(0) + recipe.waiting_time.unwrap_or(0),
-                    "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<String>>(),
-                    "nutrition": recipe.nutrition,
-                    "created": recipe.created,
-                    "updated": recipe.updated,
-                    "scaling_applied": scaling_factor != 1.0
+                    "units": units_json,
+                    "total_count": response.count
+                });
+
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => {
+                tracing::error!("get_units tool failed: {}", e);
+                Ok(error_result("Failed to get units", e))
+            }
+        }
+    }
+
+    // Meal planning tools
+    #[tool(
+        description = "Get meal plans for a date range, optionally of one meal type (by name or ID), grouped by day and meal type with totals per day"
+    )]
+    async fn get_meal_plans(
+        &self,
+        Parameters(params): Parameters<GetMealPlansParams>,
+    ) -> Result<CallToolResult, McpError> {
+        // Ensure we're authenticated before making API calls
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in get_meal_plans: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let meal_type = match &params.meal_type {
+            Some(wanted) => {
+                let meal_types = match client.get_meal_types().await {
+                    Ok(response) => response.results,
+                    Err(e) => return Ok(error_result("Failed to get meal types", e)),
+                };
+                match find_meal_type(&meal_types, wanted, "meal_type") {
+                    Ok(meal_type) => Some((meal_type.id, meal_type.name.clone())),
+                    Err(result) => return Ok(result),
+                }
+            }
+            None => None,
+        };
+
+        match client
+            .get_meal_plans(
+                Some(&params.from_date),
+                Some(&params.to_date),
+                meal_type.as_ref().map(|(id, _)| *id),
+            )
+            .await
+        {
+            Ok(response) => {
+                let plans = response.results;
+                let result = json!({
+                    "days": group_meal_plans_by_day(&plans),
+                    "meal_plans": plans.iter().map(meal_plan_json).collect::<Vec<_>>(),
+                    "total_count": plans.len(),
+                    "date_range": format!("{} to {}", params.from_date, params.to_date),
+                    "meal_type_filter": meal_type.map(|(id, name)| json!({"id": id, "name": name}))
                 });
 
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to get recipe details",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            Err(e) => Ok(error_result("Failed to get meal plans", e)),
         }
     }
 
-    #[tool(description = "Create a new recipe")]
-    async fn create_recipe(
+    #[tool(description = "Create a new meal plan")]
+    async fn create_meal_plan(
         &self,
-        Parameters(params): Parameters<CreateRecipeParams>,
+        Parameters(params): Parameters<CreateMealPlanParams>,
     ) -> Result<CallToolResult, McpError> {
         // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in create_recipe: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in create_meal_plan: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        // Convert keywords from strings to CreateKeywordRequest
-        let keywords = params
-            .keywords
-            .unwrap_or_default()
-            .into_iter()
-            .map(|name| crate::client::types::CreateKeywordRequest { name })
-            .collect();
-
-        // Create a basic step from instructions if provided
-        let steps = if let Some(instructions) = params.instructions {
-            vec![crate::client::types::CreateStepRequest {
-                name: None,
-                instruction: instructions,
-                ingredients: vec![], // Empty ingredients for now
-                time: None,
-                order: 1,
-            }]
-        } else {
-            vec![] // Empty steps array if no instructions
+        let date = match chrono::NaiveDate::parse_from_str(&params.date, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(e) => {
+                return Ok(invalid_params_result(
+                    "Invalid date format",
+                    &[("date", &format!("{e}; expected YYYY-MM-DD"))],
+                ));
+            }
         };
 
-        let request = crate::client::types::CreateRecipeRequest {
-            name: params.name,
-            description: params.description,
+        let request = crate::client::types::CreateMealPlanRequest {
+            recipe: params.recipe_id,
+            title: params.title,
             servings: params.servings,
-            working_time: params.prep_time.unwrap_or(0),
-            waiting_time: params.cook_time.unwrap_or(0),
-            keywords,
-            steps,
+            date,
+            meal_type: params.meal_type,
+            note: params.note,
         };
 
-        match client.create_recipe(request).await {
-            Ok(recipe) => {
+        match client.create_meal_plan(request).await {
+            Ok(meal_plan) => {
                 let result = json!({
-                    "id": recipe.id,
-                    "name": recipe.name,
-                    "description": recipe.description,
-                    "servings": recipe.servings,
-                    "working_time": recipe.working_time,
-                    "waiting_time": recipe.waiting_time,
-                    "created": recipe.created,
+                    "id": meal_plan.id,
+                    "date": meal_plan.date,
+                    "meal_type": meal_plan.meal_type.name,
+                    "recipe_id": meal_plan.recipe.as_ref().map(|r| r.id),
+                    "recipe_name": meal_plan.recipe.as_ref().map(|r| &r.name),
+                    "title": meal_plan.title,
+                    "servings": meal_plan.servings,
+                    "note": meal_plan.note,
+                    "created": meal_plan.created,
                     "success": true,
-                    "message": "Recipe created successfully"
+                    "message": "Meal plan created successfully"
                 });
 
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                tracing::error!("create_recipe tool failed: {}", e);
-                let error = json!({
-                    "error": "Failed to create recipe",
-                    "details": e.to_string(),
-                    "success": false
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            Err(e) => Ok(error_result("Failed to create meal plan", e)),
         }
     }
 
     #[tool(
-        description = "Update the keywords/tags on an existing recipe. Use mode='set' to replace all tags (default), or mode='add' to append to existing tags."
+        description = "Change a meal plan's date, meal type, servings, title, note or recipe, keeping its ID and shopping list links"
     )]
-    async fn update_recipe_keywords(
+    async fn update_meal_plan(
         &self,
-        Parameters(params): Parameters<UpdateRecipeKeywordsParams>,
+        Parameters(params): Parameters<UpdateMealPlanParams>,
     ) -> Result<CallToolResult, McpError> {
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in update_recipe_keywords: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in update_meal_plan: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        // If "add" mode, fetch existing keywords first and merge
-        let final_keywords = if params.mode == "add" {
-            match client.get_recipe(params.recipe_id).await {
-                Ok(recipe) => {
-                    let mut all_keywords: Vec<String> =
-                        recipe.keywords.into_iter().map(|k| k.name).collect();
-                    for kw in &params.keywords {
-                        if !all_keywords.contains(kw) {
-                            all_keywords.push(kw.clone());
-                        }
-                    }
-                    all_keywords
-                }
-                Err(e) => {
-                    let error = json!({
-                        "error": "Failed to fetch existing recipe keywords",
-                        "details": e.to_string()
-                    });
-                    return Ok(CallToolResult::error(vec![Content::text(
-                        error.to_string(),
-                    )]));
+        let date = match params
+            .date
+            .as_deref()
+            .map(|date| parse_date_param("date", date))
+        {
+            Some(Ok(date)) => Some(date),
+            Some(Err(result)) => return Ok(result),
+            None => None,
+        };
+        if params.servings.is_some_and(|servings| servings <= 0) {
+            return Ok(invalid_params_result(
+                "Servings must be a positive number",
+                &[(
+                    "servings",
+                    "Ensure this value is greater than or equal to 1.",
+                )],
+            ));
+        }
+
+        let meal_type = match &params.meal_type {
+            Some(wanted) => {
+                let meal_types = match client.get_meal_types().await {
+                    Ok(response) => response.results,
+                    Err(e) => return Ok(error_result("Failed to get meal types", e)),
+                };
+                match find_meal_type(&meal_types, wanted, "meal_type") {
+                    Ok(meal_type) => Some(meal_type.id),
+                    Err(result) => return Ok(result),
                 }
             }
-        } else {
-            params.keywords
+            None => None,
         };
 
-        let keyword_requests = final_keywords
-            .into_iter()
-            .map(|name| crate::client::types::CreateKeywordRequest { name })
-            .collect();
-
-        match client
-            .patch_recipe_keywords(params.recipe_id, keyword_requests)
-            .await
+        let request = crate::client::types::UpdateMealPlanRequest {
+            recipe: params.recipe_id,
+            title: params.title,
+            servings: params.servings,
+            date,
+            meal_type,
+            note: params.note,
+        };
+        if request.recipe.is_none()
+            && request.title.is_none()
+            && request.servings.is_none()
+            && request.date.is_none()
+            && request.meal_type.is_none()
+            && request.note.is_none()
         {
-            Ok(recipe) => {
-                let result = json!({
-                    "id": recipe.id,
-                    "name": recipe.name,
-                    "keywords": recipe.keywords.into_iter().map(|k| k.name).collect::<Vec<_>>(),
-                    "success": true
-                });
-                Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(&result).unwrap(),
-                )]))
-            }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to update recipe keywords",
-                    "details": e.to_string(),
-                    "success": false
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            return Ok(invalid_params_result(
+                "Nothing to update",
+                &[(
+                    "id",
+                    "Give at least one of date, meal_type, servings, title, note or recipe_id.",
+                )],
+            ));
         }
-    }
 
-    #[tool(description = "Get all recipe books")]
-    async fn get_recipe_books(&self) -> Result<CallToolResult, McpError> {
-        let client = match self.ensure_authenticated().await {
-            Ok(c) => c,
-            Err(e) => {
-                return Ok(CallToolResult::error(vec![Content::text(
-                    json!({"error": "Authentication Error", "details": e.to_string()}).to_string(),
-                )]));
-            }
-        };
+        match client.update_meal_plan(params.id, request).await {
+            Ok(meal_plan) => {
+                let mut result = meal_plan_json(&meal_plan);
+                result["success"] = json!(true);
+                result["message"] = json!("Meal plan updated successfully");
 
-        match client.get_recipe_books().await {
-            Ok(response) => {
-                let books: Vec<serde_json::Value> = response
-                    .results
-                    .into_iter()
-                    .map(|b| json!({"id": b.id, "name": b.name, "description": b.description}))
-                    .collect();
                 Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(
-                        &json!({"books": books, "total_count": response.count}),
-                    )
-                    .unwrap(),
+                    serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(
-                json!({"error": "Failed to get recipe books", "details": e.to_string()})
-                    .to_string(),
-            )])),
+            Err(e) => Ok(error_result("Failed to update meal plan", e)),
         }
     }
 
-    #[tool(description = "Create a new recipe book (collection)")]
-    async fn create_recipe_book(
+    #[tool(
+        description = "Move a meal plan to a date, weekday (\"thursday\"), offset (\"+2 days\") or the next free slot of a meal type (\"next free dinner slot\"), keeping its ID"
+    )]
+    async fn move_meal_plan(
         &self,
-        Parameters(params): Parameters<CreateRecipeBookParams>,
+        Parameters(params): Parameters<MoveMealPlanParams>,
     ) -> Result<CallToolResult, McpError> {
         let client = match self.ensure_authenticated().await {
-            Ok(c) => c,
+            Ok(client) => client,
             Err(e) => {
-                return Ok(CallToolResult::error(vec![Content::text(
-                    json!({"error": "Authentication Error", "details": e.to_string()}).to_string(),
-                )]));
+                tracing::error!("Authentication failed in move_meal_plan: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        let request = crate::client::types::CreateRecipeBookRequest {
-            name: params.name,
-            description: params.description.unwrap_or_default(),
-            shared: vec![],
+        let Some(target) = MoveTarget::parse(&params.to) else {
+            return Ok(invalid_params_result(
+                "Unrecognized move target",
+                &[(
+                    "to",
+                    "Use a date (YYYY-MM-DD), a weekday, \"tomorrow\", an offset such as \"+2 days\", or \"next free <meal type> slot\".",
+                )],
+            ));
         };
 
-        match client.create_recipe_book(request).await {
-            Ok(book) => Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(
-                    &json!({"id": book.id, "name": book.name, "description": book.description, "success": true}),
-                ).unwrap(),
-            )])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(
-                json!({"error": "Failed to create recipe book", "details": e.to_string()}).to_string(),
-            )])),
-        }
-    }
+        let plan = match client.get_meal_plan(params.id).await {
+            Ok(plan) => plan,
+            Err(e) => return Ok(error_result("Failed to get meal plan", e)),
+        };
 
-    #[tool(description = "Add a recipe to a recipe book")]
-    async fn add_recipe_to_book(
-        &self,
-        Parameters(params): Parameters<AddRecipeToBookParams>,
-    ) -> Result<CallToolResult, McpError> {
-        let client = match self.ensure_authenticated().await {
-            Ok(c) => c,
-            Err(e) => {
-                return Ok(CallToolResult::error(vec![Content::text(
-                    json!({"error": "Authentication Error", "details": e.to_string()}).to_string(),
-                )]));
+        let wanted_type = match &target {
+            MoveTarget::NextFree(Some(name)) => Some(NameOrId::Name(name.clone())),
+            _ => params.meal_type.clone(),
+        };
+        let (meal_type_id, meal_type_name) = match &wanted_type {
+            Some(wanted) => {
+                let meal_types = match client.get_meal_types().await {
+                    Ok(response) => response.results,
+                    Err(e) => return Ok(error_result("Failed to get meal types", e)),
+                };
+                match find_meal_type(&meal_types, wanted, "meal_type") {
+                    Ok(meal_type) => (meal_type.id, meal_type.name.clone()),
+                    Err(result) => return Ok(result),
+                }
             }
+            None => (plan.meal_type.id, plan.meal_type.name.clone()),
         };
 
-        match client.add_recipe_to_book(params.book_id, params.recipe_id).await {
-            Ok(entry) => Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(
-                    &json!({"id": entry.id, "book_id": entry.book, "recipe_id": entry.recipe, "success": true}),
-                ).unwrap(),
-            )])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(
-                json!({"error": "Failed to add recipe to book", "details": e.to_string()}).to_string(),
-            )])),
+        let date = if let MoveTarget::NextFree(_) = target {
+            let (Some(first), Some(last)) = (
+                plan.date.checked_add_days(chrono::Days::new(1)),
+                plan.date
+                    .checked_add_days(chrono::Days::new(FREE_SLOT_SEARCH_DAYS as u64)),
+            ) else {
+                let message = format!("No free {meal_type_name} slot after {}", plan.date);
+                return Ok(invalid_params_result(&message, &[("to", &message)]));
+            };
+            let taken = match client
+                .get_meal_plans(
+                    Some(&first.to_string()),
+                    Some(&last.to_string()),
+                    Some(meal_type_id),
+                )
+                .await
+            {
+                Ok(response) => response.results,
+                Err(e) => return Ok(error_result("Failed to get meal plans", e)),
+            };
+            match first
+                .iter_days()
+                .take_while(|date| *date <= last)
+                .find(|date| taken.iter().all(|other| other.date != *date))
+            {
+                Some(date) => date,
+                None => {
+                    let message = format!(
+                        "No free {meal_type_name} slot in the {FREE_SLOT_SEARCH_DAYS} days after {}",
+                        plan.date
+                    );
+                    return Ok(invalid_params_result(&message, &[("to", &message)]));
+                }
+            }
+        } else {
+            match target.date_from(plan.date) {
+                Some(date) => date,
+                None => {
+                    return Ok(invalid_params_result(
+                        "Move target is out of range",
+                        &[("to", "The target date is outside the supported date range.")],
+                    ))
+                }
+            }
+        };
+
+        // Plans already in the target slot are reported, not moved or replaced
+        let already_planned = match client
+            .get_meal_plans(
+                Some(&date.to_string()),
+                Some(&date.to_string()),
+                Some(meal_type_id),
+            )
+            .await
+        {
+            Ok(response) => response
+                .results
+                .iter()
+                .filter(|other| other.id != plan.id)
+                .map(meal_plan_json)
+                .collect::<Vec<_>>(),
+            Err(e) => return Ok(error_result("Failed to get meal plans", e)),
+        };
+
+        let request = crate::client::types::UpdateMealPlanRequest {
+            date: Some(date),
+            meal_type: Some(meal_type_id),
+            ..Default::default()
+        };
+        match client.update_meal_plan(plan.id, request).await {
+            Ok(moved) => {
+                let result = json!({
+                    "meal_plan": meal_plan_json(&moved),
+                    "moved_from": {"date": plan.date, "meal_type": plan.meal_type.name},
+                    "moved_to": {"date": moved.date, "meal_type": moved.meal_type.name},
+                    "already_planned": already_planned,
+                    "success": true,
+                    "message": format!(
+                        "Moved meal plan to {} {}",
+                        moved.date.format("%A %Y-%m-%d"),
+                        moved.meal_type.name
+                    )
+                });
+
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]))
+            }
+            Err(e) => Ok(error_result("Failed to move meal plan", e)),
         }
     }
 
-    // Shopping list tools
-    #[tool(description = "Add items to shopping list with intelligent consolidation")]
-    async fn add_to_shopping_list(
+    #[tool(
+        description = "Draft meal plans for a date range and meal types from the recipe library: respects include/exclude keywords, skips recently cooked recipes, balances prep time per day and prefers recipes using pantry items. The first call only returns the draft and a confirmation token; call again with the same parameters and confirm=<token> to create the meal plans."
+    )]
+    async fn generate_meal_plan(
         &self,
-        Parameters(params): Parameters<AddToShoppingListParams>,
+        Parameters(params): Parameters<GenerateMealPlanParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
-            Err(e) => {
-                tracing::error!("Authentication failed in add_to_shopping_list: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+            Err(e) => {
+                tracing::error!("Authentication failed in generate_meal_plan: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        if let Some(items) = params.items {
-            let mut requests = Vec::new();
-            let mut added = Vec::new();
-            let mut errors = Vec::new();
+        let (from, to) = match (
+            parse_date_param("from_date", &params.from_date),
+            parse_date_param("to_date", &params.to_date),
+        ) {
+            (Ok(from), Ok(to)) => (from, to),
+            (Err(result), _) | (_, Err(result)) => return Ok(result),
+        };
+        if to < from || (to - from).num_days() >= MAX_GENERATED_DAYS {
+            return Ok(invalid_params_result(
+                &format!("Plan between 1 and {MAX_GENERATED_DAYS} days at a time"),
+                &[(
+                    "to_date",
+                    &format!(
+                        "Must be on or after from_date and at most {} days later.",
+                        MAX_GENERATED_DAYS - 1
+                    ),
+                )],
+            ));
+        }
+        if params.servings.is_some_and(|servings| servings <= 0) {
+            return Ok(invalid_params_result(
+                "Servings must be a positive number",
+                &[(
+                    "servings",
+                    "Ensure this value is greater than or equal to 1.",
+                )],
+            ));
+        }
+        if params.avoid_cooked_within_days < 0 {
+            return Ok(invalid_params_result(
+                "avoid_cooked_within_days cannot be negative",
+                &[(
+                    "avoid_cooked_within_days",
+                    "Ensure this value is greater than or equal to 0.",
+                )],
+            ));
+        }
+        let Some(cutoff) =
+            from.checked_sub_days(chrono::Days::new(params.avoid_cooked_within_days as u64))
+        else {
+            return Ok(invalid_params_result(
+                "avoid_cooked_within_days is too large",
+                &[(
+                    "avoid_cooked_within_days",
+                    "The cutoff date is outside the supported date range.",
+                )],
+            ));
+        };
 
-            for item in items {
-                match client.search_foods(&item.name, Some(1)).await {
-                    Ok(foods_response) => {
-                        if let Some(food) = foods_response.results.first() {
-                            let request = crate::client::types::CreateShoppingListEntryRequest {
-                                food: food.id,
-                                unit: None,
-                                amount: item.amount,
-                            };
-                            requests.push(request);
-                        } else {
-                            errors.push(json!({
-                                "food": item.name,
-                                "error": "Food not found",
-                                "suggestion": "Try creating the food first or use a different name"
-                            }));
-                        }
-                    }
-                    Err(e) => {
-                        errors.push(json!({
-                            "food": item.name,
-                            "error": "Failed to search for food",
-                            "details": e.to_string()
-                        }));
-                    }
+        let all_meal_types = match client.get_meal_types().await {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get meal types", e)),
+        };
+        let mut meal_types: Vec<&MealType> = Vec::new();
+        if params.meal_types.is_empty() {
+            meal_types.extend(all_meal_types.iter().filter(|meal_type| meal_type.default));
+        }
+        for wanted in &params.meal_types {
+            match find_meal_type(&all_meal_types, wanted, "meal_types") {
+                Ok(meal_type) if !meal_types.iter().any(|t| t.id == meal_type.id) => {
+                    meal_types.push(meal_type)
                 }
+                Ok(_) => {}
+                Err(result) => return Ok(result),
             }
-
-            if !requests.is_empty() {
-                match client.add_bulk_to_shopping_list(requests).await {
-                    Ok(entries) => {
-                        for entry in entries {
-                            added.push(json!({
-                                "id": entry.id,
-                                "food": entry.food.name,
-                                "amount": entry.amount,
-                                "unit": entry.unit.as_ref().map(|u| &u.name),
-                                "status": "added"
-                            }));
-                        }
-                    }
-                    Err(e) => {
-                        errors.push(json!({
-                            "error": "Failed to add items to shopping list",
-                            "details": e.to_string()
-                        }));
+        }
+        if meal_types.is_empty() {
+            return Ok(invalid_params_result(
+                "No default meal type is set; choose the meal types to plan",
+                &[("meal_types", "Give at least one meal type by ID or name.")],
+            ));
+        }
+        meal_types.sort_by_key(|meal_type| (meal_type.order, meal_type.id));
+
+        let mut filter = RecipeSearchFilter::default();
+        if !(params.include_keywords.is_empty() && params.exclude_keywords.is_empty()) {
+            let known = match client.get_keywords().await {
+                Ok(response) => response.results,
+                Err(e) => return Ok(error_result("Failed to look up keywords", e)),
+            };
+            let mut unknown: Vec<(&str, String)> = Vec::new();
+            for (field, wanted, ids) in [
+                (
+                    "include_keywords",
+                    &params.include_keywords,
+                    &mut filter.keywords_or,
+                ),
+                (
+                    "exclude_keywords",
+                    &params.exclude_keywords,
+                    &mut filter.keywords_or_not,
+                ),
+            ] {
+                for name in wanted {
+                    match known
+                        .iter()
+                        .find(|k| k.name.eq_ignore_ascii_case(name.trim()))
+                    {
+                        Some(keyword) => ids.push(keyword.id),
+                        None => unknown.push((field, format!("Unknown keyword '{name}'"))),
                     }
                 }
             }
+            if !unknown.is_empty() {
+                let field_errors: Vec<(&str, &str)> = unknown
+                    .iter()
+                    .map(|(field, message)| (*field, message.as_str()))
+                    .collect();
+                return Ok(invalid_params_result(
+                    "Some keywords do not match anything in Tandoor",
+                    &field_errors,
+                ));
+            }
+        }
 
-            let result = json!({
-                "added": added,
-                "errors": errors,
-                "summary": format!("Added {} items, {} errors", added.len(), errors.len())
-            });
+        // Slots that already have a meal stay as they are
+        let existing = match client
+            .get_meal_plans(Some(&from.to_string()), Some(&to.to_string()), None)
+            .await
+        {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get meal plans", e)),
+        };
+        let slots: Vec<(chrono::NaiveDate, &MealType)> = from
+            .iter_days()
+            .take_while(|date| *date <= to)
+            .flat_map(|date| meal_types.iter().map(move |meal_type| (date, *meal_type)))
+            .filter(|(date, meal_type)| {
+                !existing
+                    .iter()
+                    .any(|plan| plan.date == *date && plan.meal_type.id == meal_type.id)
+            })
+            .collect();
 
-            Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(&result).unwrap(),
-            )]))
-        } else if let Some(request_text) = params.request {
-            let result = json!({
-                "message": "Natural language processing not yet implemented",
-                "request": request_text,
-                "suggestion": "Please use the structured 'items' parameter with an array of {name, amount} objects"
-            });
+        // Tandoor's `last_cooked` only covers the current user; the cook log covers everyone
+        let days_back = (chrono::Utc::now().date_naive() - cutoff).num_days().max(0) + 1;
+        let cook_log = match client
+            .get_cook_log(None, Some(days_back.try_into().unwrap_or(i32::MAX)))
+            .await
+        {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get cook log", e)),
+        };
+        let recently_cooked = |recipe: &Recipe| {
+            recipe
+                .last_cooked
+                .is_some_and(|cooked| cooked.date_naive() >= cutoff)
+                || cook_log
+                    .iter()
+                    .any(|log| log.recipe.id == recipe.id && log.created.date_naive() >= cutoff)
+        };
 
-            Ok(CallToolResult::success(vec![Content::text(
-                serde_json::to_string_pretty(&result).unwrap(),
-            )]))
-        } else {
-            let error = json!({
-                "error": "Missing required parameters",
-                "message": "Please provide either 'items' array or 'request' text"
-            });
+        let recipes: Vec<Recipe> = match client
+            .search_all_recipes(&filter, Some(MAX_SCANNED_RECIPES))
+            .try_collect()
+            .await
+        {
+            Ok(recipes) => recipes,
+            Err(e) => return Ok(error_result("Failed to search recipes", e)),
+        };
+        let mut skipped_recently_cooked = Vec::new();
+        let mut pool = Vec::new();
+        for recipe in recipes {
+            if existing
+                .iter()
+                .any(|plan| plan.recipe.as_ref().is_some_and(|r| r.id == recipe.id))
+            {
+                continue;
+            }
+            if recently_cooked(&recipe) {
+                skipped_recently_cooked.push(recipe.name);
+            } else {
+                pool.push(recipe);
+            }
+        }
+        // Best rated first, then the ones cooked longest ago, never cooked before all others
+        pool.sort_by(|a, b| {
+            b.rating
+                .unwrap_or(0.0)
+                .total_cmp(&a.rating.unwrap_or(0.0))
+                .then(a.last_cooked.cmp(&b.last_cooked))
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
 
-            Ok(CallToolResult::error(vec![Content::text(
-                error.to_string(),
-            )]))
+        let pantry: std::collections::HashSet<i32> =
+            match client.list_foods(Some(MAX_INVENTORY_FOODS)).await {
+                Ok(foods) => foods
+                    .iter()
+                    .filter(|food| food.food_onhand)
+                    .map(|food| food.id)
+                    .collect(),
+                Err(e) => return Ok(error_result("Failed to get inventory", e)),
+            };
+        let mut candidates = Vec::with_capacity(pool.len());
+        for (index, recipe) in pool.into_iter().enumerate() {
+            // Recipe listings leave out steps, so the ingredients need the full recipe
+            let recipe = if !pantry.is_empty()
+                && recipe.steps.is_empty()
+                && index < MAX_PANTRY_CHECKED_RECIPES
+            {
+                match client.get_recipe(recipe.id).await {
+                    Ok(detailed) => detailed,
+                    Err(e) => return Ok(error_result("Failed to get recipe details", e)),
+                }
+            } else {
+                recipe
+            };
+            candidates.push(PlanCandidate::new(recipe, &pantry));
+        }
+        candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.pantry_foods.len()));
+
+        let draft = draft_meal_plan(&slots, &candidates);
+        let servings = |candidate: &PlanCandidate| {
+            params.servings.unwrap_or_else(|| {
+                candidate
+                    .recipe
+                    .servings
+                    .filter(|servings| *servings > 0)
+                    .unwrap_or(1)
+            })
+        };
+        let token = confirmation_token(
+            "generate-meal-plan",
+            draft
+                .iter()
+                .map(|(date, meal_type, candidate)| {
+                    (
+                        *date,
+                        meal_type.id,
+                        candidate.recipe.id,
+                        servings(candidate),
+                    )
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        match params.confirm {
+            None => {
+                let mut days: std::collections::BTreeMap<chrono::NaiveDate, Vec<_>> =
+                    std::collections::BTreeMap::new();
+                for (date, meal_type, candidate) in &draft {
+                    days.entry(*date).or_default().push(json!({
+                        "meal_type": meal_type.name,
+                        "meal_type_id": meal_type.id,
+                        "recipe_id": candidate.recipe.id,
+                        "recipe_name": candidate.recipe.name,
+                        "servings": servings(candidate),
+                        "total_time_minutes": candidate.minutes,
+                        "pantry_items": candidate.pantry_foods,
+                        "rating": candidate.recipe.rating,
+                        "last_cooked": candidate.recipe.last_cooked
+                    }));
+                }
+                let unfilled: Vec<_> = slots
+                    .iter()
+                    .filter(|(date, meal_type)| {
+                        !draft
+                            .iter()
+                            .any(|(d, t, _)| d == date && t.id == meal_type.id)
+                    })
+                    .map(|(date, meal_type)| json!({"date": date, "meal_type": meal_type.name}))
+                    .collect();
+                let result = json!({
+                    "days": days
+                        .into_iter()
+                        .map(|(date, meals)| {
+                            let minutes: i64 = meals
+                                .iter()
+                                .filter_map(|meal| meal["total_time_minutes"].as_i64())
+                                .sum();
+                            json!({
+                                "date": date,
+                                "weekday": date.format("%A").to_string(),
+                                "meals": meals,
+                                "total_time_minutes": minutes
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                    "total_meals": draft.len(),
+                    "unfilled_slots": unfilled,
+                    "already_planned": existing
+                        .iter()
+                        .filter(|plan| meal_types.iter().any(|t| t.id == plan.meal_type.id))
+                        .map(meal_plan_json)
+                        .collect::<Vec<_>>(),
+                    "skipped_recently_cooked": skipped_recently_cooked,
+                    "created": false,
+                    "confirm": token,
+                    "message": format!(
+                        "Nothing created yet. Call generate_meal_plan again with the same parameters and confirm=\"{token}\" to create these {} meal plans.",
+                        draft.len()
+                    )
+                });
+                return Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
+                )]));
+            }
+            Some(confirm) if confirm != token => {
+                return Ok(invalid_params_result(
+                    "Confirmation token does not match this draft, or recipes, meal plans or the cook log changed since it was issued. Call generate_meal_plan without confirm to get a new draft.",
+                    &[("confirm", "Invalid or stale confirmation token")],
+                ));
+            }
+            Some(_) => {}
+        }
+
+        let mut created = Vec::new();
+        let mut errors = Vec::new();
+        for (date, meal_type, candidate) in &draft {
+            let request = crate::client::types::CreateMealPlanRequest {
+                recipe: Some(candidate.recipe.id),
+                title: None,
+                servings: servings(candidate),
+                date: *date,
+                meal_type: meal_type.id,
+                note: None,
+            };
+            match client.create_meal_plan(request).await {
+                Ok(plan) => created.push(meal_plan_json(&plan)),
+                Err(e) => {
+                    let mut error = error_json("Failed to create meal plan", e);
+                    error["date"] = json!(date);
+                    error["meal_type"] = json!(meal_type.name);
+                    error["recipe_name"] = json!(candidate.recipe.name);
+                    errors.push(error);
+                }
+            }
         }
+
+        let result = json!({
+            "meal_plans": created,
+            "errors": errors,
+            "created": true,
+            "success": errors.is_empty(),
+            "message": format!("Created {} of {} meal plans", created.len(), draft.len())
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
     }
 
-    #[tool(description = "Get current shopping list organized by store section")]
-    async fn get_shopping_list(
+    #[tool(
+        description = "Copy meal plans to another week, from a date range or a saved template, keeping each meal's day offset, meal type and servings. By default nothing is copied when a target date and meal type already has a meal; the conflicts are listed instead. Use on_conflict=\"skip\" or \"add\" to copy anyway, and dry_run to preview."
+    )]
+    async fn copy_meal_plans(
         &self,
-        Parameters(params): Parameters<GetShoppingListParams>,
+        Parameters(params): Parameters<CopyMealPlansParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in get_shopping_list: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in copy_meal_plans: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        match client.get_shopping_list().await {
-            Ok(response) => {
-                let items: Vec<serde_json::Value> = response
-                    .results
-                    .into_iter()
-                    .map(|entry| {
-                        json!({
-                            "id": entry.id,
-                            "food": entry.food.name,
-                            "amount": entry.amount,
-                            "unit": entry.unit.as_ref().map(|u| &u.name),
-                            "checked": entry.checked,
-                            "available": entry.food.food_onhand,
-                            "created": entry.created,
-                            "completed": entry.completed
-                        })
-                    })
-                    .collect();
+        let on_conflict = params.on_conflict.trim().to_lowercase();
+        if !["abort", "skip", "add"].contains(&on_conflict.as_str()) {
+            return Ok(invalid_params_result(
+                &format!("Unknown on_conflict '{}'", params.on_conflict),
+                &[("on_conflict", "Expected one of: abort, skip, add")],
+            ));
+        }
+        let target = match parse_date_param("target_date", &params.target_date) {
+            Ok(date) => date,
+            Err(result) => return Ok(result),
+        };
 
-                let result = if params.format == "grouped" {
-                    let mut unchecked = Vec::new();
-                    let mut checked = Vec::new();
+        let (source, days, mut meals) = match (&params.template, &params.from_date, &params.to_date)
+        {
+            (Some(name), None, None) => {
+                let store = match self.meal_template_store() {
+                    Ok(store) => store,
+                    Err(result) => return Ok(result),
+                };
+                let templates = match store.list() {
+                    Ok(templates) => templates,
+                    Err(e) => return Ok(error_result("Failed to read meal templates", e)),
+                };
+                let Some(template) = templates
+                    .iter()
+                    .find(|template| template.name.eq_ignore_ascii_case(name.trim()))
+                else {
+                    let known: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+                    let message = format!(
+                        "Unknown template '{}', expected one of: {}",
+                        name.trim(),
+                        known.join(", ")
+                    );
+                    return Ok(invalid_params_result(&message, &[("template", &message)]));
+                };
+                (
+                    json!({"template": template.name}),
+                    template.days,
+                    template.meals.clone(),
+                )
+            }
+            (None, Some(from_date), Some(to_date)) => {
+                let (from, to) = match parse_date_range(from_date, to_date) {
+                    Ok(range) => range,
+                    Err(result) => return Ok(result),
+                };
+                let plans = match client
+                    .get_meal_plans(Some(&from.to_string()), Some(&to.to_string()), None)
+                    .await
+                {
+                    Ok(response) => response.results,
+                    Err(e) => return Ok(error_result("Failed to get meal plans", e)),
+                };
+                (
+                    json!({"from_date": from, "to_date": to}),
+                    (to - from).num_days() as u32 + 1,
+                    plans
+                        .iter()
+                        .filter_map(|plan| TemplateMeal::from_plan(plan, from))
+                        .collect(),
+                )
+            }
+            _ => {
+                return Ok(invalid_params_result(
+                    "Give either a template or both from_date and to_date",
+                    &[(
+                        "template",
+                        "Copy from a saved template or from a from_date/to_date range, not both.",
+                    )],
+                ));
+            }
+        };
+        meals.sort_by_key(|meal| (meal.day_offset, meal.meal_type_id));
 
-                    for item in items {
-                        if item
-                            .get("checked")
-                            .and_then(|v| v.as_bool())
-                            .unwrap_or(false)
-                        {
-                            checked.push(item);
-                        } else {
-                            unchecked.push(item);
-                        }
-                    }
+        let out_of_range = || {
+            invalid_params_result(
+                "Copied meals would fall outside the supported date range",
+                &[("target_date", "Choose an earlier target date.")],
+            )
+        };
+        let Some(target_end) =
+            target.checked_add_days(chrono::Days::new(days.saturating_sub(1).into()))
+        else {
+            return Ok(out_of_range());
+        };
+        let Some(dates) = meals
+            .iter()
+            .map(|meal| meal.date_from(target))
+            .collect::<Option<Vec<_>>>()
+        else {
+            return Ok(out_of_range());
+        };
+        let existing = match client
+            .get_meal_plans(
+                Some(&target.to_string()),
+                Some(&target_end.to_string()),
+                None,
+            )
+            .await
+        {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get meal plans", e)),
+        };
 
+        let placed: Vec<(chrono::NaiveDate, &TemplateMeal, Vec<&MealPlan>)> = dates
+            .into_iter()
+            .zip(&meals)
+            .map(|(date, meal)| {
+                let conflicts = existing
+                    .iter()
+                    .filter(|plan| plan.date == date && plan.meal_type.id == meal.meal_type_id)
+                    .collect();
+                (date, meal, conflicts)
+            })
+            .collect();
+        let conflicts: Vec<_> = placed
+            .iter()
+            .filter(|(_, _, conflicts)| !conflicts.is_empty())
+            .map(|(date, meal, conflicts)| {
+                json!({
+                    "date": date,
+                    "meal_type": meal.meal_type,
+                    "copying": meal.recipe_name.as_deref().unwrap_or(&meal.title),
+                    "existing": conflicts.iter().map(|plan| meal_plan_json(plan)).collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        let target_range = format!("{target} to {target_end}");
+
+        if params.dry_run || (on_conflict == "abort" && !conflicts.is_empty()) {
+            let preview: Vec<_> = placed
+                .iter()
+                .map(|(date, meal, conflicts)| {
                     json!({
-                        "unchecked_items": unchecked,
-                        "checked_items": checked,
-                        "total_items": response.count,
-                        "format": "grouped"
-                    })
-                } else {
-                    json!({
-                        "items": items,
-                        "total_items": response.count,
-                        "format": "flat"
+                        "date": date,
+                        "weekday": date.format("%A").to_string(),
+                        "meal_type": meal.meal_type,
+                        "recipe_id": meal.recipe_id,
+                        "recipe_name": meal.recipe_name,
+                        "title": meal.title,
+                        "servings": meal.servings,
+                        "conflict": !conflicts.is_empty()
                     })
-                };
+                })
+                .collect();
+            let message = if params.dry_run {
+                format!(
+                    "Dry run: {} meals would be copied to {target_range}, {} of them into filled slots",
+                    preview.len(),
+                    conflicts.len()
+                )
+            } else {
+                format!(
+                    "Nothing copied: {} meals would land in slots that already have a meal. Call again with on_conflict=\"skip\" to leave them out or \"add\" to plan them alongside.",
+                    conflicts.len()
+                )
+            };
+            let result = json!({
+                "source": source,
+                "target_range": target_range,
+                "meals": preview,
+                "conflicts": conflicts,
+                "copied": false,
+                "message": message
+            });
+            return Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap(),
+            )]));
+        }
 
-                Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(&result).unwrap(),
-                )]))
+        let mut created = Vec::new();
+        let mut skipped = Vec::new();
+        let mut errors = Vec::new();
+        for (date, meal, conflicts) in &placed {
+            if on_conflict == "skip" && !conflicts.is_empty() {
+                skipped.push(json!({
+                    "date": date,
+                    "meal_type": meal.meal_type,
+                    "recipe_name": meal.recipe_name,
+                    "title": meal.title,
+                    "reason": "slot already has a meal"
+                }));
+                continue;
             }
-            Err(e) => {
-                tracing::error!("get_shopping_list tool failed: {}", e);
-                let error = json!({
-                    "error": "Failed to get shopping list",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
+            let request = crate::client::types::CreateMealPlanRequest {
+                recipe: meal.recipe_id,
+                title: Some(meal.title.clone()).filter(|title| !title.is_empty()),
+                servings: meal.servings,
+                date: *date,
+                meal_type: meal.meal_type_id,
+                note: Some(meal.note.clone()).filter(|note| !note.is_empty()),
+            };
+            match client.create_meal_plan(request).await {
+                Ok(plan) => created.push(meal_plan_json(&plan)),
+                Err(e) => {
+                    let mut error = error_json("Failed to create meal plan", e);
+                    error["date"] = json!(date);
+                    error["meal_type"] = json!(meal.meal_type);
+                    error["recipe_name"] = json!(meal.recipe_name);
+                    errors.push(error);
+                }
             }
         }
+
+        let result = json!({
+            "source": source,
+            "target_range": target_range,
+            "meal_plans": created,
+            "skipped": skipped,
+            "conflicts": conflicts,
+            "errors": errors,
+            "copied": true,
+            "success": errors.is_empty(),
+            "message": format!(
+                "Copied {} of {} meals to {target_range}",
+                created.len(),
+                placed.len()
+            )
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
     }
 
-    #[tool(description = "Search for foods/ingredients with fuzzy name matching")]
-    async fn search_foods(
+    #[tool(
+        description = "Save the meal plans of a date range as a named template, e.g. a standard week, to re-apply later with copy_meal_plans"
+    )]
+    async fn save_meal_plan_template(
         &self,
-        Parameters(params): Parameters<SearchFoodsParams>,
+        Parameters(params): Parameters<SaveMealPlanTemplateParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in search_foods: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in save_meal_plan_template: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
+        let store = match self.meal_template_store() {
+            Ok(store) => store,
+            Err(result) => return Ok(result),
+        };
 
-        match client.search_foods(&params.query, params.limit).await {
-            Ok(response) => {
-                let foods_json: Vec<serde_json::Value> = response
-                    .results
-                    .into_iter()
-                    .map(|food| {
-                        json!({
-                            "id": food.id,
-                            "name": food.name,
-                            "plural_name": food.plural_name,
-                            "description": food.description,
-                            "food_onhand": food.food_onhand,
-                            "supermarket_category": food.supermarket_category
-                        })
-                    })
-                    .collect();
+        let name = params.name.trim();
+        if name.is_empty() {
+            return Ok(invalid_params_result(
+                "Template name is empty",
+                &[("name", "This field may not be blank.")],
+            ));
+        }
+        let (from, to) = match parse_date_range(&params.from_date, &params.to_date) {
+            Ok(range) => range,
+            Err(result) => return Ok(result),
+        };
+
+        let plans = match client
+            .get_meal_plans(Some(&from.to_string()), Some(&to.to_string()), None)
+            .await
+        {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get meal plans", e)),
+        };
+        let mut meals: Vec<TemplateMeal> = plans
+            .iter()
+            .filter_map(|plan| TemplateMeal::from_plan(plan, from))
+            .collect();
+        if meals.is_empty() {
+            let message = format!("No meal plans between {from} and {to} to save");
+            return Ok(invalid_params_result(&message, &[("from_date", &message)]));
+        }
+        meals.sort_by_key(|meal| (meal.day_offset, meal.meal_type_id));
 
+        let days = (to - from).num_days() as u32 + 1;
+        match store.save(name, days, meals, params.overwrite) {
+            Ok(Some(template)) => {
                 let result = json!({
-                    "foods": foods_json,
-                    "total_count": response.count,
-                    "query": params.query
+                    "template": meal_template_json(&template),
+                    "success": true,
+                    "message": format!(
+                        "Saved {} meals from {from} to {to} as template '{}'",
+                        template.meals.len(),
+                        template.name
+                    )
                 });
-
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                tracing::error!(
-                    "search_foods tool failed for query '{}': {}",
-                    params.query,
-                    e
+            Ok(None) => {
+                let message = format!(
+                    "A template called '{name}' already exists; set overwrite to replace it"
                 );
-                let error = json!({
-                    "error": "Failed to search foods",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
+                Ok(invalid_params_result(&message, &[("name", &message)]))
             }
-        }
-    }
-
-    #[tool(description = "Get all available recipe keywords/tags")]
-    async fn get_keywords(&self) -> Result<CallToolResult, McpError> {
-        tracing::debug!("MCP tool call: get_keywords");
-
-        // Ensure we're authenticated before making API calls
-        let client = match self.ensure_authenticated().await {
-            Ok(client) => client,
-            Err(e) => {
-                tracing::error!("Authentication failed in get_keywords: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
-            }
-        };
-
-        match client.get_keywords().await {
-            Ok(response) => {
-                tracing::debug!("Successfully retrieved keywords from Tandoor API");
-                let keywords_json: Vec<serde_json::Value> = response
-                    .results
-                    .into_iter()
-                    .map(|keyword| {
-                        json!({
-                            "id": keyword.id,
-                            "name": keyword.name,
-                            "description": keyword.description
-                        })
-                    })
-                    .collect();
+            Err(e) => Ok(error_result("Failed to save meal template", e)),
+        }
+    }
+
+    #[tool(description = "List saved meal plan templates and their meals")]
+    async fn get_meal_plan_templates(&self) -> Result<CallToolResult, McpError> {
+        let store = match self.meal_template_store() {
+            Ok(store) => store,
+            Err(result) => return Ok(result),
+        };
 
+        match store.list() {
+            Ok(templates) => {
                 let result = json!({
-                    "keywords": keywords_json,
-                    "total_count": response.count
+                    "templates": templates.iter().map(meal_template_json).collect::<Vec<_>>(),
+                    "total_count": templates.len()
                 });
-
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                tracing::error!("get_keywords tool failed: {}", e);
+            Err(e) => Ok(error_result("Failed to read meal templates", e)),
+        }
+    }
 
-                // Provide more specific error information
-                let error_details = if e.to_string().contains("Not authenticated") {
-                    json!({
-                        "error": "Authentication Error",
-                        "message": "Your authentication token has expired or is invalid",
-                        "details": e.to_string(),
-                        "suggestion": "Please restart the MCP server to re-authenticate with Tandoor"
-                    })
-                } else if e.to_string().contains("Failed to connect") {
-                    json!({
-                        "error": "Connection Error",
-                        "message": "Unable to connect to Tandoor server",
-                        "details": e.to_string(),
-                        "suggestion": "Check that Tandoor is running and accessible at the configured URL"
-                    })
-                } else {
-                    json!({
-                        "error": "Failed to get keywords",
-                        "message": "An unexpected error occurred while fetching keywords",
-                        "details": e.to_string(),
-                        "suggestion": "Check server logs for more details"
-                    })
-                };
+    #[tool(description = "Delete a saved meal plan template")]
+    async fn delete_meal_plan_template(
+        &self,
+        Parameters(params): Parameters<DeleteMealPlanTemplateParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let store = match self.meal_template_store() {
+            Ok(store) => store,
+            Err(result) => return Ok(result),
+        };
 
-                Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error_details).unwrap(),
+        match store.remove(&params.name) {
+            Ok(true) => {
+                let result = json!({
+                    "deleted": {"name": params.name.trim()},
+                    "success": true,
+                    "message": "Meal plan template deleted successfully"
+                });
+                Ok(CallToolResult::success(vec![Content::text(
+                    serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
+            Ok(false) => {
+                let message = format!("No template called '{}'", params.name.trim());
+                Ok(invalid_params_result(&message, &[("name", &message)]))
+            }
+            Err(e) => Ok(error_result("Failed to delete meal template", e)),
         }
     }
 
-    #[tool(description = "Get available measurement units")]
-    async fn get_units(&self) -> Result<CallToolResult, McpError> {
-        tracing::info!("=== MCP tool call: get_units started ===");
-
+    #[tool(description = "Delete a meal plan")]
+    async fn delete_meal_plan(
+        &self,
+        Parameters(params): Parameters<DeleteMealPlanParams>,
+    ) -> Result<CallToolResult, McpError> {
         // Ensure we're authenticated before making API calls
-        tracing::info!("Checking authentication for get_units");
         let client = match self.ensure_authenticated().await {
-            Ok(client) => {
-                tracing::info!("Authentication successful, proceeding with get_units API");
-                client
-            }
+            Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in get_units: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in delete_meal_plan: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        match client.get_units().await {
-            Ok(response) => {
-                tracing::debug!("Successfully retrieved {} units", response.count);
-                let units_json: Vec<serde_json::Value> = response
-                    .results
-                    .into_iter()
-                    .map(|unit| {
-                        json!({
-                            "id": unit.id,
-                            "name": unit.name,
-                            "plural_name": unit.plural_name,
-                            "description": unit.description,
-                            "base_unit": unit.base_unit,
-                            "type": unit.type_
-                        })
-                    })
-                    .collect();
-
+        match client.delete_meal_plan(params.id).await {
+            Ok(_) => {
                 let result = json!({
-                    "units": units_json,
-                    "total_count": response.count
+                    "deleted": {
+                        "id": params.id
+                    },
+                    "success": true,
+                    "message": "Meal plan deleted successfully"
                 });
 
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                tracing::error!("get_units tool failed: {}", e);
-                let error = json!({
-                    "error": "Failed to get units",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            Err(e) => Ok(error_result("Failed to delete meal plan", e)),
         }
     }
 
-    // Meal planning tools
-    #[tool(description = "Get meal plans for a date range")]
-    async fn get_meal_plans(
-        &self,
-        Parameters(params): Parameters<GetMealPlansParams>,
-    ) -> Result<CallToolResult, McpError> {
+    #[tool(description = "Get available meal types")]
+    async fn get_meal_types(&self) -> Result<CallToolResult, McpError> {
         // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in get_meal_plans: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in get_meal_types: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        match client
-            .get_meal_plans(Some(&params.from_date), Some(&params.to_date))
-            .await
-        {
+        match client.get_meal_types().await {
             Ok(response) => {
-                let meal_plans_json: Vec<serde_json::Value> = response
+                let meal_types_json: Vec<serde_json::Value> = response
                     .results
                     .into_iter()
-                    .filter(|plan| {
-                        params.meal_type.as_ref().is_none_or(|mt| {
-                            plan.meal_type.name.to_lowercase() == mt.to_lowercase()
-                        })
-                    })
-                    .map(|plan| {
+                    .map(|meal_type| {
                         json!({
-                            "id": plan.id,
-                            "date": plan.date,
-                            "meal_type": plan.meal_type.name,
-                            "recipe_id": plan.recipe.as_ref().map(|r| r.id),
-                            "recipe_name": plan.recipe.as_ref().map(|r| &r.name),
-                            "title": plan.title,
-                            "servings": plan.servings,
-                            "note": plan.note,
-                            "created": plan.created
+                            "id": meal_type.id,
+                            "name": meal_type.name,
+                            "order": meal_type.order,
+                            "icon": meal_type.icon,
+                            "color": meal_type.color
                         })
                     })
                     .collect();
 
-                let total_count = meal_plans_json.len();
                 let result = json!({
-                    "meal_plans": meal_plans_json,
-                    "total_count": total_count,
-                    "date_range": format!("{} to {}", params.from_date, params.to_date),
-                    "meal_type_filter": params.meal_type
+                    "meal_types": meal_types_json,
+                    "total_count": response.count
                 });
 
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to get meal plans",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            Err(e) => Ok(error_result("Failed to get meal types", e)),
         }
     }
 
-    #[tool(description = "Create a new meal plan")]
-    async fn create_meal_plan(
+    // Shopping list management tools
+    #[tool(
+        description = "Mark shopping list items as checked/purchased, by entry ID or by food name. A name checks every unchecked entry for that food."
+    )]
+    async fn check_shopping_items(
         &self,
-        Parameters(params): Parameters<CreateMealPlanParams>,
+        Parameters(params): Parameters<CheckShoppingItemsParams>,
     ) -> Result<CallToolResult, McpError> {
         // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in create_meal_plan: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in check_shopping_items: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        let date = chrono::NaiveDate::parse_from_str(&params.date, "%Y-%m-%d").map_err(|e| {
-            McpError::invalid_params(
-                "Invalid date format",
-                Some(serde_json::json!({"error": e.to_string()})),
-            )
-        })?;
+        let mut updated = Vec::new();
+        let mut errors = Vec::new();
+        let mut shopping_list: Option<Vec<ShoppingListEntry>> = None;
 
-        let request = crate::client::types::CreateMealPlanRequest {
-            recipe: params.recipe_id,
-            title: params.title,
-            servings: params.servings,
-            date,
-            meal_type: params.meal_type,
-            note: params.note,
-        };
+        for item in params.items {
+            if let Some(item_id) = item.as_i64() {
+                let request = crate::client::types::UpdateShoppingListEntryRequest {
+                    checked: Some(true),
+                    amount: None,
+                };
 
-        match client.create_meal_plan(request).await {
-            Ok(meal_plan) => {
-                let result = json!({
-                    "id": meal_plan.id,
-                    "date": meal_plan.date,
-                    "meal_type": meal_plan.meal_type.name,
-                    "recipe_id": meal_plan.recipe.as_ref().map(|r| r.id),
-                    "recipe_name": meal_plan.recipe.as_ref().map(|r| &r.name),
-                    "title": meal_plan.title,
-                    "servings": meal_plan.servings,
-                    "note": meal_plan.note,
-                    "created": meal_plan.created,
-                    "success": true,
-                    "message": "Meal plan created successfully"
-                });
+                match client
+                    .update_shopping_list_entry(item_id as i32, request)
+                    .await
+                {
+                    Ok(entry) => {
+                        updated.push(json!({
+                            "id": entry.id,
+                            "food": entry.food.name,
+                            "checked": entry.checked,
+                            "status": "checked"
+                        }));
+                    }
+                    Err(e) => {
+                        let mut error = error_json("Failed to update item", e);
+                        error["item_id"] = json!(item_id);
+                        errors.push(error);
+                    }
+                }
+            } else if let Some(item_name) = item.as_str() {
+                // Fetched once, for the first item given by name
+                if shopping_list.is_none() {
+                    match client.get_shopping_list().await {
+                        Ok(response) => shopping_list = Some(response.results),
+                        Err(e) => {
+                            let mut error = error_json("Failed to get shopping list", e);
+                            error["item_name"] = json!(item_name);
+                            errors.push(error);
+                            continue;
+                        }
+                    }
+                }
+                let Some(entries) = shopping_list.as_mut() else {
+                    continue;
+                };
 
-                Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(&result).unwrap(),
-                )]))
-            }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to create meal plan",
-                    "details": e.to_string(),
-                    "success": false
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
+                // An exact food name wins over a partial one, e.g. "egg" over "eggplant"
+                let needle = item_name.trim().to_lowercase();
+                let unchecked = || entries.iter().filter(|entry| !entry.checked);
+                let Some(food_id) = unchecked()
+                    .find(|entry| food_has_name(&entry.food, item_name.trim()))
+                    .or_else(|| {
+                        unchecked().find(|entry| entry.food.name.to_lowercase().contains(&needle))
+                    })
+                    .map(|entry| entry.food.id)
+                else {
+                    errors.push(json!({
+                        "item_name": item_name,
+                        "error": "Item not found in shopping list"
+                    }));
+                    continue;
+                };
+
+                // Check every entry for the food, not only the first one
+                for entry in entries
+                    .iter_mut()
+                    .filter(|entry| !entry.checked && entry.food.id == food_id)
+                {
+                    let request = crate::client::types::UpdateShoppingListEntryRequest {
+                        checked: Some(true),
+                        amount: None,
+                    };
+
+                    match client.update_shopping_list_entry(entry.id, request).await {
+                        Ok(updated_entry) => {
+                            entry.checked = true;
+                            updated.push(json!({
+                                "id": updated_entry.id,
+                                "food": updated_entry.food.name,
+                                "checked": updated_entry.checked,
+                                "status": "checked"
+                            }));
+                        }
+                        Err(e) => {
+                            let mut error = error_json("Failed to update item", e);
+                            error["item_name"] = json!(item_name);
+                            errors.push(error);
+                        }
+                    }
+                }
             }
         }
+
+        let result = json!({
+            "updated": updated,
+            "errors": errors,
+            "summary": format!("Checked {} items, {} errors", updated.len(), errors.len())
+        });
+
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
     }
 
-    #[tool(description = "Delete a meal plan")]
-    async fn delete_meal_plan(
+    #[tool(
+        description = "Add the ingredients of every meal planned in a date range to the shopping list, scaled to each plan's servings, with the same food from several meals reported as one total and foods on hand left out. Reports which meals each added item is for."
+    )]
+    async fn shop_for_meal_plans(
         &self,
-        Parameters(params): Parameters<DeleteMealPlanParams>,
+        Parameters(params): Parameters<ShopForMealPlansParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in delete_meal_plan: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!("Authentication failed in shop_for_meal_plans: {}", e);
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        match client.delete_meal_plan(params.id).await {
-            Ok(_) => {
-                let result = json!({
-                    "deleted": {
-                        "id": params.id
-                    },
-                    "success": true,
-                    "message": "Meal plan deleted successfully"
-                });
+        let (from, to) = match parse_date_range(&params.from_date, &params.to_date) {
+            Ok(range) => range,
+            Err(result) => return Ok(result),
+        };
 
-                Ok(CallToolResult::success(vec![Content::text(
-                    serde_json::to_string_pretty(&result).unwrap(),
-                )]))
+        let meal_type = match &params.meal_type {
+            Some(wanted) => {
+                let meal_types = match client.get_meal_types().await {
+                    Ok(response) => response.results,
+                    Err(e) => return Ok(error_result("Failed to get meal types", e)),
+                };
+                match find_meal_type(&meal_types, wanted, "meal_type") {
+                    Ok(meal_type) => Some(meal_type.id),
+                    Err(result) => return Ok(result),
+                }
             }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to delete meal plan",
-                    "details": e.to_string(),
-                    "success": false
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
+            None => None,
+        };
+
+        let mut plans = match client
+            .get_meal_plans(Some(&from.to_string()), Some(&to.to_string()), meal_type)
+            .await
+        {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get meal plans", e)),
+        };
+        plans.sort_by_key(|plan| (plan.date, plan.meal_type.order, plan.meal_type.id));
+
+        // Planned recipes come without steps; fetch each one once
+        let mut recipes: std::collections::HashMap<i32, Recipe> = std::collections::HashMap::new();
+        for plan in &plans {
+            let Some(recipe) = &plan.recipe else {
+                continue;
+            };
+            if let std::collections::hash_map::Entry::Vacant(slot) = recipes.entry(recipe.id) {
+                match client.get_recipe(recipe.id).await {
+                    Ok(detailed) => {
+                        slot.insert(detailed);
+                    }
+                    Err(e) => {
+                        let mut error = error_json("Failed to get recipe", e);
+                        error["recipe_id"] = json!(recipe.id);
+                        return Ok(CallToolResult::error(vec![Content::text(
+                            error.to_string(),
+                        )]));
+                    }
+                }
+            }
+        }
+
+        let mut needs: Vec<MealPlanNeed> = Vec::new();
+        let mut on_hand: Vec<(&str, Vec<serde_json::Value>)> = Vec::new();
+        let mut skipped_meals = Vec::new();
+        for plan in &plans {
+            let Some(recipe) = plan.recipe.as_ref().and_then(|r| recipes.get(&r.id)) else {
+                let mut skipped = meal_plan_label(plan);
+                skipped["reason"] = json!("no recipe");
+                skipped_meals.push(skipped);
+                continue;
+            };
+            let recipe_servings = recipe.servings.filter(|s| *s > 0).unwrap_or(1);
+            let factor = plan.servings as f64 / recipe_servings as f64;
+
+            for ingredient in recipe.steps.iter().flat_map(|step| &step.ingredients) {
+                if ingredient.is_header {
+                    continue;
+                }
+                if ingredient.food.food_onhand && !params.include_on_hand {
+                    match on_hand
+                        .iter_mut()
+                        .find(|(food, _)| *food == ingredient.food.name)
+                    {
+                        Some((_, meals)) => meals.push(meal_plan_label(plan)),
+                        None => on_hand.push((&ingredient.food.name, vec![meal_plan_label(plan)])),
+                    }
+                    continue;
+                }
+
+                let amount = ingredient.amount * factor;
+                // The same food from several meals becomes one entry when the units add up
+                let existing = needs
+                    .iter_mut()
+                    .filter(|need| need.ingredient().food.id == ingredient.food.id)
+                    .find_map(|need| {
+                        add_amounts(
+                            (need.amount, need.ingredient().unit.as_ref()),
+                            (amount, ingredient.unit.as_ref()),
+                        )
+                        .map(|total| (need, total))
+                    });
+                match existing {
+                    Some((need, total)) => {
+                        need.amount = total;
+                        need.uses.push((plan, ingredient, amount));
+                    }
+                    None => needs.push(MealPlanNeed {
+                        amount,
+                        uses: vec![(plan, ingredient, amount)],
+                    }),
+                }
             }
         }
-    }
+        let skipped: Vec<_> = on_hand
+            .into_iter()
+            .map(|(food, meals)| json!({"food": food, "reason": "on hand", "meals": meals}))
+            .collect();
 
-    #[tool(description = "Get available meal types")]
-    async fn get_meal_types(&self) -> Result<CallToolResult, McpError> {
-        // Ensure we're authenticated before making API calls
-        let client = match self.ensure_authenticated().await {
-            Ok(client) => client,
-            Err(e) => {
-                tracing::error!("Authentication failed in get_meal_types: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+        if needs.is_empty() {
+            let result = json!({
+                "date_range": format!("{from} to {to}"),
+                "added": [],
+                "skipped": skipped,
+                "skipped_meals": skipped_meals,
+                "summary": format!(
+                    "Nothing to add for {} planned meals: every ingredient is already on hand or no meal has a recipe",
+                    plans.len()
+                )
+            });
+            return Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(&result).unwrap(),
+            )]));
+        }
+
+        // Each meal's entries hang off its own shopping list recipe, so removing one meal
+        // from the list never takes another meal's amounts along
+        let mut list_recipes: Vec<(i32, i32)> = Vec::new();
+        for (plan, _, _) in needs.iter().flat_map(|need| &need.uses) {
+            if list_recipes.iter().any(|(plan_id, _)| *plan_id == plan.id) {
+                continue;
+            }
+            // Only meals with a recipe have needs
+            let Some(recipe) = plan.recipe.as_ref().map(|r| r.id) else {
+                continue;
+            };
+            match client
+                .create_shopping_list_recipe(
+                    crate::client::types::CreateShoppingListRecipeRequest {
+                        recipe,
+                        servings: plan.servings as f64,
+                        mealplan: Some(plan.id),
+                    },
+                )
+                .await
+            {
+                Ok(list_recipe) => list_recipes.push((plan.id, list_recipe.id)),
+                Err(e) => {
+                    remove_list_recipes(client, &list_recipes).await;
+                    let mut error = error_json("Failed to link meal plan to shopping list", e);
+                    error["meal_plan_id"] = json!(plan.id);
+                    return Ok(CallToolResult::error(vec![Content::text(
+                        error.to_string(),
+                    )]));
+                }
             }
+        }
+        let list_recipe_for = |plan_id: i32| {
+            list_recipes
+                .iter()
+                .find(|(id, _)| *id == plan_id)
+                .map(|(_, list_recipe)| *list_recipe)
         };
 
-        match client.get_meal_types().await {
-            Ok(response) => {
-                let meal_types_json: Vec<serde_json::Value> = response
-                    .results
-                    .into_iter()
-                    .map(|meal_type| {
+        let requests = needs
+            .iter()
+            .flat_map(|need| &need.uses)
+            .map(|(plan, ingredient, amount)| {
+                crate::client::types::CreateShoppingListEntryRequest {
+                    food: ingredient.food.id,
+                    unit: ingredient.unit.as_ref().map(|u| u.id),
+                    amount: units::round_amount(*amount),
+                    list_recipe: list_recipe_for(plan.id),
+                    ingredient: Some(ingredient.id),
+                }
+            })
+            .collect();
+
+        match client.add_bulk_to_shopping_list(requests).await {
+            Ok(entries) => {
+                // Entries come back in request order, one per use of each need
+                let mut entries_by_need = Vec::new();
+                let mut remaining = entries.as_slice();
+                for need in &needs {
+                    let (used, rest) = remaining.split_at(need.uses.len().min(remaining.len()));
+                    entries_by_need.push(used);
+                    remaining = rest;
+                }
+
+                let added: Vec<serde_json::Value> = entries_by_need
+                    .iter()
+                    .zip(&needs)
+                    .map(|(entries, need)| {
+                        let meals: Vec<_> = need
+                            .uses
+                            .iter()
+                            .zip(entries.iter())
+                            .map(|((plan, ingredient, amount), entry)| {
+                                let mut meal = meal_plan_label(plan);
+                                meal["entry_id"] = json!(entry.id);
+                                meal["amount"] = json!(units::round_amount(*amount));
+                                meal["unit"] = json!(ingredient.unit.as_ref().map(|u| &u.name));
+                                meal
+                            })
+                            .collect();
                         json!({
-                            "id": meal_type.id,
-                            "name": meal_type.name,
-                            "order": meal_type.order,
-                            "icon": meal_type.icon,
-                            "color": meal_type.color
+                            "entry_ids": entries.iter().map(|entry| entry.id).collect::<Vec<_>>(),
+                            "food": need.ingredient().food.name,
+                            "amount": units::round_amount(need.amount),
+                            "unit": need.ingredient().unit.as_ref().map(|u| &u.name),
+                            "meals": meals
                         })
                     })
                     .collect();
+                let merged_count = needs.iter().map(|need| need.uses.len() - 1).sum::<usize>();
+                let meals: Vec<_> = plans
+                    .iter()
+                    .filter(|plan| plan.recipe.is_some())
+                    .map(|plan| {
+                        let items: Vec<_> = entries_by_need
+                            .iter()
+                            .zip(&needs)
+                            .flat_map(|(entries, need)| {
+                                need.uses
+                                    .iter()
+                                    .zip(entries.iter())
+                                    .filter(|((used_by, _, _), _)| used_by.id == plan.id)
+                                    .map(|((_, ingredient, amount), entry)| {
+                                        json!({
+                                            "entry_id": entry.id,
+                                            "food": ingredient.food.name,
+                                            "amount": units::round_amount(*amount),
+                                            "unit": ingredient.unit.as_ref().map(|u| &u.name)
+                                        })
+                                    })
+                            })
+                            .collect();
+                        let mut meal = meal_plan_label(plan);
+                        meal["servings"] = json!(plan.servings);
+                        meal["list_recipe_id"] = json!(list_recipe_for(plan.id));
+                        meal["items"] = json!(items);
+                        meal
+                    })
+                    .collect();
 
                 let result = json!({
-                    "meal_types": meal_types_json,
-                    "total_count": response.count
+                    "date_range": format!("{from} to {to}"),
+                    "added": added,
+                    "meals": meals,
+                    "skipped": skipped,
+                    "skipped_meals": skipped_meals,
+                    "merged_count": merged_count,
+                    "summary": format!(
+                        "Added {} items for {} planned meals, merged {} duplicates, skipped {} foods on hand",
+                        added.len(),
+                        meals.len(),
+                        merged_count,
+                        skipped.len()
+                    )
                 });
-
                 Ok(CallToolResult::success(vec![Content::text(
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
             Err(e) => {
-                let error = json!({
-                    "error": "Failed to get meal types",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
+                // Don't leave empty recipe groups behind
+                remove_list_recipes(client, &list_recipes).await;
+                Ok(error_result(
+                    "Failed to add meal plan ingredients to shopping list",
+                    e,
+                ))
             }
         }
     }
 
-    // Shopping list management tools
-    #[tool(description = "Mark shopping list items as checked/purchased")]
-    async fn check_shopping_items(
+    #[tool(
+        description = "Remove all shopping list entries that were added from a recipe, using the list_recipe_id returned by add_to_shopping_list"
+    )]
+    async fn remove_recipe_from_shopping_list(
         &self,
-        Parameters(params): Parameters<CheckShoppingItemsParams>,
+        Parameters(params): Parameters<RemoveRecipeFromShoppingListParams>,
     ) -> Result<CallToolResult, McpError> {
-        // Ensure we're authenticated before making API calls
         let client = match self.ensure_authenticated().await {
             Ok(client) => client,
             Err(e) => {
-                tracing::error!("Authentication failed in check_shopping_items: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                tracing::error!(
+                    "Authentication failed in remove_recipe_from_shopping_list: {}",
+                    e
+                );
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
-        let mut updated = Vec::new();
-        let mut errors = Vec::new();
-
-        for item in params.items {
-            if let Some(item_id) = item.as_i64() {
-                let request = crate::client::types::UpdateShoppingListEntryRequest {
-                    checked: Some(true),
-                    amount: None,
-                };
-
-                match client
-                    .update_shopping_list_entry(item_id as i32, request)
-                    .await
-                {
-                    Ok(entry) => {
-                        updated.push(json!({
-                            "id": entry.id,
-                            "food": entry.food.name,
-                            "checked": entry.checked,
-                            "status": "checked"
-                        }));
-                    }
-                    Err(e) => {
-                        errors.push(json!({
-                            "item_id": item_id,
-                            "error": "Failed to update item",
-                            "details": e.to_string()
-                        }));
-                    }
-                }
-            } else if let Some(item_name) = item.as_str() {
-                match client.get_shopping_list().await {
-                    Ok(list_response) => {
-                        if let Some(entry) = list_response.results.iter().find(|e| {
-                            e.food
-                                .name
-                                .to_lowercase()
-                                .contains(&item_name.to_lowercase())
-                        }) {
-                            let request = crate::client::types::UpdateShoppingListEntryRequest {
-                                checked: Some(true),
-                                amount: None,
-                            };
-
-                            match client.update_shopping_list_entry(entry.id, request).await {
-                                Ok(updated_entry) => {
-                                    updated.push(json!({
-                                        "id": updated_entry.id,
-                                        "food": updated_entry.food.name,
-                                        "checked": updated_entry.checked,
-                                        "status": "checked"
-                                    }));
-                                }
-                                Err(e) => {
-                                    errors.push(json!({
-                                        "item_name": item_name,
-                                        "error": "Failed to update item",
-                                        "details": e.to_string()
-                                    }));
-                                }
-                            }
-                        } else {
-                            errors.push(json!({
-                                "item_name": item_name,
-                                "error": "Item not found in shopping list"
-                            }));
-                        }
-                    }
-                    Err(e) => {
-                        errors.push(json!({
-                            "item_name": item_name,
-                            "error": "Failed to get shopping list",
-                            "details": e.to_string()
-                        }));
-                    }
-                }
-            }
+        match client
+            .delete_shopping_list_recipe(params.list_recipe_id)
+            .await
+        {
+            Ok(_) => Ok(CallToolResult::success(vec![Content::text(
+                serde_json::to_string_pretty(
+                    &json!({"removed": {"list_recipe_id": params.list_recipe_id}, "success": true}),
+                )
+                .unwrap(),
+            )])),
+            Err(e) => Ok(error_result(
+                "Failed to remove recipe from shopping list",
+                e,
+            )),
         }
-
-        let result = json!({
-            "updated": updated,
-            "errors": errors,
-            "summary": format!("Checked {} items, {} errors", updated.len(), errors.len())
-        });
-
-        Ok(CallToolResult::success(vec![Content::text(
-            serde_json::to_string_pretty(&result).unwrap(),
-        )]))
     }
 
     #[tool(description = "Clear checked items from shopping list and update pantry")]
@@ -1620,15 +5343,7 @@ impl TandoorMcpServer {
             Ok(client) => client,
             Err(e) => {
                 tracing::error!("Authentication failed in clear_shopping_list: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
@@ -1655,20 +5370,17 @@ impl TandoorMcpServer {
                                         pantry_updates.push(entry.food.name.clone());
                                     }
                                     Err(e) => {
-                                        errors.push(json!({
-                                            "food": entry.food.name,
-                                            "error": "Failed to update pantry",
-                                            "details": e.to_string()
-                                        }));
+                                        let mut error = error_json("Failed to update pantry", e);
+                                        error["food"] = json!(entry.food.name);
+                                        errors.push(error);
                                     }
                                 }
                             }
                             Err(e) => {
-                                errors.push(json!({
-                                    "food": entry.food.name,
-                                    "error": "Failed to remove from shopping list",
-                                    "details": e.to_string()
-                                }));
+                                let mut error =
+                                    error_json("Failed to remove from shopping list", e);
+                                error["food"] = json!(entry.food.name);
+                                errors.push(error);
                             }
                         }
                     }
@@ -1686,16 +5398,175 @@ impl TandoorMcpServer {
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
+            Err(e) => Ok(error_result("Failed to get shopping list", e)),
+        }
+    }
+
+    // Supermarket tools
+    #[tool(
+        description = "List supermarkets with the order their categories (store sections) are walked through, plus every known category"
+    )]
+    async fn get_supermarkets(&self) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
             Err(e) => {
-                let error = json!({
-                    "error": "Failed to get shopping list",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
+                tracing::error!("Authentication failed in get_supermarkets: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let supermarkets = match client.get_supermarkets().await {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get supermarkets", e)),
+        };
+        let categories = match client.get_supermarket_categories().await {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get supermarket categories", e)),
+        };
+
+        let result = json!({
+            "supermarkets": supermarkets.iter().map(supermarket_json).collect::<Vec<_>>(),
+            "categories": categories,
+            "total_count": supermarkets.len()
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
+    }
+
+    #[tool(
+        description = "Create a supermarket, optionally with its categories (store sections) in walking order. Missing categories are created."
+    )]
+    async fn create_supermarket(
+        &self,
+        Parameters(params): Parameters<CreateSupermarketParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in create_supermarket: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let categories = params.categories.unwrap_or_default();
+        if let Err(result) = validate_category_names("categories", &categories) {
+            return Ok(result);
+        }
+
+        let request = crate::client::types::CreateSupermarketRequest {
+            name: params.name,
+            description: params.description.unwrap_or_default(),
+        };
+        let supermarket = match client.create_supermarket(request).await {
+            Ok(supermarket) => supermarket,
+            Err(e) => return Ok(error_result("Failed to create supermarket", e)),
+        };
+
+        let errors = set_category_order(client, &supermarket, &categories).await;
+        Ok(supermarket_result(client, supermarket.id, errors).await)
+    }
+
+    #[tool(
+        description = "Set the order a supermarket's categories (store sections) are walked through. Missing categories are created; categories left out are removed from this store."
+    )]
+    async fn set_supermarket_categories(
+        &self,
+        Parameters(params): Parameters<SetSupermarketCategoriesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in set_supermarket_categories: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        if let Err(result) = validate_category_names("categories", &params.categories) {
+            return Ok(result);
+        }
+
+        let supermarkets = match client.get_supermarkets().await {
+            Ok(response) => response.results,
+            Err(e) => return Ok(error_result("Failed to get supermarkets", e)),
+        };
+        let supermarket = match find_supermarket(&supermarkets, &params.supermarket) {
+            Ok(supermarket) => supermarket,
+            Err(result) => return Ok(result),
+        };
+
+        let errors = set_category_order(client, supermarket, &params.categories).await;
+        Ok(supermarket_result(client, supermarket.id, errors).await)
+    }
+
+    #[tool(
+        description = "Shelve foods in a supermarket category (store section) so the shopping list can be grouped by aisle. Missing categories are created; omit the category to remove it."
+    )]
+    async fn set_food_category(
+        &self,
+        Parameters(params): Parameters<SetFoodCategoryParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.ensure_authenticated().await {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("Authentication failed in set_food_category: {}", e);
+                return Ok(error_result("Authentication Error", e));
+            }
+        };
+
+        let category = params
+            .category
+            .as_deref()
+            .map(str::trim)
+            .filter(|name| !name.is_empty());
+        let mut updated = Vec::new();
+        let mut errors = Vec::new();
+
+        for name in &params.foods {
+            // Only an exact name is safe here; a near match would recategorise another food
+            let food = match client.search_foods(name.trim(), Some(10)).await {
+                Ok(response) => response
+                    .results
+                    .into_iter()
+                    .find(|food| food_has_name(food, name.trim())),
+                Err(e) => {
+                    let mut error = error_json("Failed to search for food", e);
+                    error["food"] = json!(name);
+                    errors.push(error);
+                    continue;
+                }
+            };
+            let Some(food) = food else {
+                errors.push(json!({
+                    "food": name,
+                    "error": "Food not found",
+                    "suggestion": "Use search_foods to find the exact food name"
+                }));
+                continue;
+            };
+
+            match client.update_food_category(food.id, category).await {
+                Ok(food) => updated.push(json!({
+                    "id": food.id,
+                    "food": food.name,
+                    "category": food.supermarket_category.map(|c| c.name)
+                })),
+                Err(e) => {
+                    let mut error = error_json("Failed to update food", e);
+                    error["food"] = json!(name);
+                    errors.push(error);
+                }
             }
         }
+
+        let result = json!({
+            "updated": updated,
+            "errors": errors,
+            "summary": format!("Updated {} foods, {} errors", updated.len(), errors.len())
+        });
+        Ok(CallToolResult::success(vec![Content::text(
+            serde_json::to_string_pretty(&result).unwrap(),
+        )]))
     }
 
     // Inventory management tools
@@ -1709,15 +5580,7 @@ impl TandoorMcpServer {
             Ok(client) => client,
             Err(e) => {
                 tracing::error!("Authentication failed in update_pantry: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
@@ -1742,11 +5605,9 @@ impl TandoorMcpServer {
                                 }));
                             }
                             Err(e) => {
-                                errors.push(json!({
-                                    "food": item.food,
-                                    "error": "Failed to update availability",
-                                    "details": e.to_string()
-                                }));
+                                let mut error = error_json("Failed to update availability", e);
+                                error["food"] = json!(item.food);
+                                errors.push(error);
                             }
                         }
                     } else {
@@ -1758,11 +5619,9 @@ impl TandoorMcpServer {
                     }
                 }
                 Err(e) => {
-                    errors.push(json!({
-                        "food": item.food,
-                        "error": "Failed to search for food",
-                        "details": e.to_string()
-                    }));
+                    let mut error = error_json("Failed to search for food", e);
+                    error["food"] = json!(item.food);
+                    errors.push(error);
                 }
             }
         }
@@ -1789,15 +5648,7 @@ impl TandoorMcpServer {
             Ok(client) => client,
             Err(e) => {
                 tracing::error!("Authentication failed in get_cook_log: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
@@ -1834,15 +5685,7 @@ impl TandoorMcpServer {
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to get cook log",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            Err(e) => Ok(error_result("Failed to get cook log", e)),
         }
     }
 
@@ -1856,15 +5699,7 @@ impl TandoorMcpServer {
             Ok(client) => client,
             Err(e) => {
                 tracing::error!("Authentication failed in log_cooked_recipe: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
@@ -1893,16 +5728,7 @@ impl TandoorMcpServer {
                     serde_json::to_string_pretty(&result).unwrap(),
                 )]))
             }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to log cooked recipe",
-                    "details": e.to_string(),
-                    "success": false
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            Err(e) => Ok(error_result("Failed to log cooked recipe", e)),
         }
     }
 
@@ -1916,26 +5742,15 @@ impl TandoorMcpServer {
             Ok(client) => client,
             Err(e) => {
                 tracing::error!("Authentication failed in suggest_from_inventory: {}", e);
-                let error = json!({
-                    "error": "Authentication Error",
-                    "message": "Failed to authenticate with Tandoor",
-                    "details": e.to_string(),
-                    "suggestion": "Check your Tandoor credentials and server connectivity"
-                });
-                return Ok(CallToolResult::error(vec![Content::text(
-                    serde_json::to_string_pretty(&error).unwrap(),
-                )]));
+                return Ok(error_result("Authentication Error", e));
             }
         };
 
         // Get available foods in pantry
-        match client.search_foods("", Some(100)).await {
-            Ok(foods_response) => {
-                let available_foods: Vec<&crate::client::types::Food> = foods_response
-                    .results
-                    .iter()
-                    .filter(|food| food.food_onhand)
-                    .collect();
+        match client.list_foods(Some(MAX_INVENTORY_FOODS)).await {
+            Ok(foods) => {
+                let available_foods: Vec<&crate::client::types::Food> =
+                    foods.iter().filter(|food| food.food_onhand).collect();
 
                 if available_foods.is_empty() {
                     let result = json!({
@@ -2052,26 +5867,10 @@ impl TandoorMcpServer {
                             serde_json::to_string_pretty(&result).unwrap(),
                         )]))
                     }
-                    Err(e) => {
-                        let error = json!({
-                            "error": "Failed to search recipes",
-                            "details": e.to_string()
-                        });
-                        Ok(CallToolResult::error(vec![Content::text(
-                            error.to_string(),
-                        )]))
-                    }
+                    Err(e) => Ok(error_result("Failed to search recipes", e)),
                 }
             }
-            Err(e) => {
-                let error = json!({
-                    "error": "Failed to get inventory",
-                    "details": e.to_string()
-                });
-                Ok(CallToolResult::error(vec![Content::text(
-                    error.to_string(),
-                )]))
-            }
+            Err(e) => Ok(error_result("Failed to get inventory", e)),
         }
     }
 }
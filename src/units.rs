@@ -0,0 +1,259 @@
+//! Conversion between metric and US kitchen units of mass and volume.
+//!
+//! Scaling a recipe by multiplying amounts produces values like `0.1875 cup`. A
+//! [`Measure`] can be [normalized](Measure::normalized) to the handiest unit of the same
+//! system and rounded to amounts a cook can measure (`3 tbsp`), and two measures of the
+//! same dimension can be [added](Measure::plus) even when their units differ. Like the
+//! [`ingredient_parser`](crate::ingredient_parser), this module does no network access;
+//! Tandoor's units are mapped onto it with [`KitchenUnit::for_unit`].
+//!
+//! Units that are neither mass nor volume (`clove`, `can`, `piece`) are not convertible;
+//! callers keep their amounts as they are, rounded with [`round_amount`].
+//!
+//! ## Example
+//!
+//! ```
+//! use mcp_tandoor::units::{KitchenUnit, Measure};
+//!
+//! let cup = KitchenUnit::find("cups").unwrap();
+//! let scaled = Measure::new(0.1875, cup).normalized();
+//! assert_eq!(scaled.amount, 3.0);
+//! assert_eq!(scaled.unit.name, "tbsp");
+//!
+//! let flour = Measure::new(500.0, KitchenUnit::find("g").unwrap())
+//!     .plus(Measure::new(1.0, KitchenUnit::find("kg").unwrap()))
+//!     .unwrap();
+//! assert_eq!(flour.normalized().amount, 1.5);
+//! assert_eq!(flour.normalized().unit.name, "kg");
+//! ```
+
+use crate::client::types::Unit;
+use crate::ingredient_parser;
+
+use Dimension::{Mass, Volume};
+use Rounding::{Decimal, Fractions};
+use System::{Imperial, Metric};
+
+/// What a unit measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dimension {
+    Mass,
+    Volume,
+}
+
+impl Dimension {
+    /// Read a dimension from Tandoor's free-form unit `type`, e.g. `"WEIGHT"` or `"volume"`.
+    fn from_type(type_: &str) -> Option<Self> {
+        let type_ = type_.to_lowercase();
+        if type_.contains("mass") || type_.contains("weight") {
+            Some(Self::Mass)
+        } else if type_.contains("volume") {
+            Some(Self::Volume)
+        } else {
+            None
+        }
+    }
+}
+
+/// Measurement system a unit belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum System {
+    Metric,
+    /// US customary units (cups, ounces, pounds)
+    Imperial,
+}
+
+/// How amounts in a unit are rounded.
+#[derive(Debug, PartialEq)]
+enum Rounding {
+    /// Steps that shrink with the amount: 5 above 100, 1 above 10, 0.05 above 1, else 0.01
+    Decimal,
+    /// Closest multiple of 1/n for any of the given n, e.g. `[4, 3]` for quarter and third cups
+    Fractions(&'static [u32]),
+}
+
+/// A unit of mass or volume this module can convert.
+#[derive(Debug, PartialEq)]
+pub struct KitchenUnit {
+    /// Canonical name, as produced by [`ingredient_parser::normalize_unit`]
+    pub name: &'static str,
+    pub dimension: Dimension,
+    pub system: System,
+    /// Grams (mass) or millilitres (volume) in one of this unit
+    pub base_amount: f64,
+    rounding: Rounding,
+}
+
+const fn unit(
+    name: &'static str,
+    dimension: Dimension,
+    system: System,
+    base_amount: f64,
+    rounding: Rounding,
+) -> KitchenUnit {
+    KitchenUnit {
+        name,
+        dimension,
+        system,
+        base_amount,
+        rounding,
+    }
+}
+
+const KITCHEN_UNITS: &[KitchenUnit] = &[
+    unit("mg", Mass, Metric, 0.001, Decimal),
+    unit("g", Mass, Metric, 1.0, Decimal),
+    unit("kg", Mass, Metric, 1000.0, Decimal),
+    unit("oz", Mass, Imperial, 28.349523125, Fractions(&[2])),
+    unit("lb", Mass, Imperial, 453.59237, Fractions(&[4])),
+    unit("ml", Volume, Metric, 1.0, Decimal),
+    unit("l", Volume, Metric, 1000.0, Decimal),
+    unit("tsp", Volume, Imperial, 4.92892159375, Fractions(&[8])),
+    unit("tbsp", Volume, Imperial, 14.78676478125, Fractions(&[2])),
+    unit("fl oz", Volume, Imperial, 29.5735295625, Fractions(&[2])),
+    unit("cup", Volume, Imperial, 236.5882365, Fractions(&[4, 3])),
+    unit("pint", Volume, Imperial, 473.176473, Fractions(&[4])),
+    unit("quart", Volume, Imperial, 946.352946, Fractions(&[4])),
+    unit("gallon", Volume, Imperial, 3785.411784, Fractions(&[4])),
+];
+
+/// Unit names a normalized amount may use, largest first, with the smallest amount each is
+/// used for.
+type Ladder = &'static [(&'static str, f64)];
+
+/// Ladders per dimension and system. Pints, quarts and fluid ounces are understood but
+/// not chosen.
+const LADDERS: &[(Dimension, System, Ladder)] = &[
+    (Mass, Metric, &[("kg", 1.0), ("g", 0.0)]),
+    (Mass, Imperial, &[("lb", 1.0), ("oz", 0.0)]),
+    (Volume, Metric, &[("l", 1.0), ("ml", 0.0)]),
+    (
+        Volume,
+        Imperial,
+        &[("gallon", 1.0), ("cup", 0.25), ("tbsp", 1.0), ("tsp", 0.0)],
+    ),
+];
+
+/// Largest relative error rounding may introduce before a smaller unit is tried
+const MAX_ROUNDING_ERROR: f64 = 0.05;
+
+impl KitchenUnit {
+    /// Look up a unit by any spelling [`ingredient_parser::normalize_unit`] knows, or by
+    /// Tandoor's `base_unit` names such as `fluid_ounce`.
+    pub fn find(name: &str) -> Option<&'static Self> {
+        let canonical = ingredient_parser::normalize_unit(&name.replace('_', " "))?;
+        KITCHEN_UNITS.iter().find(|unit| unit.name == canonical)
+    }
+
+    /// The kitchen unit behind a Tandoor unit, from its `base_unit`, name or plural name.
+    ///
+    /// A unit whose `type` names a different dimension, e.g. a "cup" typed as weight, is
+    /// treated as unknown rather than converted wrongly.
+    pub fn for_unit(unit: &Unit) -> Option<&'static Self> {
+        let kitchen = [
+            unit.base_unit.as_deref(),
+            Some(unit.name.as_str()),
+            unit.plural_name.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .find_map(Self::find)?;
+
+        match unit.type_.as_deref().and_then(Dimension::from_type) {
+            Some(dimension) if dimension != kitchen.dimension => None,
+            _ => Some(kitchen),
+        }
+    }
+
+    /// Round `amount` of this unit to something measurable.
+    fn round(&self, amount: f64) -> f64 {
+        match self.rounding {
+            Decimal => {
+                let step = match amount.abs() {
+                    a if a >= 100.0 => 5.0,
+                    a if a >= 10.0 => 1.0,
+                    a if a >= 1.0 => 0.05,
+                    _ => 0.01,
+                };
+                round_to(amount, step)
+            }
+            Fractions(denominators) => denominators
+                .iter()
+                .map(|n| round_to(amount, 1.0 / *n as f64))
+                .min_by(|a, b| (a - amount).abs().total_cmp(&(b - amount).abs()))
+                .unwrap_or(amount),
+        }
+    }
+}
+
+/// An amount of a [`KitchenUnit`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Measure {
+    pub amount: f64,
+    pub unit: &'static KitchenUnit,
+}
+
+impl Measure {
+    pub fn new(amount: f64, unit: &'static KitchenUnit) -> Self {
+        Self { amount, unit }
+    }
+
+    /// This measure expressed in `unit`, or `None` if `unit` measures something else.
+    pub fn in_unit(self, unit: &'static KitchenUnit) -> Option<Self> {
+        (unit.dimension == self.unit.dimension).then(|| Self {
+            amount: self.amount * self.unit.base_amount / unit.base_amount,
+            unit,
+        })
+    }
+
+    /// The sum of both measures in this measure's unit, or `None` if one is a mass and
+    /// the other a volume.
+    pub fn plus(self, other: Self) -> Option<Self> {
+        let other = other.in_unit(self.unit)?;
+        Some(Self::new(self.amount + other.amount, self.unit))
+    }
+
+    /// The largest unit of the same system and dimension that expresses this amount
+    /// without rounding it by more than 5%, with the amount rounded to kitchen precision.
+    ///
+    /// Amounts no unit can express that closely keep their unit, rounded to two decimals.
+    pub fn normalized(self) -> Self {
+        let ladder = LADDERS
+            .iter()
+            .find(|(dimension, system, _)| {
+                *dimension == self.unit.dimension && *system == self.unit.system
+            })
+            .map_or(&[][..], |(_, _, ladder)| *ladder);
+
+        for (name, minimum) in ladder {
+            let Some(candidate) = KITCHEN_UNITS
+                .iter()
+                .find(|unit| unit.name == *name)
+                .and_then(|unit| self.in_unit(unit))
+            else {
+                continue;
+            };
+            if candidate.amount < *minimum {
+                continue;
+            }
+            let rounded = candidate.unit.round(candidate.amount);
+            if rounded != 0.0
+                && ((rounded - candidate.amount) / candidate.amount).abs() <= MAX_ROUNDING_ERROR
+            {
+                return Self::new(rounded, candidate.unit);
+            }
+        }
+        Self::new(round_amount(self.amount), self.unit)
+    }
+}
+
+/// Round an amount in a unit this module cannot convert to two decimals.
+pub fn round_amount(amount: f64) -> f64 {
+    round_to(amount, 0.01)
+}
+
+fn round_to(amount: f64, step: f64) -> f64 {
+    // Dividing by the step count keeps 0.01 steps from turning into 0.30000000000000004
+    let steps = 1.0 / step;
+    (amount * steps).round() / steps
+}
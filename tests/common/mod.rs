@@ -20,7 +20,7 @@ impl TestEnvironment {
         let username = std::env::var("TANDOOR_USERNAME").unwrap_or_else(|_| "admin".to_string());
         let password = std::env::var("TANDOOR_PASSWORD").unwrap_or_else(|_| "testing1".to_string());
 
-        let mut client = TandoorClient::new(base_url.clone());
+        let client = TandoorClient::new(base_url.clone());
 
         // Check if we have a shared token first
         if let Some(token) = SHARED_TOKEN.get() {
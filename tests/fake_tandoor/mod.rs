@@ -0,0 +1,1091 @@
+//! In-process fake of the Tandoor REST API for hermetic tests.
+//!
+//! Serves the `/api/...` routes [`TandoorClient`](mcp_tandoor::TandoorClient) uses from an
+//! in-memory store seeded with `tests/fixtures/tandoor.json`. Related objects are stored
+//! by ID and nested when rendered, the way Tandoor's serializers do, so marking a food as
+//! on hand shows up in recipes and shopping list entries alike.
+//!
+//! Like the real server it requires a bearer token from `/api-token-auth/`, paginates list
+//! endpoints and answers invalid requests with Django REST framework style field errors.
+
+#![allow(dead_code, clippy::result_large_err)]
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+pub const USERNAME: &str = "chef";
+pub const PASSWORD: &str = "mise-en-place";
+
+const FIXTURE: &str = include_str!("../fixtures/tandoor.json");
+
+/// Resources served even when the fixture has no rows for them
+const RESOURCES: &[&str] = &[
+    "cook-log",
+    "food",
+    "keyword",
+    "meal-plan",
+    "meal-type",
+    "recipe",
+    "recipe-book",
+    "recipe-book-entry",
+    "shopping-list-entry",
+    "shopping-list-recipe",
+    "supermarket",
+    "supermarket-category",
+    "supermarket-category-relation",
+    "unit",
+];
+
+/// Host used in pagination links, as reported by a Tandoor behind a reverse proxy
+const INTERNAL_HOST: &str = "http://tandoor.internal:8080";
+
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// First ID handed out to created objects; fixture IDs stay below it
+const FIRST_ID: i64 = 1000;
+
+/// A running fake Tandoor server. The server stops when this is dropped.
+pub struct FakeTandoor {
+    base_url: String,
+    store: Arc<Mutex<Store>>,
+    server: tokio::task::JoinHandle<()>,
+}
+
+impl FakeTandoor {
+    /// Start a server seeded with the fixture data on a free local port.
+    pub async fn start() -> Self {
+        let store = Arc::new(Mutex::new(Store::seeded()));
+        let app = axum::Router::new()
+            .fallback(handle)
+            .with_state(store.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind fake Tandoor");
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("fake Tandoor server");
+        });
+
+        Self {
+            base_url,
+            store,
+            server,
+        }
+    }
+
+    pub fn base_url(&self) -> String {
+        self.base_url.clone()
+    }
+
+    /// Number of login attempts made against `/api-token-auth/`.
+    pub fn login_count(&self) -> usize {
+        self.store.lock().unwrap().logins
+    }
+
+    /// Issue a valid token without a login, like a pre-set `TANDOOR_AUTH_TOKEN`.
+    pub fn issue_token(&self) -> String {
+        let mut store = self.store.lock().unwrap();
+        let token = format!("tda_preset_{}", store.tokens.len() + 1);
+        store.tokens.insert(token.clone());
+        token
+    }
+
+    /// Invalidate every issued token, as if they had expired.
+    pub fn revoke_tokens(&self) {
+        self.store.lock().unwrap().tokens.clear();
+    }
+
+    /// Cap page sizes so list endpoints need several pages.
+    pub fn set_max_page_size(&self, max_page_size: usize) {
+        self.store.lock().unwrap().max_page_size = max_page_size;
+    }
+
+    /// Every request received so far, as `"METHOD /path?query"`.
+    pub fn requests(&self) -> Vec<String> {
+        self.store.lock().unwrap().requests.clone()
+    }
+
+    /// Stored rows of `resource`, with related objects as IDs.
+    pub fn rows(&self, resource: &str) -> Vec<Value> {
+        self.store.lock().unwrap().tables[resource].clone()
+    }
+
+    /// A stored row of `resource`, with related objects as IDs.
+    pub fn row(&self, resource: &str, id: i64) -> Option<Value> {
+        let store = self.store.lock().unwrap();
+        store
+            .find(resource, id)
+            .map(|index| store.tables[resource][index].clone())
+    }
+
+    /// Store `row` under `resource` with a new ID, returning the ID.
+    pub fn insert(&self, resource: &str, mut row: Value) -> i64 {
+        let mut store = self.store.lock().unwrap();
+        let id = store.next_id();
+        row["id"] = json!(id);
+        store.tables.get_mut(resource).unwrap().push(row);
+        id
+    }
+}
+
+impl Drop for FakeTandoor {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+async fn handle(
+    State(store): State<Arc<Mutex<Store>>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Response {
+    store.lock().unwrap().handle(method, uri, headers, body)
+}
+
+/// Field errors in the shape Django REST framework reports them
+type FieldErrors = Value;
+
+struct Store {
+    tables: BTreeMap<String, Vec<Value>>,
+    imports: Map<String, Value>,
+    tokens: HashSet<String>,
+    logins: usize,
+    next_id: i64,
+    max_page_size: usize,
+    requests: Vec<String>,
+}
+
+impl Store {
+    fn seeded() -> Self {
+        let mut fixture: Map<String, Value> =
+            serde_json::from_str(FIXTURE).expect("valid Tandoor fixture");
+        let imports = match fixture.remove("imports") {
+            Some(Value::Object(imports)) => imports,
+            _ => Map::new(),
+        };
+
+        let mut tables: BTreeMap<String, Vec<Value>> = RESOURCES
+            .iter()
+            .map(|resource| (resource.to_string(), Vec::new()))
+            .collect();
+        for (resource, rows) in fixture {
+            let Value::Array(rows) = rows else {
+                panic!("fixture table {resource} is not an array");
+            };
+            tables.insert(resource, rows);
+        }
+
+        Self {
+            tables,
+            imports,
+            tokens: HashSet::new(),
+            logins: 0,
+            next_id: FIRST_ID,
+            max_page_size: 100,
+            requests: Vec::new(),
+        }
+    }
+
+    fn handle(&mut self, method: Method, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+        let path = uri.path().to_string();
+        self.requests.push(format!(
+            "{method} {}",
+            uri.path_and_query().map_or(path.as_str(), |p| p.as_str())
+        ));
+
+        let body: Value = if body.is_empty() {
+            Value::Null
+        } else {
+            match serde_json::from_slice(&body) {
+                Ok(body) => body,
+                Err(e) => {
+                    return reply(
+                        StatusCode::BAD_REQUEST,
+                        json!({"detail": format!("JSON parse error - {e}")}),
+                    )
+                }
+            }
+        };
+
+        if path == "/api-token-auth/" && method == Method::POST {
+            return self.login(&body);
+        }
+        if let Err(detail) = self.check_token(&headers) {
+            return reply(StatusCode::UNAUTHORIZED, json!({ "detail": detail }));
+        }
+
+        let query = parse_query(uri.query());
+        let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+        let result = match (method.as_str(), segments.as_slice()) {
+            ("GET", ["api", resource]) => self.list(resource, &query),
+            ("GET", ["api", resource, id]) => self.retrieve(resource, id),
+            ("POST", ["api", "shopping-list-entry", "bulk"]) => self.bulk_create(&body),
+            ("POST", ["api", "recipe-from-source"]) => Ok(self.recipe_from_source(&body)),
+            ("POST", ["api", resource]) => self.create(resource, &body),
+            ("PATCH" | "PUT", ["api", resource, id]) => self.update(resource, id, &body),
+            ("DELETE", ["api", resource, id]) => self.destroy(resource, id),
+            _ => Err(not_found()),
+        };
+        result.unwrap_or_else(|response| response)
+    }
+
+    fn login(&mut self, body: &Value) -> Response {
+        self.logins += 1;
+        if body["username"] != USERNAME || body["password"] != PASSWORD {
+            return reply(
+                StatusCode::BAD_REQUEST,
+                json!({"non_field_errors": ["Unable to log in with provided credentials."]}),
+            );
+        }
+        let token = format!("tda_fake_{}", self.logins);
+        self.tokens.insert(token.clone());
+        reply(
+            StatusCode::OK,
+            json!({"token": token, "scope": "read write", "expires": "2099-01-01T00:00:00Z"}),
+        )
+    }
+
+    fn check_token(&self, headers: &HeaderMap) -> Result<(), &'static str> {
+        let token = headers
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or("Authentication credentials were not provided.")?;
+        if self.tokens.contains(token) {
+            Ok(())
+        } else {
+            Err("Invalid token.")
+        }
+    }
+
+    fn next_id(&mut self) -> i64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn table(&self, resource: &str) -> Result<&Vec<Value>, Response> {
+        self.tables.get(resource).ok_or_else(not_found)
+    }
+
+    fn find(&self, resource: &str, id: i64) -> Option<usize> {
+        self.tables
+            .get(resource)?
+            .iter()
+            .position(|row| row["id"] == id)
+    }
+
+    fn find_path(&self, resource: &str, id: &str) -> Result<usize, Response> {
+        self.table(resource)?;
+        id.parse()
+            .ok()
+            .and_then(|id| self.find(resource, id))
+            .ok_or_else(not_found)
+    }
+
+    fn list(&self, resource: &str, query: &[(String, String)]) -> Result<Response, Response> {
+        let mut rows: Vec<Value> = self
+            .table(resource)?
+            .iter()
+            .filter(|row| matches_filters(resource, row, query))
+            .map(|row| self.render(resource, row))
+            .filter(|row| resource != "recipe" || self.recipe_matches(row, query))
+            .collect();
+        if resource == "recipe" {
+            sort_recipes(&mut rows, query);
+        }
+
+        let param = |name: &str| {
+            query
+                .iter()
+                .find(|(key, _)| key == name)
+                .and_then(|(_, value)| value.parse::<usize>().ok())
+        };
+        let page_size = param("page_size")
+            .unwrap_or(DEFAULT_PAGE_SIZE)
+            .clamp(1, self.max_page_size);
+        let page = param("page").unwrap_or(1).max(1);
+        let start = (page - 1) * page_size;
+        if start > 0 && start >= rows.len() {
+            return Err(reply(
+                StatusCode::NOT_FOUND,
+                json!({"detail": "Invalid page."}),
+            ));
+        }
+        let end = (start + page_size).min(rows.len());
+
+        let link = |page: usize| {
+            let mut pairs: Vec<String> = query
+                .iter()
+                .filter(|(key, _)| key != "page")
+                .map(|(key, value)| format!("{key}={}", urlencoding::encode(value)))
+                .collect();
+            pairs.push(format!("page={page}"));
+            format!("{INTERNAL_HOST}/api/{resource}/?{}", pairs.join("&"))
+        };
+
+        Ok(reply(
+            StatusCode::OK,
+            json!({
+                "count": rows.len(),
+                "next": (end < rows.len()).then(|| link(page + 1)),
+                "previous": (page > 1).then(|| link(page - 1)),
+                "results": rows[start..end],
+            }),
+        ))
+    }
+
+    fn retrieve(&self, resource: &str, id: &str) -> Result<Response, Response> {
+        let index = self.find_path(resource, id)?;
+        Ok(reply(
+            StatusCode::OK,
+            self.render(resource, &self.tables[resource][index]),
+        ))
+    }
+
+    fn create(&mut self, resource: &str, body: &Value) -> Result<Response, Response> {
+        self.table(resource)?;
+        let row = self.new_row(resource, body).map_err(validation_error)?;
+        let rendered = self.render(resource, &row);
+        self.tables.get_mut(resource).unwrap().push(row);
+        Ok(reply(StatusCode::CREATED, rendered))
+    }
+
+    fn bulk_create(&mut self, body: &Value) -> Result<Response, Response> {
+        let entries = body["entries"].as_array().cloned().unwrap_or_default();
+        let errors: Vec<Value> = entries
+            .iter()
+            .map(|entry| {
+                self.validate_shopping_entry(entry)
+                    .err()
+                    .unwrap_or_else(|| json!({}))
+            })
+            .collect();
+        if errors.iter().any(|error| error != &json!({})) {
+            return Err(validation_error(json!({ "entries": errors })));
+        }
+
+        let mut created = Vec::new();
+        for entry in &entries {
+            let row = self
+                .new_row("shopping-list-entry", entry)
+                .map_err(validation_error)?;
+            created.push(self.render("shopping-list-entry", &row));
+            self.tables
+                .get_mut("shopping-list-entry")
+                .unwrap()
+                .push(row);
+        }
+        Ok(reply(StatusCode::CREATED, Value::Array(created)))
+    }
+
+    fn update(&mut self, resource: &str, id: &str, body: &Value) -> Result<Response, Response> {
+        let index = self.find_path(resource, id)?;
+        let Value::Object(changes) = body else {
+            return Err(validation_error(
+                json!({"non_field_errors": ["Invalid data. Expected a dictionary."]}),
+            ));
+        };
+
+        let mut row = self.tables[resource][index].clone();
+        for (field, value) in changes {
+            match (resource, field.as_str()) {
+                (_, "id") => {}
+                (_, "name" | "amount" | "checked" | "food_onhand") if value.is_null() => {
+                    return Err(validation_error(
+                        json!({ field: ["This field may not be null."] }),
+                    ));
+                }
+                ("food", "supermarket_category") => {
+                    row["supermarket_category"] = match value["name"].as_str() {
+                        Some(name) => json!(self.get_or_create("supermarket-category", name)),
+                        None => Value::Null,
+                    };
+                }
+                ("meal-plan", "meal_type" | "recipe") => {
+                    let target = if field == "recipe" {
+                        "recipe"
+                    } else {
+                        "meal-type"
+                    };
+                    self.require_pk(body, field, target)
+                        .map_err(validation_error)?;
+                    row[field] = value.clone();
+                }
+                ("meal-plan", "date") => {
+                    let date = value.as_str().unwrap_or_default();
+                    if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                        return Err(validation_error(json!({"date": [
+                            "Date has wrong format. Use one of these formats instead: YYYY-MM-DD."
+                        ]})));
+                    }
+                    row["date"] = value.clone();
+                }
+                ("recipe", "keywords") => {
+                    row["keywords"] = json!(self.keyword_ids(value));
+                }
+                ("recipe", "steps") => {
+                    self.validate_steps(value).map_err(validation_error)?;
+                    row["steps"] = self.build_steps(value);
+                }
+                ("shopping-list-entry", "checked") => {
+                    let checked = value.as_bool().unwrap_or(false);
+                    row["checked"] = json!(checked);
+                    row["completed"] = if checked { json!(now()) } else { Value::Null };
+                }
+                _ => row[field] = value.clone(),
+            }
+        }
+        if resource == "recipe" {
+            row["updated_at"] = json!(now());
+        } else if resource == "meal-plan" {
+            row["updated"] = json!(now());
+        }
+
+        let rendered = self.render(resource, &row);
+        self.tables.get_mut(resource).unwrap()[index] = row;
+        Ok(reply(StatusCode::OK, rendered))
+    }
+
+    fn destroy(&mut self, resource: &str, id: &str) -> Result<Response, Response> {
+        let index = self.find_path(resource, id)?;
+        let row = self.tables.get_mut(resource).unwrap().remove(index);
+        if resource == "shopping-list-recipe" {
+            self.tables
+                .get_mut("shopping-list-entry")
+                .unwrap()
+                .retain(|entry| entry["list_recipe"] != row["id"]);
+        }
+        Ok(StatusCode::NO_CONTENT.into_response())
+    }
+
+    fn recipe_from_source(&self, body: &Value) -> Response {
+        let url = body["url"].as_str().unwrap_or_default();
+        let parsed = self.imports.get(url).cloned().unwrap_or_else(|| {
+            json!({
+                "error": true,
+                "msg": "The requested site does not provide any recognized data format to import the recipe from."
+            })
+        });
+        reply(StatusCode::OK, parsed)
+    }
+
+    /// Build the stored row for a new object, or the field errors that reject it.
+    fn new_row(&mut self, resource: &str, body: &Value) -> Result<Value, FieldErrors> {
+        if !body.is_object() {
+            return Err(json!({"non_field_errors": ["Invalid data. Expected a dictionary."]}));
+        }
+        let row = match resource {
+            "recipe" => {
+                require_text(body, "name")?;
+                self.validate_steps(&body["steps"])?;
+                json!({
+                    "name": body["name"],
+                    "description": body["description"],
+                    "instructions": null,
+                    "servings": body.get("servings").cloned().unwrap_or(json!(1)),
+                    "working_time": body.get("working_time").cloned().unwrap_or(json!(0)),
+                    "waiting_time": body.get("waiting_time").cloned().unwrap_or(json!(0)),
+                    "created_at": now(),
+                    "updated_at": now(),
+                    "internal": true,
+                    "keywords": self.keyword_ids(&body["keywords"]),
+                    "steps": self.build_steps(&body["steps"]),
+                    "source_url": body["source_url"],
+                    "image": null,
+                    "rating": null,
+                    "private": false
+                })
+            }
+            "shopping-list-entry" => {
+                self.validate_shopping_entry(body)?;
+                json!({
+                    "food": body["food"],
+                    "unit": body["unit"],
+                    "amount": body.get("amount").cloned().unwrap_or(json!(1.0)),
+                    "order": 0,
+                    "checked": false,
+                    "created": now(),
+                    "completed": null,
+                    "delay_until": null,
+                    "created_by": 1,
+                    "completed_by": null,
+                    "list_recipe": body["list_recipe"],
+                    "ingredient": body["ingredient"]
+                })
+            }
+            "shopping-list-recipe" => {
+                let recipe = self.require_pk(body, "recipe", "recipe")?;
+                json!({
+                    "name": recipe["name"],
+                    "recipe": body["recipe"],
+                    "mealplan": body["mealplan"],
+                    "servings": body["servings"]
+                })
+            }
+            "meal-plan" => {
+                let mut errors = Map::new();
+                if let Err(Value::Object(error)) = self.require_pk(body, "meal_type", "meal-type") {
+                    errors.extend(error);
+                }
+                if !body["recipe"].is_null() {
+                    if let Err(Value::Object(error)) = self.require_pk(body, "recipe", "recipe") {
+                        errors.extend(error);
+                    }
+                }
+                let date = body["date"].as_str().unwrap_or_default();
+                if chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+                    errors.insert(
+                        "date".to_string(),
+                        json!([
+                            "Date has wrong format. Use one of these formats instead: YYYY-MM-DD."
+                        ]),
+                    );
+                }
+                if !errors.is_empty() {
+                    return Err(Value::Object(errors));
+                }
+                json!({
+                    "title": body.get("title").cloned().unwrap_or(json!("")),
+                    "recipe": body["recipe"],
+                    "servings": body["servings"],
+                    "note": body.get("note").cloned().unwrap_or(json!("")),
+                    "date": date,
+                    "meal_type": body["meal_type"],
+                    "created": now(),
+                    "updated": now(),
+                    "created_by": 1
+                })
+            }
+            "cook-log" => {
+                self.require_pk(body, "recipe", "recipe")?;
+                json!({
+                    "recipe": body["recipe"],
+                    "servings": body.get("servings").cloned().unwrap_or(json!(1)),
+                    "rating": body["rating"],
+                    "comment": body["comment"],
+                    "created": now(),
+                    "created_by": 1
+                })
+            }
+            "recipe-book" => {
+                require_text(body, "name")?;
+                json!({
+                    "name": body["name"],
+                    "description": body["description"],
+                    "created_by": 1,
+                    "filter": null,
+                    "order": 0,
+                    "icon": null
+                })
+            }
+            "recipe-book-entry" => {
+                self.require_pk(body, "book", "recipe-book")?;
+                self.require_pk(body, "recipe", "recipe")?;
+                json!({
+                    "book": body["book"],
+                    "book_content": null,
+                    "recipe": body["recipe"],
+                    "recipe_content": null
+                })
+            }
+            "supermarket" => {
+                require_text(body, "name")?;
+                json!({
+                    "name": body["name"],
+                    "description": body["description"]
+                })
+            }
+            "supermarket-category-relation" => {
+                self.require_pk(body, "supermarket", "supermarket")?;
+                let Some(name) = body["category"]["name"].as_str() else {
+                    return Err(json!({"category": ["This field is required."]}));
+                };
+                json!({
+                    "supermarket": body["supermarket"],
+                    "category": self.get_or_create("supermarket-category", name),
+                    "order": body.get("order").cloned().unwrap_or(json!(0))
+                })
+            }
+            "food" | "keyword" | "unit" | "supermarket-category" => {
+                require_text(body, "name")?;
+                let mut row = new_named(resource, body["name"].as_str().unwrap());
+                if let (Value::Object(row), Value::Object(body)) = (&mut row, body) {
+                    row.extend(body.clone());
+                }
+                row
+            }
+            _ => body.clone(),
+        };
+
+        let mut row = row;
+        row["id"] = json!(self.next_id());
+        Ok(row)
+    }
+
+    fn validate_shopping_entry(&self, body: &Value) -> Result<(), FieldErrors> {
+        self.require_pk(body, "food", "food")?;
+        if !body["unit"].is_null() {
+            self.require_pk(body, "unit", "unit")?;
+        }
+        Ok(())
+    }
+
+    /// The row `body[field]` refers to, or a DRF "invalid pk" error.
+    fn require_pk(&self, body: &Value, field: &str, resource: &str) -> Result<Value, FieldErrors> {
+        let value = &body[field];
+        if value.is_null() {
+            return Err(json!({ field: ["This field is required."] }));
+        }
+        value
+            .as_i64()
+            .and_then(|id| self.find(resource, id))
+            .map(|index| self.tables[resource][index].clone())
+            .ok_or_else(
+                || json!({ field: [format!("Invalid pk \"{value}\" - object does not exist.")] }),
+            )
+    }
+
+    /// Check nested steps the way the recipe serializer does, reporting errors per item.
+    fn validate_steps(&self, steps: &Value) -> Result<(), FieldErrors> {
+        let Some(steps) = steps.as_array() else {
+            return Ok(());
+        };
+        let mut failed = false;
+        let errors: Vec<Value> = steps
+            .iter()
+            .map(|step| {
+                let ingredients = step["ingredients"].as_array().cloned().unwrap_or_default();
+                let ingredient_errors: Vec<Value> = ingredients
+                    .iter()
+                    .map(|ingredient| {
+                        let mut error = Map::new();
+                        if parse_amount(&ingredient["amount"]).is_none() {
+                            error.insert(
+                                "amount".to_string(),
+                                json!(["A valid number is required."]),
+                            );
+                        }
+                        if ingredient["food"]["name"]
+                            .as_str()
+                            .is_none_or(|name| name.trim().is_empty())
+                            && !ingredient["is_header"].as_bool().unwrap_or(false)
+                        {
+                            error.insert("food".to_string(), json!(["This field is required."]));
+                        }
+                        failed |= !error.is_empty();
+                        Value::Object(error)
+                    })
+                    .collect();
+                if ingredient_errors.iter().all(|error| error == &json!({})) {
+                    json!({})
+                } else {
+                    json!({ "ingredients": ingredient_errors })
+                }
+            })
+            .collect();
+        if failed {
+            Err(json!({ "steps": errors }))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Store posted steps with foods, units and ingredients as IDs, creating missing
+    /// foods and units by name like Tandoor's nested serializer.
+    fn build_steps(&mut self, steps: &Value) -> Value {
+        let steps = steps.as_array().cloned().unwrap_or_default();
+        let mut built = Vec::new();
+        for (step_index, step) in steps.iter().enumerate() {
+            let mut ingredients = Vec::new();
+            for (index, ingredient) in step["ingredients"]
+                .as_array()
+                .cloned()
+                .unwrap_or_default()
+                .iter()
+                .enumerate()
+            {
+                let food = match ingredient["food"]["name"].as_str() {
+                    Some(name) => json!(self.get_or_create("food", name)),
+                    None => Value::Null,
+                };
+                let unit = match ingredient["unit"]["name"].as_str() {
+                    Some(name) if !name.trim().is_empty() => {
+                        json!(self.get_or_create("unit", name))
+                    }
+                    _ => Value::Null,
+                };
+                let id = self.existing_or_next_id(ingredient);
+                ingredients.push(json!({
+                    "id": id,
+                    "food": food,
+                    "unit": unit,
+                    "amount": parse_amount(&ingredient["amount"]).unwrap_or(0.0),
+                    "note": ingredient["note"],
+                    "order": ingredient.get("order").cloned().unwrap_or(json!(index)),
+                    "is_header": ingredient["is_header"].as_bool().unwrap_or(false),
+                    "no_amount": ingredient["no_amount"].as_bool().unwrap_or(false)
+                }));
+            }
+            let id = self.existing_or_next_id(step);
+            built.push(json!({
+                "id": id,
+                "name": step["name"].as_str().unwrap_or_default(),
+                "instruction": step["instruction"].as_str().unwrap_or_default(),
+                "time": step.get("time").cloned().unwrap_or(json!(0)),
+                "order": step.get("order").cloned().unwrap_or(json!(step_index)),
+                "file": null,
+                "ingredients": ingredients
+            }));
+        }
+        Value::Array(built)
+    }
+
+    fn keyword_ids(&mut self, keywords: &Value) -> Vec<i64> {
+        keywords
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|keyword| keyword["name"].as_str())
+            .map(|name| self.get_or_create("keyword", name))
+            .collect()
+    }
+
+    /// ID of a nested step or ingredient: kept when the write updates it, new otherwise.
+    fn existing_or_next_id(&mut self, nested: &Value) -> Value {
+        match nested["id"].as_i64() {
+            Some(id) => json!(id),
+            None => json!(self.next_id()),
+        }
+    }
+
+    /// ID of the food, unit or keyword called `name`, created if it does not exist.
+    fn get_or_create(&mut self, resource: &str, name: &str) -> i64 {
+        let existing = self.tables[resource]
+            .iter()
+            .find(|row| {
+                row["name"]
+                    .as_str()
+                    .is_some_and(|existing| existing.eq_ignore_ascii_case(name.trim()))
+            })
+            .and_then(|row| row["id"].as_i64());
+        if let Some(id) = existing {
+            return id;
+        }
+        let id = self.next_id();
+        let mut row = new_named(resource, name.trim());
+        row["id"] = json!(id);
+        self.tables.get_mut(resource).unwrap().push(row);
+        id
+    }
+
+    fn lookup(&self, resource: &str, id: &Value) -> Value {
+        id.as_i64()
+            .and_then(|id| self.find(resource, id))
+            .map(|index| self.render(resource, &self.tables[resource][index]))
+            .unwrap_or(Value::Null)
+    }
+
+    /// Apply `/api/recipe/` search filters to a rendered recipe.
+    fn recipe_matches(&self, recipe: &Value, query: &[(String, String)]) -> bool {
+        let ids = |values: &Value| -> Vec<i64> {
+            values
+                .as_array()
+                .map(|values| values.iter().filter_map(|v| v["id"].as_i64()).collect())
+                .unwrap_or_default()
+        };
+        let keywords = ids(&recipe["keywords"]);
+        let foods: Vec<i64> = recipe["steps"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|step| {
+                ids(&json!(step["ingredients"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|ingredient| ingredient["food"].clone())
+                    .collect::<Vec<_>>()))
+            })
+            .collect();
+        let books: Vec<i64> = self.tables["recipe-book-entry"]
+            .iter()
+            .filter(|entry| entry["recipe"] == recipe["id"])
+            .filter_map(|entry| entry["book"].as_i64())
+            .collect();
+
+        let values = |key: &str| -> Vec<i64> {
+            query
+                .iter()
+                .filter(|(name, _)| name == key)
+                .filter_map(|(_, value)| value.parse().ok())
+                .collect()
+        };
+        let family = |prefix: &str, have: &[i64]| {
+            let or = values(&format!("{prefix}_or"));
+            let and = values(&format!("{prefix}_and"));
+            let or_not = values(&format!("{prefix}_or_not"));
+            let and_not = values(&format!("{prefix}_and_not"));
+            (or.is_empty() || or.iter().any(|id| have.contains(id)))
+                && and.iter().all(|id| have.contains(id))
+                && !or_not.iter().any(|id| have.contains(id))
+                && (and_not.is_empty() || !and_not.iter().all(|id| have.contains(id)))
+        };
+
+        let date = |field: &str| recipe[field].as_str().map(|date| date[..10].to_string());
+        query.iter().all(|(key, value)| match key.as_str() {
+            "rating" => {
+                let rating = recipe["rating"].as_f64().unwrap_or(0.0).round() as i64;
+                match value.parse::<i64>() {
+                    Ok(0) => rating == 0,
+                    Ok(max) if max < 0 => rating != 0 && rating <= -max,
+                    Ok(min) => rating >= min,
+                    Err(_) => true,
+                }
+            }
+            "cookedon" | "createdon" => {
+                let field = if key == "cookedon" {
+                    "last_cooked"
+                } else {
+                    "created_at"
+                };
+                match (date(field), value.strip_prefix('-')) {
+                    (None, _) => false,
+                    (Some(date), Some(before)) => date.as_str() <= before,
+                    (Some(date), None) => date.as_str() >= value.as_str(),
+                }
+            }
+            "internal" => value != "true" || recipe["internal"] == true,
+            _ => true,
+        }) && family("keywords", &keywords)
+            && family("foods", &foods)
+            && family("books", &books)
+    }
+
+    /// Nest related objects the way Tandoor's serializers return them.
+    fn render(&self, resource: &str, row: &Value) -> Value {
+        let mut rendered = row.clone();
+        match resource {
+            "recipe" => {
+                // Tandoor annotates these from the user's cook log
+                let logs: Vec<&Value> = self.tables["cook-log"]
+                    .iter()
+                    .filter(|log| log["recipe"] == row["id"])
+                    .collect();
+                rendered["last_cooked"] = logs
+                    .iter()
+                    .filter_map(|log| log["created"].as_str())
+                    .max()
+                    .map_or(Value::Null, |created| json!(created));
+                let ratings: Vec<f64> = logs
+                    .iter()
+                    .filter_map(|log| log["rating"].as_f64())
+                    .collect();
+                rendered["rating"] = if ratings.is_empty() {
+                    Value::Null
+                } else {
+                    json!(ratings.iter().sum::<f64>() / ratings.len() as f64)
+                };
+                rendered["keywords"] = row["keywords"]
+                    .as_array()
+                    .map(|ids| ids.iter().map(|id| self.lookup("keyword", id)).collect())
+                    .unwrap_or_else(|| json!([]));
+                if let Some(steps) = rendered["steps"].as_array_mut() {
+                    for step in steps {
+                        if let Some(ingredients) = step["ingredients"].as_array_mut() {
+                            for ingredient in ingredients {
+                                ingredient["food"] = self.lookup("food", &ingredient["food"]);
+                                ingredient["unit"] = self.lookup("unit", &ingredient["unit"]);
+                            }
+                        }
+                    }
+                }
+            }
+            "food" => {
+                rendered["supermarket_category"] =
+                    self.lookup("supermarket-category", &row["supermarket_category"]);
+            }
+            "supermarket" => {
+                let mut relations: Vec<Value> = self.tables["supermarket-category-relation"]
+                    .iter()
+                    .filter(|relation| relation["supermarket"] == row["id"])
+                    .map(|relation| self.render("supermarket-category-relation", relation))
+                    .collect();
+                relations.sort_by_key(|relation| relation["order"].as_i64());
+                rendered["category_to_supermarket"] = Value::Array(relations);
+            }
+            "supermarket-category-relation" => {
+                rendered["category"] = self.lookup("supermarket-category", &row["category"]);
+            }
+            "shopping-list-entry" => {
+                rendered["food"] = self.lookup("food", &row["food"]);
+                rendered["unit"] = self.lookup("unit", &row["unit"]);
+            }
+            "meal-plan" => {
+                rendered["recipe"] = self.lookup("recipe", &row["recipe"]);
+                rendered["meal_type"] = self.lookup("meal-type", &row["meal_type"]);
+            }
+            "cook-log" => {
+                rendered["recipe"] = self.lookup("recipe", &row["recipe"]);
+            }
+            _ => {}
+        }
+        rendered
+    }
+}
+
+/// Whether `row` passes the query filters Tandoor supports on `resource`.
+fn matches_filters(resource: &str, row: &Value, query: &[(String, String)]) -> bool {
+    query.iter().all(|(key, value)| match key.as_str() {
+        "query" => {
+            let needle = value.to_lowercase();
+            ["name", "plural_name"].iter().any(|field| {
+                row[field]
+                    .as_str()
+                    .is_some_and(|name| name.to_lowercase().contains(&needle))
+            })
+        }
+        "from_date" | "to_date" => {
+            let date_field = if resource == "meal-plan" {
+                "date"
+            } else {
+                "created"
+            };
+            let date = row[date_field].as_str().unwrap_or_default();
+            let date = &date[..date.len().min(10)];
+            if key == "from_date" {
+                date >= value.as_str()
+            } else {
+                date <= value.as_str()
+            }
+        }
+        "recipe" if resource == "cook-log" => row["recipe"].as_i64() == value.parse().ok(),
+        "meal_type" if resource == "meal-plan" => row["meal_type"].as_i64() == value.parse().ok(),
+        _ => true,
+    })
+}
+
+/// Order recipes by `sort_order`, e.g. `name` or `-rating`; `random` keeps fixture order.
+fn sort_recipes(recipes: &mut [Value], query: &[(String, String)]) {
+    let Some((_, order)) = query.iter().find(|(key, _)| key == "sort_order") else {
+        return;
+    };
+    let (descending, field) = match order.strip_prefix('-') {
+        Some(field) => (true, field),
+        None => (false, order.as_str()),
+    };
+    let field = match field {
+        "lastcooked" => "last_cooked",
+        "created_at" | "name" | "rating" => field,
+        _ => return,
+    };
+    recipes.sort_by(|a, b| {
+        let ordering = match (&a[field], &b[field]) {
+            (Value::Number(a), Value::Number(b)) => a
+                .as_f64()
+                .unwrap_or(0.0)
+                .total_cmp(&b.as_f64().unwrap_or(0.0)),
+            (Value::String(a), Value::String(b)) => a.to_lowercase().cmp(&b.to_lowercase()),
+            (a, b) => a.is_null().cmp(&b.is_null()).reverse(),
+        };
+        if descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+fn new_named(resource: &str, name: &str) -> Value {
+    match resource {
+        "food" => json!({
+            "name": name,
+            "plural_name": null,
+            "description": null,
+            "recipe": null,
+            "food_onhand": false,
+            "supermarket_category": null,
+            "inherit_fields": [],
+            "properties": []
+        }),
+        "unit" => json!({
+            "name": name,
+            "plural_name": null,
+            "description": null,
+            "base_unit": null,
+            "type": null
+        }),
+        "supermarket-category" => json!({
+            "name": name,
+            "description": null
+        }),
+        _ => json!({
+            "name": name,
+            "description": "",
+            "numchild": 0,
+            "full_name": name
+        }),
+    }
+}
+
+fn require_text(body: &Value, field: &str) -> Result<(), FieldErrors> {
+    match body[field].as_str() {
+        Some(text) if !text.trim().is_empty() => Ok(()),
+        Some(_) => Err(json!({ field: ["This field may not be blank."] })),
+        None => Err(json!({ field: ["This field is required."] })),
+    }
+}
+
+/// Amounts are posted as strings by the client and as numbers by fixtures.
+fn parse_amount(amount: &Value) -> Option<f64> {
+    match amount {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        Value::Null => Some(0.0),
+        _ => None,
+    }
+}
+
+fn parse_query(query: Option<&str>) -> Vec<(String, String)> {
+    query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |text: &str| {
+                urlencoding::decode(&text.replace('+', " "))
+                    .map(|text| text.into_owned())
+                    .unwrap_or_else(|_| text.to_string())
+            };
+            (decode(key), decode(value))
+        })
+        .collect()
+}
+
+fn now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+fn reply(status: StatusCode, body: Value) -> Response {
+    (status, Json(body)).into_response()
+}
+
+fn not_found() -> Response {
+    reply(StatusCode::NOT_FOUND, json!({"detail": "Not found."}))
+}
+
+fn validation_error(errors: FieldErrors) -> Response {
+    reply(StatusCode::BAD_REQUEST, errors)
+}
@@ -35,7 +35,7 @@ async fn test_authentication_failure() {
     let base_url =
         std::env::var("TANDOOR_BASE_URL").unwrap_or_else(|_| "http://localhost:8080".to_string());
 
-    let mut client = mcp_tandoor::TandoorClient::new(base_url);
+    let client = mcp_tandoor::TandoorClient::new(base_url);
     let result = client
         .authenticate("invalid_user".to_string(), "wrong_password".to_string())
         .await;
@@ -0,0 +1,56 @@
+use mcp_tandoor::client::auth::TandoorAuth;
+use mcp_tandoor::client::{CredentialProvider, Credentials};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Nothing listens here, so logins fail fast with a network error.
+const UNREACHABLE: &str = "http://127.0.0.1:9";
+
+#[test]
+fn test_credentials_debug_hides_password() {
+    let credentials = Credentials::new("alice", "hunter2");
+    let debug = format!("{credentials:?}");
+    assert!(debug.contains("alice"));
+    assert!(!debug.contains("hunter2"));
+}
+
+#[tokio::test]
+async fn test_each_auth_uses_its_own_provider() {
+    let alice_calls = Arc::new(AtomicUsize::new(0));
+    let bob_calls = Arc::new(AtomicUsize::new(0));
+
+    let counting = |name: &'static str, calls: Arc<AtomicUsize>| {
+        Arc::new(move || {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Ok(Credentials::new(name, "secret"))
+        }) as Arc<dyn CredentialProvider>
+    };
+
+    let alice = TandoorAuth::new(UNREACHABLE.to_string());
+    alice.set_credential_provider(counting("alice", alice_calls.clone()));
+    let bob = TandoorAuth::new(UNREACHABLE.to_string());
+    bob.set_credential_provider(counting("bob", bob_calls.clone()));
+
+    assert!(alice.authenticate_with_provider().await.is_err());
+    assert_eq!(alice_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(bob_calls.load(Ordering::SeqCst), 0);
+
+    assert!(bob.authenticate_with_provider().await.is_err());
+    assert_eq!(alice_calls.load(Ordering::SeqCst), 1);
+    assert_eq!(bob_calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_missing_or_failing_provider() {
+    let auth = TandoorAuth::new(UNREACHABLE.to_string());
+    assert!(!auth.has_credentials());
+    let error = auth.authenticate_with_provider().await.unwrap_err();
+    assert!(error.to_string().contains("No credentials"));
+
+    auth.set_credential_provider(Arc::new(|| -> anyhow::Result<Credentials> {
+        anyhow::bail!("vault is sealed")
+    }));
+    assert!(auth.has_credentials());
+    let error = auth.authenticate_with_provider().await.unwrap_err();
+    assert!(error.to_string().contains("vault is sealed"));
+}
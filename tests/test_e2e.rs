@@ -0,0 +1,1910 @@
+//! End-to-end tests that drive every MCP tool through [`TandoorMcpServer`] against the
+//! in-process fake Tandoor, so they run in `cargo test` without Docker or network access.
+
+mod fake_tandoor;
+
+use fake_tandoor::{FakeTandoor, PASSWORD, USERNAME};
+use mcp_tandoor::meal_templates::MealTemplateStore;
+use mcp_tandoor::server::TandoorMcpServer;
+use pretty_assertions::assert_eq;
+use rmcp::model::{CallToolRequestParam, CallToolResult};
+use rmcp::service::RunningService;
+use rmcp::{RoleClient, ServiceExt};
+use serde_json::{json, Value};
+
+/// An MCP client connected to a [`TandoorMcpServer`] backed by a fake Tandoor.
+struct Harness {
+    tandoor: FakeTandoor,
+    client: RunningService<RoleClient, ()>,
+}
+
+impl Harness {
+    async fn start() -> Self {
+        let tandoor = FakeTandoor::start().await;
+        let server = TandoorMcpServer::new_with_credentials(
+            tandoor.base_url(),
+            USERNAME.to_string(),
+            PASSWORD.to_string(),
+        );
+        Self::connect(tandoor, server).await
+    }
+
+    async fn connect(tandoor: FakeTandoor, server: TandoorMcpServer) -> Self {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(service) = server.serve(server_io).await {
+                let _ = service.waiting().await;
+            }
+        });
+        let client = ().serve(client_io).await.expect("MCP handshake");
+        Self { tandoor, client }
+    }
+
+    async fn call_raw(&self, tool: &str, arguments: Value) -> CallToolResult {
+        let arguments = match arguments {
+            Value::Object(arguments) => Some(arguments),
+            Value::Null => None,
+            other => panic!("tool arguments must be an object, got {other}"),
+        };
+        self.client
+            .call_tool(CallToolRequestParam {
+                name: tool.to_string().into(),
+                arguments,
+            })
+            .await
+            .unwrap_or_else(|e| panic!("{tool} call failed: {e}"))
+    }
+
+    /// Call a tool that must succeed and return its JSON output.
+    async fn call(&self, tool: &str, arguments: Value) -> Value {
+        let result = self.call_raw(tool, arguments).await;
+        let body = result_json(&result);
+        assert_ne!(result.is_error, Some(true), "{tool} failed: {body:#}");
+        body
+    }
+
+    /// Call a tool that must fail and return its JSON error.
+    async fn call_error(&self, tool: &str, arguments: Value) -> Value {
+        let result = self.call_raw(tool, arguments).await;
+        let body = result_json(&result);
+        assert_eq!(result.is_error, Some(true), "{tool} should fail: {body:#}");
+        body
+    }
+}
+
+fn result_json(result: &CallToolResult) -> Value {
+    let text = &result.content[0].as_text().expect("text content").text;
+    serde_json::from_str(text).unwrap_or_else(|e| panic!("tool output is not JSON ({e}): {text}"))
+}
+
+fn names(values: &Value, field: &str) -> Vec<String> {
+    values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|value| value[field].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_lists_every_tool() {
+    let harness = Harness::start().await;
+    let mut tools: Vec<String> = harness
+        .client
+        .list_all_tools()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|tool| tool.name.to_string())
+        .collect();
+    tools.sort();
+
+    assert_eq!(
+        tools,
+        vec![
+            "add_recipe_to_book",
+            "add_to_shopping_list",
+            "check_shopping_items",
+            "clear_shopping_list",
+            "copy_meal_plans",
+            "create_meal_plan",
+            "create_recipe",
+            "create_recipe_book",
+            "create_supermarket",
+            "delete_meal_plan",
+            "delete_meal_plan_template",
+            "delete_recipe",
+            "duplicate_recipe",
+            "generate_meal_plan",
+            "get_cook_log",
+            "get_keywords",
+            "get_meal_plan_templates",
+            "get_meal_plans",
+            "get_meal_types",
+            "get_recipe_books",
+            "get_recipe_details",
+            "get_shopping_list",
+            "get_supermarkets",
+            "get_units",
+            "import_recipe",
+            "log_cooked_recipe",
+            "merge_shopping_list_duplicates",
+            "move_meal_plan",
+            "remove_recipe_from_shopping_list",
+            "save_meal_plan_template",
+            "search_foods",
+            "search_recipes",
+            "set_food_category",
+            "set_supermarket_categories",
+            "shop_for_meal_plans",
+            "suggest_from_inventory",
+            "update_meal_plan",
+            "update_pantry",
+            "update_recipe",
+            "update_recipe_keywords",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_logs_in_once_and_reuses_token() {
+    let harness = Harness::start().await;
+
+    harness.call("get_keywords", json!({})).await;
+    harness.call("get_units", json!({})).await;
+    harness.call("search_recipes", json!({})).await;
+
+    assert_eq!(harness.tandoor.login_count(), 1);
+}
+
+#[tokio::test]
+async fn test_concurrent_tool_calls_share_one_login() {
+    let harness = Harness::start().await;
+
+    let (keywords, units, recipes, meal_types) = tokio::join!(
+        harness.call("get_keywords", json!({})),
+        harness.call("get_units", json!({})),
+        harness.call("search_recipes", json!({"query": "bread"})),
+        harness.call("get_meal_types", json!({})),
+    );
+
+    assert_eq!(keywords["total_count"], 3);
+    assert_eq!(units["total_count"], 6);
+    assert_eq!(recipes["total_count"], 1);
+    assert_eq!(meal_types["total_count"], 3);
+    assert_eq!(harness.tandoor.login_count(), 1);
+}
+
+#[tokio::test]
+async fn test_reauthenticates_after_token_expiry() {
+    let harness = Harness::start().await;
+    harness.call("get_keywords", json!({})).await;
+
+    harness.tandoor.revoke_tokens();
+    let keywords = harness.call("get_keywords", json!({})).await;
+
+    assert_eq!(keywords["total_count"], 3);
+    assert_eq!(harness.tandoor.login_count(), 2);
+}
+
+#[tokio::test]
+async fn test_preset_token_skips_login() {
+    let tandoor = FakeTandoor::start().await;
+    let server = TandoorMcpServer::new(tandoor.base_url());
+    server.set_auth_token(tandoor.issue_token());
+    let harness = Harness::connect(tandoor, server).await;
+
+    harness.call("get_keywords", json!({})).await;
+
+    assert_eq!(harness.tandoor.login_count(), 0);
+}
+
+#[tokio::test]
+async fn test_wrong_password_is_reported_as_unauthorized() {
+    let tandoor = FakeTandoor::start().await;
+    let server = TandoorMcpServer::new_with_credentials(
+        tandoor.base_url(),
+        USERNAME.to_string(),
+        "wrong".to_string(),
+    );
+    let harness = Harness::connect(tandoor, server).await;
+
+    let error = harness.call_error("get_keywords", json!({})).await;
+
+    assert_eq!(error["error"], "Authentication Error");
+    assert_eq!(error["code"], "unauthorized");
+    assert_eq!(error["retryable"], false);
+}
+
+#[tokio::test]
+async fn test_listing_tools_follow_every_page() {
+    let harness = Harness::start().await;
+    harness.tandoor.set_max_page_size(2);
+
+    let keywords = harness.call("get_keywords", json!({})).await;
+    let units = harness.call("get_units", json!({})).await;
+    let meal_types = harness.call("get_meal_types", json!({})).await;
+
+    assert_eq!(keywords["total_count"], 3);
+    assert_eq!(
+        names(&keywords["keywords"], "name"),
+        vec!["dinner", "vegetarian", "quick"]
+    );
+    assert_eq!(units["total_count"], 6);
+    assert_eq!(meal_types["total_count"], 3);
+
+    // Next links name Tandoor's internal host; pages must still come from the fake
+    let requests = harness.tandoor.requests();
+    assert!(requests.contains(&"GET /api/unit/?page_size=100&page=3".to_string()));
+}
+
+#[tokio::test]
+async fn test_search_and_get_recipe_details() {
+    let harness = Harness::start().await;
+
+    let search = harness
+        .call("search_recipes", json!({"query": "garlic"}))
+        .await;
+    assert_eq!(names(&search["recipes"], "name"), vec!["Garlic Bread"]);
+    assert_eq!(search["recipes"][0]["total_time"], 15);
+
+    let first_page = harness
+        .call("search_recipes", json!({"limit": 2, "page": 1}))
+        .await;
+    assert_eq!(first_page["total_count"], 3);
+    assert_eq!(first_page["next_page"], 2);
+    let last_page = harness
+        .call("search_recipes", json!({"limit": 2, "page": 2}))
+        .await;
+    assert_eq!(
+        names(&last_page["recipes"], "name"),
+        vec!["Roast Chicken Thighs"]
+    );
+    assert_eq!(last_page["next_page"], Value::Null);
+
+    let details = harness
+        .call("get_recipe_details", json!({"id": 1, "servings": 4}))
+        .await;
+    assert_eq!(details["name"], "Spaghetti Pomodoro");
+    assert_eq!(details["servings"], 4);
+    assert_eq!(details["scaling_applied"], true);
+    assert_eq!(details["ingredients"][0]["food"], "spaghetti");
+    assert_eq!(details["ingredients"][0]["amount"], 400.0);
+    assert_eq!(details["ingredients"][0]["unit"], "g");
+    assert_eq!(details["keywords"], json!(["dinner", "vegetarian"]));
+
+    // Scaled amounts move to handier units and are rounded
+    let single = harness
+        .call("get_recipe_details", json!({"id": 2, "servings": 1}))
+        .await;
+    assert_eq!(single["ingredients"][1]["amount"], 2.25);
+    assert_eq!(single["ingredients"][1]["unit"], "tsp");
+    assert_eq!(single["ingredients"][2]["amount"], 0.75);
+    assert_eq!(single["ingredients"][2]["unit"], "clove");
+    let party = harness
+        .call("get_recipe_details", json!({"id": 3, "servings": 12}))
+        .await;
+    assert_eq!(party["ingredients"][0]["amount"], 6.0);
+    assert_eq!(party["ingredients"][0]["unit"], "pound");
+
+    let missing = harness
+        .call_error("get_recipe_details", json!({"id": 999}))
+        .await;
+    assert_eq!(missing["code"], "not_found");
+    assert_eq!(missing["status"], 404);
+}
+
+#[tokio::test]
+async fn test_search_recipes_with_filters() {
+    let harness = Harness::start().await;
+    harness
+        .call("log_cooked_recipe", json!({"recipe_id": 3, "rating": 5}))
+        .await;
+
+    let quick_dinners = harness
+        .call(
+            "search_recipes",
+            json!({"keywords": ["dinner"], "max_total_time": 30}),
+        )
+        .await;
+    assert_eq!(
+        names(&quick_dinners["recipes"], "name"),
+        vec!["Spaghetti Pomodoro"]
+    );
+    assert_eq!(quick_dinners["total_count"], 1);
+    assert!(quick_dinners["truncated"].is_null());
+
+    let top_rated = harness
+        .call(
+            "search_recipes",
+            json!({"keywords": ["dinner"], "min_rating": 4}),
+        )
+        .await;
+    assert_eq!(
+        names(&top_rated["recipes"], "name"),
+        vec!["Roast Chicken Thighs"]
+    );
+
+    let sorted = harness
+        .call(
+            "search_recipes",
+            json!({"any_keywords": ["vegetarian", "quick"], "sort": "-name"}),
+        )
+        .await;
+    assert_eq!(
+        names(&sorted["recipes"], "name"),
+        vec!["Spaghetti Pomodoro", "Garlic Bread"]
+    );
+
+    let not_cooked = harness
+        .call(
+            "search_recipes",
+            json!({"exclude_keywords": ["vegetarian"], "not_cooked_since": "2000-01-01"}),
+        )
+        .await;
+    assert_eq!(names(&not_cooked["recipes"], "name"), vec!["Garlic Bread"]);
+
+    let with_spaghetti = harness
+        .call("search_recipes", json!({"foods": ["spaghetti"]}))
+        .await;
+    assert_eq!(
+        names(&with_spaghetti["recipes"], "name"),
+        vec!["Spaghetti Pomodoro"]
+    );
+
+    let unknown = harness
+        .call_error(
+            "search_recipes",
+            json!({"keywords": ["dessert", "dinner", "brunch"]}),
+        )
+        .await;
+    assert_eq!(unknown["code"], "invalid_params");
+    assert_eq!(
+        unknown["field_errors"]["keywords"],
+        json!(["Unknown keyword 'dessert'", "Unknown keyword 'brunch'"])
+    );
+
+    let bad_sort = harness
+        .call_error("search_recipes", json!({"sort": "popularity"}))
+        .await;
+    assert_eq!(bad_sort["code"], "invalid_params");
+}
+
+#[tokio::test]
+async fn test_search_recipes_reports_truncated_scan() {
+    let harness = Harness::start().await;
+    let bread = harness.tandoor.row("recipe", 2).unwrap();
+    for n in 0..2000 {
+        let mut copy = bread.clone();
+        copy["name"] = json!(format!("Garlic Bread {n}"));
+        harness.tandoor.insert("recipe", copy);
+    }
+
+    let quick = harness
+        .call("search_recipes", json!({"max_total_time": 30}))
+        .await;
+    assert_eq!(quick["truncated"], true);
+    assert!(quick["search_interpretation"]
+        .as_str()
+        .unwrap()
+        .contains("only the first 2000 recipes"));
+}
+
+#[tokio::test]
+async fn test_create_recipe_and_update_keywords() {
+    let harness = Harness::start().await;
+
+    let created = harness
+        .call(
+            "create_recipe",
+            json!({
+                "name": "Overnight Oats",
+                "description": "No cooking needed",
+                "instructions": "Mix everything and refrigerate overnight.",
+                "servings": 1,
+                "prep_time": 5,
+                "cook_time": 480,
+                "keywords": ["breakfast", "quick"]
+            }),
+        )
+        .await;
+    assert_eq!(created["success"], true);
+    let recipe_id = created["id"].as_i64().unwrap();
+
+    let details = harness
+        .call("get_recipe_details", json!({"id": recipe_id}))
+        .await;
+    assert_eq!(details["keywords"], json!(["breakfast", "quick"]));
+    assert_eq!(
+        details["instructions"],
+        json!(["Mix everything and refrigerate overnight."])
+    );
+    assert_eq!(details["total_time"], 485);
+
+    let added = harness
+        .call(
+            "update_recipe_keywords",
+            json!({"recipe_id": recipe_id, "keywords": ["vegetarian"], "mode": "add"}),
+        )
+        .await;
+    assert_eq!(
+        added["keywords"],
+        json!(["breakfast", "quick", "vegetarian"])
+    );
+
+    let replaced = harness
+        .call(
+            "update_recipe_keywords",
+            json!({"recipe_id": recipe_id, "keywords": ["dessert"]}),
+        )
+        .await;
+    assert_eq!(replaced["keywords"], json!(["dessert"]));
+
+    let blank = harness
+        .call_error("create_recipe", json!({"name": ""}))
+        .await;
+    assert_eq!(blank["code"], "validation_error");
+    assert_eq!(
+        blank["field_errors"]["name"],
+        json!(["This field may not be blank."])
+    );
+}
+
+#[tokio::test]
+async fn test_create_recipe_with_ingredients() {
+    let harness = Harness::start().await;
+    let foods_before = harness.tandoor.rows("food").len();
+
+    let created = harness
+        .call(
+            "create_recipe",
+            json!({
+                "name": "Shakshuka",
+                "servings": 2,
+                "steps": [
+                    {
+                        "name": "Sauce",
+                        "instruction": "Simmer the tomatoes with garlic and paprika.",
+                        "time": 15,
+                        "ingredients": [
+                            {"food": "Tomatoes", "amount": 400, "unit": "grams"},
+                            {"food": "smoked paprika", "amount": 1, "unit": "teaspoon"}
+                        ],
+                        "ingredient_lines": ["2 cloves garlic, minced"]
+                    },
+                    {
+                        "name": "Eggs",
+                        "instruction": "Crack the eggs into the sauce and cover.",
+                        "ingredients": [
+                            {"food": "egg", "amount": 4},
+                            {"food": "salt", "note": "to taste"}
+                        ]
+                    }
+                ]
+            }),
+        )
+        .await;
+
+    assert_eq!(created["new_foods"], json!(["smoked paprika", "salt"]));
+    assert_eq!(created["new_units"], json!(["teaspoon"]));
+    assert_eq!(harness.tandoor.rows("food").len(), foods_before + 2);
+
+    let sauce = &created["steps"][0];
+    assert_eq!(sauce["name"], "Sauce");
+    assert_eq!(
+        names(&sauce["ingredients"], "food"),
+        vec!["tomato", "smoked paprika", "garlic"]
+    );
+    assert_eq!(
+        names(&sauce["ingredients"], "unit"),
+        vec!["g", "teaspoon", "clove"]
+    );
+    assert_eq!(sauce["ingredients"][2]["amount"], 2.0);
+    assert_eq!(sauce["ingredients"][2]["note"], "minced");
+
+    let eggs = &created["steps"][1]["ingredients"];
+    assert_eq!(names(eggs, "food"), vec!["egg", "salt"]);
+    assert_eq!(eggs[1]["no_amount"], true);
+
+    let details = harness
+        .call("get_recipe_details", json!({"id": created["id"]}))
+        .await;
+    assert_eq!(
+        details["instructions"],
+        json!([
+            "Sauce: Simmer the tomatoes with garlic and paprika.",
+            "Eggs: Crack the eggs into the sauce and cover."
+        ])
+    );
+}
+
+#[tokio::test]
+async fn test_create_recipe_rejects_bad_ingredients() {
+    let harness = Harness::start().await;
+    let recipes_before = harness.tandoor.rows("recipe").len();
+
+    let both = harness
+        .call_error(
+            "create_recipe",
+            json!({
+                "name": "Toast",
+                "instructions": "Toast the bread.",
+                "steps": [{"instruction": "Toast the bread."}]
+            }),
+        )
+        .await;
+    assert_eq!(both["code"], "invalid_params");
+
+    let unparsable = harness
+        .call_error(
+            "create_recipe",
+            json!({
+                "name": "Toast",
+                "steps": [{"instruction": "Toast it.", "ingredient_lines": ["2"]}]
+            }),
+        )
+        .await;
+    assert_eq!(unparsable["code"], "invalid_params");
+    assert!(unparsable["field_errors"]
+        .get("steps[0].ingredient_lines[0]")
+        .is_some());
+
+    assert_eq!(harness.tandoor.rows("recipe").len(), recipes_before);
+}
+
+#[tokio::test]
+async fn test_update_recipe_fields_and_steps() {
+    let harness = Harness::start().await;
+
+    let details = harness.call("get_recipe_details", json!({"id": 1})).await;
+    assert_eq!(details["steps"][0]["id"], 1);
+    assert_eq!(details["ingredients"][1]["id"], 2);
+    assert_eq!(details["ingredients"][1]["step_id"], 1);
+
+    let updated = harness
+        .call(
+            "update_recipe",
+            json!({
+                "recipe_id": 1,
+                "name": "Spaghetti al Pomodoro",
+                "servings": 3,
+                "private": true,
+                "edits": [
+                    {"action": "update_ingredient", "ingredient_id": 1, "amount": 300},
+                    {"action": "update_ingredient", "ingredient_id": 2, "unit": "cups", "note": ""},
+                    {"action": "remove_ingredient", "ingredient_id": 5},
+                    {"action": "add_ingredient", "step_id": 1, "line": "50g parmesan, grated"},
+                    {"action": "move_ingredient", "ingredient_id": 3, "position": 1},
+                    {
+                        "action": "add_step",
+                        "name": "Serve",
+                        "instruction": "Top with basil.",
+                        "ingredients": [{"food": "basil"}]
+                    },
+                    {"action": "add_step", "instruction": "Boil salted water.", "position": 1},
+                    {"action": "update_step", "step_id": 1, "name": "Pasta"}
+                ]
+            }),
+        )
+        .await;
+
+    assert_eq!(updated["name"], "Spaghetti al Pomodoro");
+    assert_eq!(updated["servings"], 3);
+    assert_eq!(updated["private"], true);
+    assert_eq!(updated["new_foods"], json!(["parmesan"]));
+    assert_eq!(updated["new_units"], json!([]));
+
+    let steps = updated["steps"].as_array().unwrap();
+    assert_eq!(names(&updated["steps"], "name"), vec!["", "Pasta", "Serve"]);
+    assert_eq!(steps[0]["instruction"], "Boil salted water.");
+    assert_eq!(steps[1]["id"], 1);
+
+    let pasta = &steps[1]["ingredients"];
+    assert_eq!(
+        names(pasta, "food"),
+        vec!["garlic", "spaghetti", "tomato", "olive oil", "parmesan"]
+    );
+    assert_eq!(pasta[0]["id"], 3);
+    assert_eq!(pasta[1]["amount"], 300.0);
+    assert_eq!(pasta[2]["unit"], "cup");
+    assert_eq!(pasta[2]["note"], Value::Null);
+    assert_eq!(pasta[4]["amount"], 50.0);
+    assert_eq!(pasta[4]["note"], "grated");
+    assert_eq!(steps[2]["ingredients"][0]["no_amount"], true);
+
+    let unknown = harness
+        .call_error(
+            "update_recipe",
+            json!({
+                "recipe_id": 1,
+                "edits": [{"action": "remove_step", "step_id": 999}]
+            }),
+        )
+        .await;
+    assert_eq!(unknown["code"], "invalid_params");
+    assert!(unknown["field_errors"].get("edits[0]").is_some());
+
+    let nothing = harness
+        .call_error("update_recipe", json!({"recipe_id": 1}))
+        .await;
+    assert_eq!(nothing["code"], "invalid_params");
+
+    let missing = harness
+        .call_error("update_recipe", json!({"recipe_id": 999, "name": "Ghost"}))
+        .await;
+    assert_eq!(missing["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_delete_recipe_requires_confirmation() {
+    let harness = Harness::start().await;
+
+    let preview = harness.call("delete_recipe", json!({"recipe_id": 2})).await;
+    assert_eq!(preview["deleted"], false);
+    assert_eq!(preview["recipe"]["name"], "Garlic Bread");
+    assert!(harness.tandoor.row("recipe", 2).is_some());
+    let token = preview["confirm"].as_str().unwrap().to_string();
+
+    let wrong = harness
+        .call_error("delete_recipe", json!({"recipe_id": 2, "confirm": "yes"}))
+        .await;
+    assert_eq!(wrong["code"], "invalid_params");
+
+    // A token for one recipe does not delete another
+    let other = harness
+        .call_error("delete_recipe", json!({"recipe_id": 3, "confirm": token}))
+        .await;
+    assert_eq!(other["code"], "invalid_params");
+    assert!(harness.tandoor.row("recipe", 3).is_some());
+
+    let deleted = harness
+        .call("delete_recipe", json!({"recipe_id": 2, "confirm": token}))
+        .await;
+    assert_eq!(deleted["deleted"], true);
+    assert!(harness.tandoor.row("recipe", 2).is_none());
+
+    let gone = harness
+        .call_error("delete_recipe", json!({"recipe_id": 2, "confirm": token}))
+        .await;
+    assert_eq!(gone["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_delete_token_goes_stale_when_recipe_changes() {
+    let harness = Harness::start().await;
+
+    let preview = harness.call("delete_recipe", json!({"recipe_id": 2})).await;
+    harness
+        .call("update_recipe", json!({"recipe_id": 2, "servings": 6}))
+        .await;
+
+    let stale = harness
+        .call_error(
+            "delete_recipe",
+            json!({"recipe_id": 2, "confirm": preview["confirm"]}),
+        )
+        .await;
+    assert_eq!(stale["code"], "invalid_params");
+    assert!(harness.tandoor.row("recipe", 2).is_some());
+}
+
+#[tokio::test]
+async fn test_duplicate_recipe() {
+    let harness = Harness::start().await;
+
+    let copy = harness
+        .call(
+            "duplicate_recipe",
+            json!({"recipe_id": 1, "name": "My Pomodoro", "servings": 4}),
+        )
+        .await;
+    assert_eq!(copy["name"], "My Pomodoro");
+    assert_eq!(copy["servings"], 4);
+    assert_eq!(copy["scaling_factor"], 2.0);
+    assert_eq!(
+        copy["keywords"],
+        json!(["dinner", "vegetarian", "copy-of-recipe-1"])
+    );
+
+    let ingredients = &copy["steps"][0]["ingredients"];
+    assert_eq!(
+        names(ingredients, "food"),
+        vec!["spaghetti", "tomato", "garlic", "olive oil", "basil"]
+    );
+    assert_eq!(ingredients[0]["amount"], 400.0);
+    assert_eq!(ingredients[2]["unit"], "clove");
+    assert_eq!(ingredients[4]["no_amount"], true);
+    assert_eq!(ingredients[4]["amount"], 0.0);
+
+    // The copy has its own steps and ingredients; the original is untouched
+    let copy_id = copy["id"].as_i64().unwrap();
+    assert_ne!(copy["steps"][0]["id"], 1);
+    assert_ne!(ingredients[0]["id"], 1);
+    let original = harness.call("get_recipe_details", json!({"id": 1})).await;
+    assert_eq!(original["ingredients"][0]["amount"], 200.0);
+    assert_eq!(original["servings"], 2);
+
+    let default_name = harness
+        .call("duplicate_recipe", json!({"recipe_id": 2}))
+        .await;
+    assert_eq!(default_name["name"], "Garlic Bread (copy)");
+    assert_eq!(default_name["servings"], 4);
+    assert_ne!(default_name["id"], copy_id);
+}
+
+#[tokio::test]
+async fn test_import_recipe() {
+    let harness = Harness::start().await;
+    let recipes_before = harness.tandoor.rows("recipe").len();
+
+    let preview = harness
+        .call(
+            "import_recipe",
+            json!({"url": "https://example.com/tomato-soup", "preview": true}),
+        )
+        .await;
+    assert_eq!(preview["saved"], false);
+    assert_eq!(preview["preview"]["name"], "Tomato Soup");
+    assert_eq!(preview["preview"]["keywords"], json!(["soup"]));
+    assert_eq!(
+        preview["skipped_ingredients"],
+        json!(["salt and pepper to taste"])
+    );
+    assert_eq!(harness.tandoor.rows("recipe").len(), recipes_before);
+
+    let imported = harness
+        .call(
+            "import_recipe",
+            json!({
+                "url": "https://example.com/tomato-soup",
+                "keywords": ["dinner"],
+                "book_id": 1
+            }),
+        )
+        .await;
+    assert_eq!(imported["saved"], true);
+    assert_eq!(imported["keywords"], json!(["soup", "dinner"]));
+    assert_eq!(imported["source_url"], "https://example.com/tomato-soup");
+    assert_eq!(imported["book"], json!({"book_id": 1, "added": true}));
+    assert_eq!(
+        imported["skipped_ingredients"],
+        json!(["salt and pepper to taste"])
+    );
+
+    let details = harness
+        .call("get_recipe_details", json!({"id": imported["id"]}))
+        .await;
+    assert_eq!(
+        names(&details["ingredients"], "food"),
+        vec!["tomato", "garlic"]
+    );
+
+    let unknown = harness
+        .call_error(
+            "import_recipe",
+            json!({"url": "https://example.com/not-a-recipe"}),
+        )
+        .await;
+    assert_eq!(unknown["code"], "validation_error");
+    assert_eq!(unknown["url"], "https://example.com/not-a-recipe");
+}
+
+#[tokio::test]
+async fn test_recipe_books() {
+    let harness = Harness::start().await;
+
+    let books = harness.call("get_recipe_books", json!({})).await;
+    assert_eq!(names(&books["books"], "name"), vec!["Weeknight"]);
+
+    let created = harness
+        .call(
+            "create_recipe_book",
+            json!({"name": "Holidays", "description": "Feasts"}),
+        )
+        .await;
+    let book_id = created["id"].as_i64().unwrap();
+
+    let entry = harness
+        .call(
+            "add_recipe_to_book",
+            json!({"book_id": book_id, "recipe_id": 3}),
+        )
+        .await;
+    assert_eq!(entry["book_id"], book_id);
+    assert_eq!(entry["recipe_id"], 3);
+
+    let books = harness.call("get_recipe_books", json!({})).await;
+    assert_eq!(books["total_count"], 2);
+
+    let missing_book = harness
+        .call_error(
+            "add_recipe_to_book",
+            json!({"book_id": 999, "recipe_id": 3}),
+        )
+        .await;
+    assert_eq!(missing_book["code"], "validation_error");
+    assert!(missing_book["field_errors"]["book"].is_array());
+}
+
+#[tokio::test]
+async fn test_shopping_list_round_trip() {
+    let harness = Harness::start().await;
+
+    let added = harness
+        .call(
+            "add_to_shopping_list",
+            json!({"items": [
+                {"name": "flour", "amount": 2, "unit": "kg"},
+                {"name": "dragon fruit"}
+            ]}),
+        )
+        .await;
+    assert_eq!(names(&added["added"], "food"), vec!["flour"]);
+    assert_eq!(added["added"][0]["unit"], "kg");
+    assert_eq!(added["errors"][0]["food"], "dragon fruit");
+    assert_eq!(added["errors"][0]["error"], "Food not found");
+
+    let parsed = harness
+        .call(
+            "add_to_shopping_list",
+            json!({"request": "2 lbs chicken thighs (skin on), a dozen eggs"}),
+        )
+        .await;
+    assert_eq!(
+        names(&parsed["added"], "food"),
+        vec!["chicken thigh", "egg"]
+    );
+    assert_eq!(parsed["added"][0]["unit"], "pound");
+    assert_eq!(parsed["added"][1]["amount"], 12.0);
+    // Shopping list entries cannot hold the note, so it is reported as not saved
+    assert!(parsed["parsed"][0].get("note").is_none());
+    assert_eq!(
+        parsed["notes_not_saved"],
+        json!([{"food": "chicken thighs", "note": "skin on"}])
+    );
+
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(list["total_items"], 3);
+    let egg_id = list["items"][2]["id"].clone();
+
+    let checked = harness
+        .call("check_shopping_items", json!({"items": ["flour", egg_id]}))
+        .await;
+    assert_eq!(names(&checked["updated"], "food"), vec!["flour", "egg"]);
+
+    let grouped = harness
+        .call("get_shopping_list", json!({"format": "grouped"}))
+        .await;
+    assert_eq!(
+        names(&grouped["unchecked_items"], "food"),
+        vec!["chicken thigh"]
+    );
+    assert_eq!(grouped["checked_items"].as_array().unwrap().len(), 2);
+
+    let cleared = harness.call("clear_shopping_list", json!({})).await;
+    assert_eq!(cleared["pantry_updates"], json!(["flour", "egg"]));
+    assert_eq!(harness.tandoor.row("food", 8).unwrap()["food_onhand"], true);
+
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(names(&list["items"], "food"), vec!["chicken thigh"]);
+
+    let empty = harness.call_error("add_to_shopping_list", json!({})).await;
+    assert_eq!(empty["code"], "invalid_params");
+}
+
+#[tokio::test]
+async fn test_add_to_shopping_list_merges_items_with_compatible_units() {
+    let harness = Harness::start().await;
+
+    let added = harness
+        .call(
+            "add_to_shopping_list",
+            json!({"request": "500 g flour, 1 kg flour, 1 cup flour"}),
+        )
+        .await;
+
+    assert_eq!(names(&added["added"], "food"), vec!["flour", "flour"]);
+    assert_eq!(added["added"][0]["amount"], 1500.0);
+    assert_eq!(added["added"][0]["unit"], "g");
+    assert_eq!(added["added"][1]["unit"], "cup");
+    assert!(added["summary"]
+        .as_str()
+        .unwrap()
+        .contains("1 duplicate items merged"));
+}
+
+#[tokio::test]
+async fn test_shopping_list_consolidates_duplicate_foods() {
+    let harness = Harness::start().await;
+    harness
+        .call("add_to_shopping_list", json!({"request": "500 g flour"}))
+        .await;
+    harness
+        .call(
+            "add_to_shopping_list",
+            json!({"request": "1 kg flour, 2 cloves garlic"}),
+        )
+        .await;
+
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(list["format"], "consolidated");
+    assert_eq!(names(&list["items"], "food"), vec!["flour", "garlic"]);
+    assert_eq!(list["items"][0]["amount"], 1500.0);
+    assert_eq!(list["items"][0]["unit"], "g");
+    assert_eq!(list["items"][0]["entry_ids"].as_array().unwrap().len(), 2);
+    assert_eq!(list["duplicates_merged"], 1);
+
+    let preview = harness
+        .call("merge_shopping_list_duplicates", json!({"dry_run": true}))
+        .await;
+    assert_eq!(names(&preview["merged"], "food"), vec!["flour"]);
+    let flat = harness
+        .call("get_shopping_list", json!({"format": "flat"}))
+        .await;
+    assert_eq!(flat["total_items"], 3);
+
+    let merged = harness
+        .call("merge_shopping_list_duplicates", json!({}))
+        .await;
+    assert_eq!(merged["merged"][0]["amount"], 1500.0);
+    let flat = harness
+        .call("get_shopping_list", json!({"format": "flat"}))
+        .await;
+    assert_eq!(names(&flat["items"], "food"), vec!["flour", "garlic"]);
+    assert_eq!(flat["items"][0]["amount"], 1500.0);
+
+    let unknown = harness
+        .call_error("get_shopping_list", json!({"format": "by_color"}))
+        .await;
+    assert_eq!(unknown["code"], "invalid_params");
+    let misplaced = harness
+        .call_error(
+            "get_shopping_list",
+            json!({"format": "flat", "supermarket": "Corner Market"}),
+        )
+        .await;
+    assert!(misplaced["field_errors"]["supermarket"].is_array());
+}
+
+#[tokio::test]
+async fn test_shopping_list_by_aisle_follows_store_order() {
+    let harness = Harness::start().await;
+    harness
+        .call(
+            "add_to_shopping_list",
+            json!({"request": "4 tomatoes, 2 chicken thighs, 500 g flour, a dozen eggs, 1 bread"}),
+        )
+        .await;
+
+    let corner = harness
+        .call(
+            "get_shopping_list",
+            json!({"format": "by_aisle", "supermarket": "corner market"}),
+        )
+        .await;
+    assert_eq!(corner["supermarket"], "Corner Market");
+    assert_eq!(
+        names(&corner["aisles"], "category"),
+        vec!["Produce", "Bakery", "Meat", "Pantry", "Other"]
+    );
+    assert_eq!(names(&corner["aisles"][0]["items"], "food"), vec!["tomato"]);
+    assert_eq!(corner["aisles"][4]["items"][0]["food"], "egg");
+
+    // Sections Big Box does not stock come after its own, by name
+    let big_box = harness
+        .call(
+            "get_shopping_list",
+            json!({"format": "by_aisle", "supermarket": "2"}),
+        )
+        .await;
+    assert_eq!(
+        names(&big_box["aisles"], "category"),
+        vec!["Pantry", "Produce", "Bakery", "Meat", "Other"]
+    );
+
+    let unsorted = harness
+        .call("get_shopping_list", json!({"format": "by_aisle"}))
+        .await;
+    assert_eq!(unsorted["supermarket"], Value::Null);
+    assert_eq!(
+        unsorted["available_supermarkets"],
+        json!(["Corner Market", "Big Box"])
+    );
+    assert_eq!(
+        names(&unsorted["aisles"], "category"),
+        vec!["Bakery", "Meat", "Pantry", "Produce", "Other"]
+    );
+
+    let unknown = harness
+        .call_error(
+            "get_shopping_list",
+            json!({"format": "by_aisle", "supermarket": "Moon Mart"}),
+        )
+        .await;
+    assert_eq!(unknown["code"], "invalid_params");
+}
+
+#[tokio::test]
+async fn test_manage_supermarkets_and_food_categories() {
+    let harness = Harness::start().await;
+
+    let listed = harness.call("get_supermarkets", json!({})).await;
+    assert_eq!(
+        names(&listed["supermarkets"], "name"),
+        vec!["Corner Market", "Big Box"]
+    );
+    assert_eq!(
+        names(&listed["supermarkets"][1]["categories"], "name"),
+        vec!["Pantry", "Produce"]
+    );
+    assert_eq!(listed["categories"].as_array().unwrap().len(), 5);
+
+    let created = harness
+        .call(
+            "create_supermarket",
+            json!({"name": "Farmers Market", "categories": ["Produce", "Cheese"]}),
+        )
+        .await;
+    assert_eq!(created["name"], "Farmers Market");
+    assert_eq!(
+        names(&created["categories"], "name"),
+        vec!["Produce", "Cheese"]
+    );
+
+    let reordered = harness
+        .call(
+            "set_supermarket_categories",
+            json!({"supermarket": "big box", "categories": ["Meat", "Pantry"]}),
+        )
+        .await;
+    assert_eq!(reordered["success"], true);
+    assert_eq!(
+        names(&reordered["categories"], "name"),
+        vec!["Meat", "Pantry"]
+    );
+
+    let shelved = harness
+        .call(
+            "set_food_category",
+            json!({"foods": ["eggs", "dragon fruit"], "category": "Cheese"}),
+        )
+        .await;
+    assert_eq!(shelved["updated"][0]["food"], "egg");
+    assert_eq!(shelved["updated"][0]["category"], "Cheese");
+    assert_eq!(shelved["errors"][0]["food"], "dragon fruit");
+
+    harness
+        .call("add_to_shopping_list", json!({"request": "a dozen eggs"}))
+        .await;
+    let list = harness
+        .call(
+            "get_shopping_list",
+            json!({"format": "by_aisle", "supermarket": "Farmers Market"}),
+        )
+        .await;
+    assert_eq!(names(&list["aisles"], "category"), vec!["Cheese"]);
+
+    let cleared = harness
+        .call("set_food_category", json!({"foods": ["egg"]}))
+        .await;
+    assert_eq!(cleared["updated"][0]["category"], Value::Null);
+
+    let repeated = harness
+        .call_error(
+            "set_supermarket_categories",
+            json!({"supermarket": 1, "categories": ["Meat", "meat"]}),
+        )
+        .await;
+    assert_eq!(repeated["code"], "invalid_params");
+}
+
+#[tokio::test]
+async fn test_check_shopping_items_by_name_checks_every_entry() {
+    let harness = Harness::start().await;
+    harness
+        .call("add_to_shopping_list", json!({"request": "2 eggs"}))
+        .await;
+    harness
+        .call("add_to_shopping_list", json!({"request": "a dozen eggs"}))
+        .await;
+
+    let checked = harness
+        .call("check_shopping_items", json!({"items": ["eggs"]}))
+        .await;
+    assert_eq!(names(&checked["updated"], "food"), vec!["egg", "egg"]);
+
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(list["items"], json!([]));
+    assert_eq!(list["checked_items"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_shop_for_recipe_and_remove_it_again() {
+    let harness = Harness::start().await;
+
+    let added = harness
+        .call(
+            "add_to_shopping_list",
+            json!({"from_recipe": {"recipe_id": 1, "servings": 4}}),
+        )
+        .await;
+    assert_eq!(added["scaling_factor"], 2.0);
+    // Garlic and olive oil are on hand; basil has no amount but is still bought
+    assert_eq!(
+        names(&added["added"], "food"),
+        vec!["spaghetti", "tomato", "basil"]
+    );
+    assert_eq!(added["added"][0]["amount"], 400.0);
+    assert_eq!(
+        names(&added["skipped"], "food"),
+        vec!["garlic", "olive oil"]
+    );
+
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(list["total_items"], 3);
+
+    harness
+        .call(
+            "remove_recipe_from_shopping_list",
+            json!({"list_recipe_id": added["list_recipe_id"]}),
+        )
+        .await;
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(list["total_items"], 0);
+}
+
+#[tokio::test]
+async fn test_merge_keeps_recipes_apart() {
+    let harness = Harness::start().await;
+
+    let mut added = Vec::new();
+    for recipe_id in [2, 3] {
+        added.push(
+            harness
+                .call(
+                    "add_to_shopping_list",
+                    json!({"from_recipe": {"recipe_id": recipe_id, "include_on_hand": true}}),
+                )
+                .await,
+        );
+    }
+    // Both recipes use garlic: the list shows one total, but in Tandoor each recipe's
+    // cloves stay its own entry
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(list["duplicates_merged"], 1);
+    let garlic = list["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["food"] == "garlic")
+        .unwrap();
+    assert_eq!(garlic["amount"], 7.0);
+    assert_eq!(garlic["entry_ids"].as_array().unwrap().len(), 2);
+    let merged = harness
+        .call("merge_shopping_list_duplicates", json!({}))
+        .await;
+    assert_eq!(merged["merged"], json!([]));
+
+    harness
+        .call(
+            "remove_recipe_from_shopping_list",
+            json!({"list_recipe_id": added[0]["list_recipe_id"]}),
+        )
+        .await;
+    let flat = harness
+        .call("get_shopping_list", json!({"format": "flat"}))
+        .await;
+    assert_eq!(
+        names(&flat["items"], "food"),
+        vec!["chicken thigh", "olive oil", "garlic"]
+    );
+    assert_eq!(flat["items"][2]["amount"], 4.0);
+}
+
+#[tokio::test]
+async fn test_foods_pantry_and_suggestions() {
+    let harness = Harness::start().await;
+
+    let foods = harness.call("search_foods", json!({"query": "tom"})).await;
+    assert_eq!(names(&foods["foods"], "name"), vec!["tomato"]);
+
+    let updated = harness
+        .call(
+            "update_pantry",
+            json!({"items": [
+                {"food": "spaghetti", "available": true},
+                {"food": "tomato", "available": true},
+                {"food": "unobtainium", "available": true}
+            ]}),
+        )
+        .await;
+    assert_eq!(
+        names(&updated["updated"], "name"),
+        vec!["spaghetti", "tomato"]
+    );
+    assert_eq!(updated["errors"][0]["food"], "unobtainium");
+
+    let suggestions = harness.call("suggest_from_inventory", json!({})).await;
+    let suggested = names(&suggestions["suggestions"], "recipe_name");
+    assert!(suggested.contains(&"Spaghetti Pomodoro".to_string()));
+    assert!(suggested.contains(&"Garlic Bread".to_string()));
+    assert_eq!(suggestions["suggestions"][0]["match_percentage"], 100.0);
+}
+
+#[tokio::test]
+async fn test_meal_plans() {
+    let harness = Harness::start().await;
+
+    let meal_types = harness.call("get_meal_types", json!({})).await;
+    assert_eq!(
+        names(&meal_types["meal_types"], "name"),
+        vec!["Breakfast", "Lunch", "Dinner"]
+    );
+
+    let created = harness
+        .call(
+            "create_meal_plan",
+            json!({"recipe_id": 2, "servings": 4, "date": "2026-10-20", "meal_type": 2}),
+        )
+        .await;
+    assert_eq!(created["recipe_name"], "Garlic Bread");
+    assert_eq!(created["meal_type"], "Lunch");
+
+    let week = harness
+        .call(
+            "get_meal_plans",
+            json!({"from_date": "2026-10-19", "to_date": "2026-10-25"}),
+        )
+        .await;
+    assert_eq!(week["total_count"], 2);
+
+    let dinners = harness
+        .call(
+            "get_meal_plans",
+            json!({"from_date": "2026-10-19", "to_date": "2026-10-25", "meal_type": "dinner"}),
+        )
+        .await;
+    assert_eq!(
+        names(&dinners["meal_plans"], "recipe_name"),
+        vec!["Spaghetti Pomodoro"]
+    );
+
+    let bad_date = harness
+        .call_error(
+            "create_meal_plan",
+            json!({"recipe_id": 2, "servings": 4, "date": "20/10/2026", "meal_type": 2}),
+        )
+        .await;
+    assert_eq!(bad_date["code"], "invalid_params");
+    assert!(bad_date["field_errors"]["date"].is_array());
+
+    let bad_meal_type = harness
+        .call_error(
+            "create_meal_plan",
+            json!({"recipe_id": 2, "servings": 4, "date": "2026-10-20", "meal_type": 99}),
+        )
+        .await;
+    assert_eq!(bad_meal_type["code"], "validation_error");
+    assert!(bad_meal_type["field_errors"]["meal_type"].is_array());
+
+    harness
+        .call("delete_meal_plan", json!({"id": created["id"]}))
+        .await;
+    let week = harness
+        .call(
+            "get_meal_plans",
+            json!({"from_date": "2026-10-19", "to_date": "2026-10-25"}),
+        )
+        .await;
+    assert_eq!(week["total_count"], 1);
+
+    let missing = harness
+        .call_error("delete_meal_plan", json!({"id": 999}))
+        .await;
+    assert_eq!(missing["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_meal_plans_by_day_and_meal_type() {
+    let harness = Harness::start().await;
+
+    for (recipe_id, servings, meal_type) in [(3, 2, 3), (2, 4, 2)] {
+        harness
+            .call(
+                "create_meal_plan",
+                json!({"recipe_id": recipe_id, "servings": servings, "date": "2026-10-20", "meal_type": meal_type}),
+            )
+            .await;
+    }
+
+    let week = harness
+        .call(
+            "get_meal_plans",
+            json!({"from_date": "2026-10-19", "to_date": "2026-10-25"}),
+        )
+        .await;
+    assert_eq!(
+        names(&week["days"], "date"),
+        vec!["2026-10-19", "2026-10-20"]
+    );
+    let tuesday = &week["days"][1];
+    assert_eq!(tuesday["weekday"], "Tuesday");
+    assert_eq!(
+        names(&tuesday["meals"], "meal_type"),
+        vec!["Lunch", "Dinner"]
+    );
+    assert_eq!(
+        names(&tuesday["meals"][1]["plans"], "recipe_name"),
+        vec!["Roast Chicken Thighs"]
+    );
+    assert_eq!(
+        tuesday["totals"],
+        json!({"meals": 2, "servings": 6, "total_time_minutes": 70})
+    );
+
+    for meal_type in [json!("DINNER"), json!(3)] {
+        let dinners = harness
+            .call(
+                "get_meal_plans",
+                json!({"from_date": "2026-10-19", "to_date": "2026-10-25", "meal_type": meal_type}),
+            )
+            .await;
+        assert_eq!(
+            names(&dinners["meal_plans"], "recipe_name"),
+            vec!["Spaghetti Pomodoro", "Roast Chicken Thighs"]
+        );
+        assert_eq!(
+            dinners["meal_type_filter"],
+            json!({"id": 3, "name": "Dinner"})
+        );
+        assert_eq!(dinners["days"][1]["totals"]["meals"], 1);
+    }
+
+    let unknown = harness
+        .call_error(
+            "get_meal_plans",
+            json!({"from_date": "2026-10-19", "to_date": "2026-10-25", "meal_type": "Brunch"}),
+        )
+        .await;
+    assert_eq!(unknown["code"], "invalid_params");
+    assert!(unknown["field_errors"]["meal_type"].is_array());
+}
+
+#[tokio::test]
+async fn test_update_and_move_meal_plans() {
+    let harness = Harness::start().await;
+
+    let updated = harness
+        .call(
+            "update_meal_plan",
+            json!({"id": 1, "servings": 4, "note": "Double batch", "meal_type": "lunch"}),
+        )
+        .await;
+    assert_eq!(updated["id"], 1);
+    assert_eq!(updated["servings"], 4);
+    assert_eq!(updated["note"], "Double batch");
+    assert_eq!(updated["meal_type"], "Lunch");
+    assert_eq!(updated["recipe_name"], "Spaghetti Pomodoro");
+
+    let nothing = harness
+        .call_error("update_meal_plan", json!({"id": 1}))
+        .await;
+    assert_eq!(nothing["code"], "invalid_params");
+    let bad_date = harness
+        .call_error("update_meal_plan", json!({"id": 1, "date": "Thursday"}))
+        .await;
+    assert!(bad_date["field_errors"]["date"].is_array());
+    let bad_recipe = harness
+        .call_error("update_meal_plan", json!({"id": 1, "recipe_id": 99}))
+        .await;
+    assert_eq!(bad_recipe["code"], "validation_error");
+
+    let chicken = harness
+        .call(
+            "create_meal_plan",
+            json!({"recipe_id": 3, "servings": 2, "date": "2026-10-20", "meal_type": 3}),
+        )
+        .await;
+    let moved = harness
+        .call(
+            "move_meal_plan",
+            json!({"id": chicken["id"], "to": "Thursday"}),
+        )
+        .await;
+    assert_eq!(moved["meal_plan"]["id"], chicken["id"]);
+    assert_eq!(moved["moved_from"]["date"], "2026-10-20");
+    assert_eq!(
+        moved["moved_to"],
+        json!({"date": "2026-10-22", "meal_type": "Dinner"})
+    );
+
+    harness
+        .call(
+            "create_meal_plan",
+            json!({"recipe_id": 2, "servings": 2, "date": "2026-10-20", "meal_type": 3}),
+        )
+        .await;
+    let free = harness
+        .call(
+            "move_meal_plan",
+            json!({"id": 1, "to": "next free dinner slot"}),
+        )
+        .await;
+    assert_eq!(
+        free["moved_to"],
+        json!({"date": "2026-10-21", "meal_type": "Dinner"})
+    );
+    assert_eq!(free["already_planned"], json!([]));
+    let row = harness.tandoor.row("meal-plan", 1).unwrap();
+    assert_eq!(row["date"], "2026-10-21");
+    assert_eq!(row["meal_type"], 3);
+
+    let shared = harness
+        .call(
+            "move_meal_plan",
+            json!({"id": chicken["id"], "to": "-1 day"}),
+        )
+        .await;
+    assert_eq!(shared["moved_to"]["date"], "2026-10-21");
+    assert_eq!(
+        names(&shared["already_planned"], "recipe_name"),
+        vec!["Spaghetti Pomodoro"]
+    );
+
+    let unknown = harness
+        .call_error("move_meal_plan", json!({"id": 1, "to": "someday"}))
+        .await;
+    assert_eq!(unknown["code"], "invalid_params");
+    assert!(unknown["field_errors"]["to"].is_array());
+    for to in ["+9999999999999999 days", "+999999999 days"] {
+        let out_of_range = harness
+            .call_error("move_meal_plan", json!({"id": 1, "to": to}))
+            .await;
+        assert_eq!(out_of_range["code"], "invalid_params");
+        assert!(out_of_range["field_errors"]["to"].is_array());
+    }
+    let missing = harness
+        .call_error("move_meal_plan", json!({"id": 999, "to": "tomorrow"}))
+        .await;
+    assert_eq!(missing["code"], "not_found");
+}
+
+#[tokio::test]
+async fn test_generate_meal_plan() {
+    let harness = Harness::start().await;
+    let week = json!({"from_date": "2026-10-19", "to_date": "2026-10-22"});
+
+    let draft = harness.call("generate_meal_plan", week.clone()).await;
+    assert_eq!(draft["created"], false);
+    assert_eq!(
+        names(&draft["already_planned"], "recipe_name"),
+        vec!["Spaghetti Pomodoro"]
+    );
+    assert_eq!(
+        names(&draft["days"], "date"),
+        vec!["2026-10-20", "2026-10-21"]
+    );
+    assert_eq!(
+        draft["days"][0]["meals"][0]["recipe_name"],
+        "Roast Chicken Thighs"
+    );
+    let bread = &draft["days"][1]["meals"][0];
+    assert_eq!(bread["recipe_name"], "Garlic Bread");
+    assert_eq!(bread["meal_type"], "Dinner");
+    assert_eq!(bread["pantry_items"], json!(["bread", "butter", "garlic"]));
+    assert_eq!(
+        draft["unfilled_slots"],
+        json!([{"date": "2026-10-22", "meal_type": "Dinner"}])
+    );
+    assert_eq!(harness.tandoor.rows("meal-plan").len(), 1);
+
+    let balanced = harness
+        .call(
+            "generate_meal_plan",
+            json!({"from_date": "2026-10-20", "to_date": "2026-10-21", "meal_types": ["lunch", 3]}),
+        )
+        .await;
+    let totals: Vec<i64> = balanced["days"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|day| day["total_time_minutes"].as_i64().unwrap())
+        .collect();
+    assert_eq!(totals, vec![55, 40]);
+    assert_eq!(
+        names(&balanced["days"][1]["meals"], "recipe_name"),
+        vec!["Garlic Bread", "Spaghetti Pomodoro"]
+    );
+    assert_eq!(
+        names(&balanced["days"][1]["meals"], "meal_type"),
+        vec!["Lunch", "Dinner"]
+    );
+
+    let vegetarian = harness
+        .call(
+            "generate_meal_plan",
+            json!({"from_date": "2026-10-20", "to_date": "2026-10-21", "meal_types": ["Dinner"],
+                   "include_keywords": ["dinner"], "exclude_keywords": ["Vegetarian"]}),
+        )
+        .await;
+    assert_eq!(vegetarian["total_meals"], 1);
+    assert_eq!(
+        vegetarian["days"][0]["meals"][0]["recipe_name"],
+        "Roast Chicken Thighs"
+    );
+    let unknown = harness
+        .call_error(
+            "generate_meal_plan",
+            json!({"from_date": "2026-10-20", "to_date": "2026-10-21", "include_keywords": ["dessert"]}),
+        )
+        .await;
+    assert!(unknown["field_errors"]["include_keywords"].is_array());
+
+    harness
+        .call("log_cooked_recipe", json!({"recipe_id": 3}))
+        .await;
+    let draft = harness.call("generate_meal_plan", week.clone()).await;
+    assert_eq!(
+        draft["skipped_recently_cooked"],
+        json!(["Roast Chicken Thighs"])
+    );
+    assert_eq!(draft["total_meals"], 1);
+
+    let mut stale = week.clone();
+    stale["confirm"] = json!("generate-meal-plan-0000000000000000");
+    let rejected = harness.call_error("generate_meal_plan", stale).await;
+    assert_eq!(rejected["code"], "invalid_params");
+    let mut unknown = week.clone();
+    unknown["include_keywords"] = json!(["dessert", "brunch"]);
+    let rejected = harness.call_error("generate_meal_plan", unknown).await;
+    assert_eq!(
+        rejected["field_errors"]["include_keywords"],
+        json!(["Unknown keyword 'dessert'", "Unknown keyword 'brunch'"])
+    );
+    let mut far_back = week.clone();
+    far_back["avoid_cooked_within_days"] = json!(i64::MAX);
+    let rejected = harness.call_error("generate_meal_plan", far_back).await;
+    assert!(rejected["field_errors"]["avoid_cooked_within_days"].is_array());
+
+    let mut confirmed = week.clone();
+    confirmed["confirm"] = draft["confirm"].clone();
+    let created = harness.call("generate_meal_plan", confirmed).await;
+    assert_eq!(created["created"], true);
+    assert_eq!(
+        names(&created["meal_plans"], "recipe_name"),
+        vec!["Garlic Bread"]
+    );
+    let row = harness
+        .tandoor
+        .row(
+            "meal-plan",
+            created["meal_plans"][0]["id"].as_i64().unwrap(),
+        )
+        .unwrap();
+    assert_eq!(row["date"], "2026-10-20");
+    assert_eq!(row["servings"], 4);
+
+    let next = harness.call("generate_meal_plan", week).await;
+    assert_eq!(next["total_meals"], 0);
+}
+
+#[tokio::test]
+async fn test_shop_for_meal_plans() {
+    let harness = Harness::start().await;
+
+    for plan in [
+        json!({"recipe_id": 1, "servings": 4, "date": "2026-10-20", "meal_type": 2}),
+        json!({"recipe_id": 3, "servings": 2, "date": "2026-10-21", "meal_type": 3}),
+        json!({"title": "Leftovers", "servings": 2, "date": "2026-10-22", "meal_type": 3}),
+        json!({"recipe_id": 2, "servings": 4, "date": "2026-10-30", "meal_type": 3}),
+    ] {
+        harness.call("create_meal_plan", plan).await;
+    }
+
+    let shopped = harness
+        .call(
+            "shop_for_meal_plans",
+            json!({"from_date": "2026-10-19", "to_date": "2026-10-25"}),
+        )
+        .await;
+    assert_eq!(
+        names(&shopped["added"], "food"),
+        vec!["spaghetti", "tomato", "basil", "chicken thigh"]
+    );
+    let spaghetti = &shopped["added"][0];
+    assert_eq!(spaghetti["amount"], 600.0);
+    assert_eq!(spaghetti["unit"], "g");
+    assert_eq!(
+        names(&spaghetti["meals"], "date"),
+        vec!["2026-10-19", "2026-10-20"]
+    );
+    assert_eq!(spaghetti["meals"][1]["amount"], 400.0);
+    assert_eq!(shopped["added"][1]["amount"], 12.0);
+    assert_eq!(shopped["added"][3]["amount"], 1.0);
+    assert_eq!(shopped["merged_count"], 3);
+    assert_eq!(
+        names(&shopped["skipped"], "food"),
+        vec!["garlic", "olive oil"]
+    );
+    assert_eq!(shopped["skipped"][0]["meals"].as_array().unwrap().len(), 3);
+    assert_eq!(names(&shopped["skipped_meals"], "title"), vec!["Leftovers"]);
+    assert_eq!(
+        names(&shopped["meals"], "recipe_name"),
+        vec![
+            "Spaghetti Pomodoro",
+            "Spaghetti Pomodoro",
+            "Roast Chicken Thighs"
+        ]
+    );
+    assert_eq!(
+        names(&shopped["meals"][2]["items"], "food"),
+        vec!["chicken thigh"]
+    );
+
+    // Each meal's entries hang off its own shopping list recipe, linked to its meal plan
+    let entries = harness.tandoor.rows("shopping-list-entry");
+    assert_eq!(entries.len(), 7);
+    assert_eq!(spaghetti["entry_ids"].as_array().unwrap().len(), 2);
+    let list_recipe = shopped["meals"][0]["list_recipe_id"].as_i64().unwrap();
+    assert_eq!(entries[0]["list_recipe"], list_recipe);
+    assert_eq!(
+        harness
+            .tandoor
+            .row("shopping-list-recipe", list_recipe)
+            .unwrap()["mealplan"],
+        1
+    );
+    assert!(shopped["meals"][1]["list_recipe_id"].is_i64());
+
+    // The list still shows one total, and removing the first meal keeps the second's
+    let list = harness.call("get_shopping_list", json!({})).await;
+    assert_eq!(list["items"][0]["food"], "spaghetti");
+    assert_eq!(list["items"][0]["amount"], 600.0);
+    harness
+        .call(
+            "remove_recipe_from_shopping_list",
+            json!({"list_recipe_id": list_recipe}),
+        )
+        .await;
+    let flat = harness
+        .call("get_shopping_list", json!({"format": "flat"}))
+        .await;
+    assert_eq!(
+        names(&flat["items"], "food"),
+        vec!["spaghetti", "tomato", "basil", "chicken thigh"]
+    );
+    assert_eq!(flat["items"][0]["amount"], 400.0);
+
+    let dinners = harness
+        .call(
+            "shop_for_meal_plans",
+            json!({"from_date": "2026-10-19", "to_date": "2026-10-25", "meal_type": "Dinner", "include_on_hand": true}),
+        )
+        .await;
+    let garlic = dinners["added"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|item| item["food"] == "garlic")
+        .unwrap();
+    assert_eq!(garlic["amount"], 4.0);
+    assert_eq!(garlic["unit"], "clove");
+    assert_eq!(
+        names(&garlic["meals"], "recipe_name"),
+        vec!["Spaghetti Pomodoro", "Roast Chicken Thighs"]
+    );
+
+    let backwards = harness
+        .call_error(
+            "shop_for_meal_plans",
+            json!({"from_date": "2026-10-25", "to_date": "2026-10-19"}),
+        )
+        .await;
+    assert_eq!(backwards["code"], "invalid_params");
+}
+
+#[tokio::test]
+async fn test_copy_meal_plans_and_templates() {
+    let tandoor = FakeTandoor::start().await;
+    let server = TandoorMcpServer::new_with_credentials(
+        tandoor.base_url(),
+        USERNAME.to_string(),
+        PASSWORD.to_string(),
+    );
+    let dir =
+        std::env::temp_dir().join(format!("mcp-tandoor-e2e-templates-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    server.set_meal_template_store(MealTemplateStore::new(dir.join("meal-templates.json")));
+    let harness = Harness::connect(tandoor, server).await;
+
+    for plan in [
+        json!({"recipe_id": 3, "servings": 4, "date": "2026-10-20", "meal_type": 3}),
+        json!({"title": "Leftovers", "servings": 2, "date": "2026-10-21", "meal_type": 2}),
+    ] {
+        harness.call("create_meal_plan", plan).await;
+    }
+    let week =
+        json!({"from_date": "2026-10-19", "to_date": "2026-10-25", "target_date": "2026-10-26"});
+
+    let copied = harness.call("copy_meal_plans", week.clone()).await;
+    assert_eq!(copied["copied"], true);
+    assert_eq!(copied["target_range"], "2026-10-26 to 2026-11-01");
+    assert_eq!(
+        names(&copied["meal_plans"], "date"),
+        vec!["2026-10-26", "2026-10-27", "2026-10-28"]
+    );
+    assert_eq!(
+        copied["meal_plans"][1]["recipe_name"],
+        "Roast Chicken Thighs"
+    );
+    assert_eq!(copied["meal_plans"][1]["servings"], 4);
+    assert_eq!(copied["meal_plans"][2]["title"], "Leftovers");
+    assert_eq!(copied["meal_plans"][2]["meal_type"], "Lunch");
+    assert_eq!(harness.tandoor.rows("meal-plan").len(), 6);
+
+    // Copying onto the same week again only reports the filled slots
+    let conflicting = harness.call("copy_meal_plans", week.clone()).await;
+    assert_eq!(conflicting["copied"], false);
+    assert_eq!(
+        names(&conflicting["conflicts"], "date"),
+        vec!["2026-10-26", "2026-10-27", "2026-10-28"]
+    );
+    assert_eq!(
+        conflicting["conflicts"][1]["existing"][0]["recipe_name"],
+        "Roast Chicken Thighs"
+    );
+    let mut skip = week.clone();
+    skip["on_conflict"] = json!("skip");
+    let skipped = harness.call("copy_meal_plans", skip).await;
+    assert_eq!(skipped["meal_plans"], json!([]));
+    assert_eq!(skipped["skipped"].as_array().unwrap().len(), 3);
+    assert_eq!(harness.tandoor.rows("meal-plan").len(), 6);
+
+    let saved = harness
+        .call(
+            "save_meal_plan_template",
+            json!({"name": "Standard week", "from_date": "2026-10-19", "to_date": "2026-10-25"}),
+        )
+        .await;
+    assert_eq!(saved["template"]["days"], 7);
+    assert_eq!(
+        saved["template"]["meals"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|meal| meal["day_offset"].as_i64().unwrap())
+            .collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    let duplicate = harness
+        .call_error(
+            "save_meal_plan_template",
+            json!({"name": "standard week", "from_date": "2026-10-19", "to_date": "2026-10-25"}),
+        )
+        .await;
+    assert!(duplicate["field_errors"]["name"].is_array());
+    let templates = harness.call("get_meal_plan_templates", json!({})).await;
+    assert_eq!(
+        names(&templates["templates"], "name"),
+        vec!["Standard week"]
+    );
+
+    harness
+        .call(
+            "create_meal_plan",
+            json!({"recipe_id": 2, "servings": 2, "date": "2026-11-03", "meal_type": 3}),
+        )
+        .await;
+    let preview = harness
+        .call(
+            "copy_meal_plans",
+            json!({"template": "STANDARD WEEK", "target_date": "2026-11-02", "on_conflict": "add", "dry_run": true}),
+        )
+        .await;
+    assert_eq!(preview["copied"], false);
+    assert_eq!(preview["meals"][1]["weekday"], "Tuesday");
+    assert_eq!(preview["meals"][1]["conflict"], true);
+    let applied = harness
+        .call(
+            "copy_meal_plans",
+            json!({"template": "standard week", "target_date": "2026-11-02", "on_conflict": "add"}),
+        )
+        .await;
+    assert_eq!(applied["source"], json!({"template": "Standard week"}));
+    assert_eq!(
+        names(&applied["meal_plans"], "date"),
+        vec!["2026-11-02", "2026-11-03", "2026-11-04"]
+    );
+    assert_eq!(
+        applied["conflicts"][0]["existing"][0]["recipe_name"],
+        "Garlic Bread"
+    );
+
+    let unknown = harness
+        .call_error(
+            "copy_meal_plans",
+            json!({"template": "Holidays", "target_date": "2026-11-02"}),
+        )
+        .await;
+    assert!(unknown["field_errors"]["template"].is_array());
+    let ambiguous = harness
+        .call_error(
+            "copy_meal_plans",
+            json!({"template": "Standard week", "from_date": "2026-10-19", "to_date": "2026-10-25", "target_date": "2026-11-02"}),
+        )
+        .await;
+    assert_eq!(ambiguous["code"], "invalid_params");
+    let out_of_range = harness
+        .call_error(
+            "copy_meal_plans",
+            json!({"template": "Standard week", "target_date": "+262142-12-31"}),
+        )
+        .await;
+    assert!(out_of_range["field_errors"]["target_date"].is_array());
+
+    harness
+        .call(
+            "delete_meal_plan_template",
+            json!({"name": "standard week"}),
+        )
+        .await;
+    let templates = harness.call("get_meal_plan_templates", json!({})).await;
+    assert_eq!(templates["total_count"], 0);
+    let missing = harness
+        .call_error(
+            "delete_meal_plan_template",
+            json!({"name": "standard week"}),
+        )
+        .await;
+    assert_eq!(missing["code"], "invalid_params");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[tokio::test]
+async fn test_meal_plan_templates_need_a_store() {
+    let harness = Harness::start().await;
+
+    let error = harness
+        .call_error("get_meal_plan_templates", json!({}))
+        .await;
+    assert_eq!(error["error"], "Meal plan templates are disabled");
+}
+
+#[tokio::test]
+async fn test_cook_log() {
+    let harness = Harness::start().await;
+
+    let logged = harness
+        .call(
+            "log_cooked_recipe",
+            json!({"recipe_id": 3, "servings": 4, "rating": 5, "comment": "Crispy skin"}),
+        )
+        .await;
+    assert_eq!(logged["recipe_name"], "Roast Chicken Thighs");
+    harness
+        .call("log_cooked_recipe", json!({"recipe_id": 1}))
+        .await;
+
+    let all = harness.call("get_cook_log", json!({})).await;
+    assert_eq!(all["total_count"], 2);
+
+    let chicken = harness.call("get_cook_log", json!({"recipe_id": 3})).await;
+    assert_eq!(chicken["total_count"], 1);
+    assert_eq!(chicken["cook_log"][0]["rating"], 5);
+    assert_eq!(chicken["cook_log"][0]["comment"], "Crispy skin");
+}
+
+#[tokio::test]
+async fn test_keywords_and_units() {
+    let harness = Harness::start().await;
+
+    let keywords = harness.call("get_keywords", json!({})).await;
+    assert_eq!(keywords["keywords"][2]["description"], "Under 30 minutes");
+
+    let units = harness.call("get_units", json!({})).await;
+    assert_eq!(
+        names(&units["units"], "name"),
+        vec!["g", "kg", "cup", "tablespoon", "pound", "clove"]
+    );
+}
@@ -0,0 +1,134 @@
+use mcp_tandoor::client::TandoorError;
+use pretty_assertions::assert_eq;
+use reqwest::StatusCode;
+
+#[test]
+fn test_error_from_status() {
+    let error = TandoorError::from_response(
+        StatusCode::NOT_FOUND,
+        None,
+        r#"{"detail": "No Recipe matches the given query."}"#,
+    );
+    assert_eq!(
+        error,
+        TandoorError::NotFound {
+            message: "No Recipe matches the given query.".to_string()
+        }
+    );
+
+    let error = TandoorError::from_response(StatusCode::UNAUTHORIZED, None, "");
+    assert!(matches!(error, TandoorError::Unauthorized { .. }));
+    assert_eq!(error.message(), "Unauthorized");
+
+    let error = TandoorError::from_response(
+        StatusCode::TOO_MANY_REQUESTS,
+        Some(60),
+        r#"{"detail": "Request was throttled."}"#,
+    );
+    assert_eq!(
+        error,
+        TandoorError::RateLimited {
+            message: "Request was throttled.".to_string(),
+            retry_after: Some(60)
+        }
+    );
+
+    let error = TandoorError::from_response(
+        StatusCode::BAD_GATEWAY,
+        None,
+        "<html><body>Bad Gateway</body></html>",
+    );
+    assert_eq!(error.status(), Some(502));
+    assert!(matches!(error, TandoorError::Server { .. }));
+}
+
+#[test]
+fn test_validation_field_errors() {
+    let error = TandoorError::from_response(
+        StatusCode::BAD_REQUEST,
+        None,
+        r#"{"from_date": ["Date has wrong format. Use one of these formats instead: YYYY-MM-DD."], "meal_type": ["This field is required."]}"#,
+    );
+
+    let TandoorError::Validation {
+        message,
+        field_errors,
+    } = error
+    else {
+        panic!("expected a validation error, got {error:?}");
+    };
+    assert_eq!(field_errors.len(), 2);
+    assert_eq!(
+        field_errors["meal_type"],
+        vec!["This field is required.".to_string()]
+    );
+    assert!(message.contains("from_date: Date has wrong format"));
+}
+
+#[test]
+fn test_long_error_body_is_truncated() {
+    let body = "x".repeat(2000);
+    let error = TandoorError::from_response(StatusCode::INTERNAL_SERVER_ERROR, None, &body);
+    assert!(error.message().len() < 600);
+    assert!(error.message().ends_with("..."));
+}
+
+#[test]
+fn test_nested_field_errors_are_flattened() {
+    let error = TandoorError::from_response(
+        StatusCode::BAD_REQUEST,
+        None,
+        r#"{
+            "steps": [{}, {"ingredients": [{}, {"amount": ["A valid number is required."]}]}],
+            "non_field_errors": ["Recipe name must be unique."]
+        }"#,
+    );
+
+    let field_errors = error.field_errors().unwrap();
+    assert_eq!(
+        field_errors.keys().collect::<Vec<_>>(),
+        vec!["non_field_errors", "steps[1].ingredients[1].amount"]
+    );
+    assert_eq!(
+        field_errors["steps[1].ingredients[1].amount"],
+        vec!["A valid number is required.".to_string()]
+    );
+}
+
+#[test]
+fn test_error_codes_and_retryability() {
+    let cases = [
+        (StatusCode::UNAUTHORIZED, "unauthorized", false),
+        (StatusCode::FORBIDDEN, "forbidden", false),
+        (StatusCode::NOT_FOUND, "not_found", false),
+        (StatusCode::BAD_REQUEST, "validation_error", false),
+        (StatusCode::TOO_MANY_REQUESTS, "rate_limited", true),
+        (StatusCode::SERVICE_UNAVAILABLE, "server_error", true),
+        (StatusCode::METHOD_NOT_ALLOWED, "unexpected_response", false),
+    ];
+    for (status, code, retryable) in cases {
+        let error = TandoorError::from_response(status, None, "");
+        assert_eq!(error.code(), code, "code for {status}");
+        assert_eq!(error.is_retryable(), retryable, "retryable for {status}");
+    }
+
+    let network = TandoorError::Network {
+        message: "connection refused".to_string(),
+    };
+    assert_eq!(network.code(), "network_error");
+    assert!(network.is_retryable());
+
+    let json = TandoorError::from_response(
+        StatusCode::BAD_REQUEST,
+        None,
+        r#"{"from_date": ["Date has wrong format."]}"#,
+    )
+    .to_json();
+    assert_eq!(json["code"], "validation_error");
+    assert_eq!(json["retryable"], false);
+    assert_eq!(json["status"], 400);
+    assert_eq!(
+        json["field_errors"]["from_date"][0],
+        "Date has wrong format."
+    );
+}
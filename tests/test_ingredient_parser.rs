@@ -0,0 +1,109 @@
+use mcp_tandoor::ingredient_parser::{normalize_unit, parse_line, parse_request};
+use pretty_assertions::assert_eq;
+
+#[test]
+fn test_parse_request_mixed_items() {
+    let items = parse_request("2 lbs chicken thighs, a dozen eggs, 500g flour");
+
+    assert_eq!(items.len(), 3);
+
+    assert_eq!(items[0].amount, Some(2.0));
+    assert_eq!(items[0].unit.as_deref(), Some("lb"));
+    assert_eq!(items[0].food, "chicken thighs");
+
+    assert_eq!(items[1].amount, Some(12.0));
+    assert_eq!(items[1].unit, None);
+    assert_eq!(items[1].food, "eggs");
+
+    assert_eq!(items[2].amount, Some(500.0));
+    assert_eq!(items[2].unit.as_deref(), Some("g"));
+    assert_eq!(items[2].food, "flour");
+}
+
+#[test]
+fn test_parse_line_fractions() {
+    let item = parse_line("1 1/2 cups of sugar").unwrap();
+    assert_eq!(item.amount, Some(1.5));
+    assert_eq!(item.unit.as_deref(), Some("cup"));
+    assert_eq!(item.food, "sugar");
+
+    let item = parse_line("½ tsp salt").unwrap();
+    assert_eq!(item.amount, Some(0.5));
+    assert_eq!(item.unit.as_deref(), Some("tsp"));
+
+    let item = parse_line("1½ kg potatoes").unwrap();
+    assert_eq!(item.amount, Some(1.5));
+    assert_eq!(item.unit.as_deref(), Some("kg"));
+    assert_eq!(item.food, "potatoes");
+
+    // Amounts must be positive
+    assert_eq!(parse_line("-1/2 cup milk"), None);
+    assert_eq!(parse_line("-2 eggs"), None);
+    assert_eq!(parse_line("0 g flour"), None);
+    assert_eq!(parse_line("- 1/2 cup milk").unwrap().amount, Some(0.5));
+}
+
+#[test]
+fn test_parse_line_notes() {
+    let item = parse_line("2 cloves garlic (minced)").unwrap();
+    assert_eq!(item.amount, Some(2.0));
+    assert_eq!(item.unit.as_deref(), Some("clove"));
+    assert_eq!(item.food, "garlic");
+    assert_eq!(item.note.as_deref(), Some("minced"));
+
+    let item = parse_line("1 can tomatoes - crushed").unwrap();
+    assert_eq!(item.food, "tomatoes");
+    assert_eq!(item.note.as_deref(), Some("crushed"));
+
+    // Characters whose lowercase form is longer must not shift the split
+    let item = parse_line("200 g İzmir köfte FOR the sauce").unwrap();
+    assert_eq!(item.food, "İzmir köfte");
+    assert_eq!(item.note.as_deref(), Some("FOR the sauce"));
+    let item = parse_line("İİİİİ for é").unwrap();
+    assert_eq!(item.food, "İİİİİ");
+    assert_eq!(item.note.as_deref(), Some("for é"));
+}
+
+#[test]
+fn test_parse_request_descriptor_attaches_to_previous_item() {
+    let items = parse_request("2 lbs chicken thighs, boneless, 1 onion");
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].note.as_deref(), Some("boneless"));
+    assert_eq!(items[1].food, "onion");
+}
+
+#[test]
+fn test_parse_request_splits_on_quantity_and() {
+    let items = parse_request("salt and pepper and 2 onions");
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].food, "salt and pepper");
+    assert_eq!(items[0].amount, None);
+    assert_eq!(items[1].food, "onions");
+    assert_eq!(items[1].amount, Some(2.0));
+}
+
+#[test]
+fn test_parse_request_number_words_and_multipliers() {
+    let items = parse_request("half a dozen bagels\na couple of lemons\n3x yogurt\n- milk");
+
+    assert_eq!(items.len(), 4);
+    assert_eq!(items[0].amount, Some(6.0));
+    assert_eq!(items[0].food, "bagels");
+    assert_eq!(items[1].amount, Some(2.0));
+    assert_eq!(items[1].food, "lemons");
+    assert_eq!(items[2].amount, Some(3.0));
+    assert_eq!(items[2].food, "yogurt");
+    assert_eq!(items[3].amount, None);
+    assert_eq!(items[3].food, "milk");
+}
+
+#[test]
+fn test_normalize_unit() {
+    assert_eq!(normalize_unit("Pounds"), Some("lb"));
+    assert_eq!(normalize_unit("T"), Some("tbsp"));
+    assert_eq!(normalize_unit("t"), Some("tsp"));
+    assert_eq!(normalize_unit("fl oz"), Some("fl oz"));
+    assert_eq!(normalize_unit("banana"), None);
+}
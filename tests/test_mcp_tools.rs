@@ -21,8 +21,7 @@ async fn test_mcp_server_initialization() {
 
     // If we have a shared token, use it instead of authenticating
     if let Ok(token) = std::env::var("TANDOOR_AUTH_TOKEN") {
-        let set_result = server.set_global_auth_token(token).await;
-        assert!(set_result.is_ok(), "Should set token successfully");
+        server.set_auth_token(token);
     } else {
         // Fallback to authentication (may fail due to rate limiting)
         let auth_result = server.authenticate(username, password).await;
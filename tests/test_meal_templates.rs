@@ -0,0 +1,153 @@
+use mcp_tandoor::meal_templates::{MealTemplateStore, TemplateMeal};
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+
+fn temp_template_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "mcp-tandoor-templates-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("data").join("meal-templates.json")
+}
+
+fn dinner(day_offset: u32, recipe: &str) -> TemplateMeal {
+    TemplateMeal {
+        day_offset,
+        meal_type_id: 3,
+        meal_type: "Dinner".to_string(),
+        recipe_id: Some(day_offset as i32 + 1),
+        recipe_name: Some(recipe.to_string()),
+        title: String::new(),
+        servings: 4,
+        note: String::new(),
+    }
+}
+
+#[test]
+fn test_meal_template_store_round_trip() {
+    let path = temp_template_path("round-trip");
+    let store = MealTemplateStore::new(&path);
+
+    assert_eq!(store.list().unwrap(), vec![]);
+    assert_eq!(store.get("weeknights").unwrap(), None);
+
+    store
+        .save(
+            "Weeknights",
+            7,
+            vec![dinner(1, "Tacos"), dinner(3, "Curry")],
+            false,
+        )
+        .unwrap();
+    store
+        .save("  Lunches ", 5, vec![dinner(0, "Soup")], false)
+        .unwrap();
+
+    // Names are trimmed, looked up ignoring case, and listed by name
+    let reopened = MealTemplateStore::new(&path);
+    let names: Vec<String> = reopened
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|template| template.name)
+        .collect();
+    assert_eq!(names, vec!["Lunches", "Weeknights"]);
+    let weeknights = reopened.get("WEEKNIGHTS").unwrap().unwrap();
+    assert_eq!(weeknights.days, 7);
+    assert_eq!(
+        weeknights.meals,
+        vec![dinner(1, "Tacos"), dinner(3, "Curry")]
+    );
+
+    // Saving under an existing name needs overwrite, and replaces that template only
+    assert_eq!(
+        reopened
+            .save("weeknights", 7, vec![dinner(1, "Tacos")], false)
+            .unwrap(),
+        None
+    );
+    assert_eq!(store.get("Weeknights").unwrap().unwrap().meals.len(), 2);
+    reopened
+        .save("weeknights", 7, vec![dinner(1, "Tacos")], true)
+        .unwrap();
+    assert_eq!(store.list().unwrap().len(), 2);
+    assert_eq!(store.get("Weeknights").unwrap().unwrap().meals.len(), 1);
+
+    assert!(store.remove("lunches").unwrap());
+    assert!(!store.remove("lunches").unwrap());
+    assert_eq!(store.list().unwrap().len(), 1);
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
+
+#[test]
+fn test_meal_template_store_concurrent_saves() {
+    let path = temp_template_path("concurrent");
+    let store = MealTemplateStore::new(&path);
+
+    // Clones share a lock, so no save overwrites another
+    let threads: Vec<_> = (0..16)
+        .map(|n| {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                store
+                    .save(&format!("Week {n}"), 7, vec![dinner(1, "Tacos")], false)
+                    .unwrap();
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(store.list().unwrap().len(), 16);
+
+    // Without overwrite, only one of several saves under the same name wins
+    let threads: Vec<_> = (0..16)
+        .map(|n| {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                store
+                    .save("Shared", 7, vec![dinner(n, "Tacos")], false)
+                    .unwrap()
+                    .is_some()
+            })
+        })
+        .collect();
+    let saved = threads
+        .into_iter()
+        .map(|thread| thread.join().unwrap())
+        .filter(|saved| *saved)
+        .count();
+    assert_eq!(saved, 1);
+    // No temporary files are left behind
+    let files = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+    assert_eq!(files, 1);
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
+
+#[test]
+fn test_meal_template_store_keeps_corrupt_file() {
+    let path = temp_template_path("corrupt");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "not json").unwrap();
+
+    let store = MealTemplateStore::new(&path);
+    assert!(store.list().is_err());
+    assert!(store.save("Weeknights", 7, vec![], true).is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not json");
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
+
+#[test]
+fn test_template_meal_dates() {
+    let start = chrono::NaiveDate::from_ymd_opt(2026, 11, 2).unwrap();
+    assert_eq!(
+        dinner(1, "Tacos").date_from(start),
+        chrono::NaiveDate::from_ymd_opt(2026, 11, 3)
+    );
+    assert_eq!(dinner(1, "Tacos").date_from(chrono::NaiveDate::MAX), None);
+}
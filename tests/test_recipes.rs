@@ -61,6 +61,7 @@ async fn test_create_and_retrieve_recipe() {
         servings: Some(4),
         working_time: 30,
         waiting_time: 15,
+        source_url: None,
         keywords: vec![mcp_tandoor::client::types::CreateKeywordRequest {
             name: "test".to_string(),
         }],
@@ -117,3 +118,22 @@ async fn test_get_recipe_not_found() {
 
     assert!(result.is_err(), "Should fail for non-existent recipe");
 }
+
+#[tokio::test]
+#[serial]
+async fn test_import_recipe_invalid_url() {
+    common::init_test_logging();
+    DockerEnvironment::ensure_running().expect("Docker environment not running");
+
+    let env = TestEnvironment::new()
+        .await
+        .expect("Failed to create test environment");
+
+    // A URL with no recipe on it should not produce a parsed recipe
+    let result = env
+        .client
+        .import_recipe_from_url("http://localhost:1/not-a-recipe")
+        .await;
+
+    assert!(result.is_err(), "Should fail for a URL without a recipe");
+}
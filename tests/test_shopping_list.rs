@@ -56,6 +56,8 @@ async fn test_add_to_shopping_list() {
         food: food_id,
         unit: None,
         amount: 2.0,
+        list_recipe: None,
+        ingredient: None,
     };
 
     let result = env.client.add_bulk_to_shopping_list(vec![request]).await;
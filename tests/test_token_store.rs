@@ -0,0 +1,134 @@
+use mcp_tandoor::client::TokenStore;
+use pretty_assertions::assert_eq;
+use std::path::PathBuf;
+
+fn temp_cache_path(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("mcp-tandoor-test-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("state").join("tokens.json")
+}
+
+#[test]
+fn test_token_store_round_trip() {
+    let path = temp_cache_path("round-trip");
+    let store = TokenStore::new(&path);
+
+    assert_eq!(store.load("http://localhost:8080", "admin"), None);
+
+    store
+        .save("http://localhost:8080/", "admin", "tda_first")
+        .unwrap();
+    store
+        .save("http://localhost:8080", "other", "tda_other")
+        .unwrap();
+    store
+        .save("https://recipes.example.com", "admin", "tda_remote")
+        .unwrap();
+
+    // Trailing slashes don't change the key
+    assert_eq!(
+        store.load("http://localhost:8080", "admin").as_deref(),
+        Some("tda_first")
+    );
+    assert_eq!(
+        store.load("http://localhost:8080", "other").as_deref(),
+        Some("tda_other")
+    );
+    assert_eq!(
+        store
+            .load("https://recipes.example.com", "admin")
+            .as_deref(),
+        Some("tda_remote")
+    );
+
+    // A new token replaces the old one, and a fresh store reads it from disk
+    store
+        .save("http://localhost:8080", "admin", "tda_second")
+        .unwrap();
+    let reopened = TokenStore::new(&path);
+    assert_eq!(
+        reopened.load("http://localhost:8080", "admin").as_deref(),
+        Some("tda_second")
+    );
+
+    reopened.remove("http://localhost:8080", "admin").unwrap();
+    assert_eq!(store.load("http://localhost:8080", "admin"), None);
+    assert_eq!(
+        store.load("http://localhost:8080", "other").as_deref(),
+        Some("tda_other")
+    );
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
+
+#[test]
+fn test_token_store_concurrent_saves() {
+    let path = temp_cache_path("concurrent");
+    let store = TokenStore::new(&path);
+
+    // Clones share a lock, so no save overwrites another
+    let threads: Vec<_> = (0..16)
+        .map(|n| {
+            let store = store.clone();
+            std::thread::spawn(move || {
+                store
+                    .save("http://localhost:8080", &format!("user{n}"), "tda_token")
+                    .unwrap();
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    for n in 0..16 {
+        assert_eq!(
+            store
+                .load("http://localhost:8080", &format!("user{n}"))
+                .as_deref(),
+            Some("tda_token")
+        );
+    }
+    // No temporary files are left behind
+    let files = std::fs::read_dir(path.parent().unwrap()).unwrap().count();
+    assert_eq!(files, 1);
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
+
+#[test]
+fn test_token_store_ignores_corrupt_file() {
+    let path = temp_cache_path("corrupt");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "not json").unwrap();
+
+    let store = TokenStore::new(&path);
+    assert_eq!(store.load("http://localhost:8080", "admin"), None);
+
+    store
+        .save("http://localhost:8080", "admin", "tda_new")
+        .unwrap();
+    assert_eq!(
+        store.load("http://localhost:8080", "admin").as_deref(),
+        Some("tda_new")
+    );
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_token_store_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let path = temp_cache_path("permissions");
+    let store = TokenStore::new(&path);
+    store
+        .save("http://localhost:8080", "admin", "tda_secret")
+        .unwrap();
+
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap().parent().unwrap());
+}
@@ -0,0 +1,89 @@
+use mcp_tandoor::client::types::Unit;
+use mcp_tandoor::units::{round_amount, Dimension, KitchenUnit, Measure, System};
+use pretty_assertions::assert_eq;
+
+fn kitchen(name: &str) -> &'static KitchenUnit {
+    KitchenUnit::find(name).unwrap_or_else(|| panic!("unknown unit {name}"))
+}
+
+fn tandoor_unit(name: &str, base_unit: Option<&str>, type_: Option<&str>) -> Unit {
+    Unit {
+        id: 1,
+        name: name.to_string(),
+        plural_name: None,
+        description: None,
+        base_unit: base_unit.map(str::to_string),
+        type_: type_.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_find_accepts_any_spelling() {
+    assert_eq!(kitchen("Tablespoons").name, "tbsp");
+    assert_eq!(kitchen("fluid_ounce").name, "fl oz");
+    assert_eq!(kitchen("pounds").dimension, Dimension::Mass);
+    assert_eq!(kitchen("l").system, System::Metric);
+    assert!(KitchenUnit::find("clove").is_none());
+    assert!(KitchenUnit::find("handful").is_none());
+}
+
+#[test]
+fn test_for_unit_prefers_base_unit_and_checks_type() {
+    let custom = tandoor_unit("Becher", Some("cup"), None);
+    assert_eq!(KitchenUnit::for_unit(&custom).unwrap().name, "cup");
+
+    let by_name = tandoor_unit("gram", None, Some("WEIGHT"));
+    assert_eq!(KitchenUnit::for_unit(&by_name).unwrap().name, "g");
+
+    let mistyped = tandoor_unit("cup", None, Some("weight"));
+    assert_eq!(KitchenUnit::for_unit(&mistyped), None);
+}
+
+#[test]
+fn test_normalized_moves_to_handier_units() {
+    let tbsp = Measure::new(0.1875, kitchen("cup")).normalized();
+    assert_eq!((tbsp.amount, tbsp.unit.name), (3.0, "tbsp"));
+
+    let cups = Measure::new(8.0, kitchen("tbsp")).normalized();
+    assert_eq!((cups.amount, cups.unit.name), (0.5, "cup"));
+
+    let kg = Measure::new(1250.0, kitchen("g")).normalized();
+    assert_eq!((kg.amount, kg.unit.name), (1.25, "kg"));
+
+    let lb = Measure::new(24.0, kitchen("oz")).normalized();
+    assert_eq!((lb.amount, lb.unit.name), (1.5, "lb"));
+}
+
+#[test]
+fn test_normalized_avoids_large_rounding_errors() {
+    // 5 tbsp is 0.3125 cup, which would round to a quarter cup
+    let tbsp = Measure::new(5.0, kitchen("tbsp")).normalized();
+    assert_eq!((tbsp.amount, tbsp.unit.name), (5.0, "tbsp"));
+
+    let third = Measure::new(0.34, kitchen("cup")).normalized();
+    assert_eq!(third.unit.name, "cup");
+    assert!((third.amount - 1.0 / 3.0).abs() < 1e-9);
+
+    let grams = Measure::new(123.4567, kitchen("g")).normalized();
+    assert_eq!((grams.amount, grams.unit.name), (125.0, "g"));
+}
+
+#[test]
+fn test_plus_converts_between_systems() {
+    let flour = Measure::new(500.0, kitchen("g"))
+        .plus(Measure::new(1.0, kitchen("lb")))
+        .unwrap();
+    assert_eq!(flour.unit.name, "g");
+    assert_eq!(round_amount(flour.amount), 953.59);
+
+    let milk = Measure::new(1.0, kitchen("cup"))
+        .plus(Measure::new(250.0, kitchen("ml")))
+        .unwrap()
+        .normalized();
+    assert_eq!((milk.amount, milk.unit.name), (2.0, "cup"));
+
+    assert_eq!(
+        Measure::new(1.0, kitchen("cup")).plus(Measure::new(100.0, kitchen("g"))),
+        None
+    );
+}